@@ -0,0 +1,439 @@
+pub use crate::tokenizer::Span;
+use std::rc::Rc;
+
+/// A whole source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Using(Using),
+    Data(Data),
+    Object(Object),
+    Enum(Enum),
+    Func(Func),
+    Trait(Trait),
+    Impl(Impl),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ident {
+    pub name: String,
+    pub location: Span,
+}
+
+/// `A::B::c`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub segments: Vec<Ident>,
+    pub location: Span,
+}
+
+impl Path {
+    /// The last segment of the path, the name of the thing being referred to
+    pub fn name(&self) -> &Ident {
+        self.segments.last().expect("Paths always have a segment")
+    }
+}
+
+/// `using A::B::c;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Using {
+    pub path: Path,
+    pub location: Span,
+}
+
+/// `data Name<T> { field: Type; }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data {
+    pub public: bool,
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    /// `<T, U>`, the types the fields can be generic over
+    pub type_params: Vec<Ident>,
+    pub fields: Vec<Field>,
+    pub location: Span,
+}
+
+/// `object Name { field: Type; func method (ref Name) () { body } }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    pub public: bool,
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    pub fields: Vec<Field>,
+    /// Functions called as `Name::method`
+    pub methods: Vec<Func>,
+    pub location: Span,
+}
+
+impl Object {
+    pub fn method(&self, name: &str) -> Option<&Func> {
+        self.methods.iter().find(|method| method.name.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: Ident,
+    pub ty: Type,
+    pub location: Span,
+}
+
+/// `enum Name<T> { A, B(Type), C { field: Type; } }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub public: bool,
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    /// `<T, U>`, the types the payloads can be generic over
+    pub type_params: Vec<Ident>,
+    pub variants: Vec<Variant>,
+    pub location: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: Ident,
+    pub kind: VariantKind,
+    pub location: Span,
+}
+
+/// The payload a variant carries
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantKind {
+    /// `Name`
+    Unit,
+    /// `Name(Type, Type)`
+    Tuple(Vec<Type>),
+    /// `Name { field: Type; }`, the payload is a single record
+    Record(Vec<Field>),
+}
+
+impl VariantKind {
+    /// The number of arguments that construct the variant, `Shape::Circle 1.0`
+    pub fn arity(&self) -> usize {
+        match self {
+            VariantKind::Unit => 0,
+            VariantKind::Tuple(types) => types.len(),
+            VariantKind::Record(_) => 1,
+        }
+    }
+}
+
+/// `func name<T> (Params) (Returns) { body }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Func {
+    pub public: bool,
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    /// `<T, U: Add>`, the types the signature can be generic over, they are inferred from the
+    /// arguments of each call
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<Param>,
+    pub returns: Vec<Type>,
+    pub body: Block,
+    pub location: Span,
+}
+
+/// `T` or `T: Add + Display`, the traits every type given for `T` must implement
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParam {
+    pub name: Ident,
+    pub bounds: Vec<Path>,
+}
+
+/// `trait Name { func method (Self) (Returns); }`, `Self` stands for the implementing type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trait {
+    pub public: bool,
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    pub methods: Vec<TraitMethod>,
+    pub location: Span,
+}
+
+impl Trait {
+    pub fn method(&self, name: &str) -> Option<&TraitMethod> {
+        self.methods.iter().find(|method| method.name.name == name)
+    }
+}
+
+/// `func method (Self, Type) (Returns);`, a method every implementation must provide
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitMethod {
+    /// The `///` comments before the declaration, one line each
+    pub doc: Option<String>,
+    pub name: Ident,
+    pub params: Vec<Param>,
+    pub returns: Vec<Type>,
+    pub location: Span,
+}
+
+/// `impl Trait for Type { func method (Type) (Returns) { body } }`, the methods are called
+/// through the trait, `Trait::method value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impl {
+    pub r#trait: Path,
+    pub ty: Type,
+    pub methods: Vec<Func>,
+    pub location: Span,
+}
+
+impl Impl {
+    pub fn method(&self, name: &str) -> Option<&Func> {
+        self.methods.iter().find(|method| method.name.name == name)
+    }
+}
+
+/// `Type`, `ref Type` or `mut Type`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub mode: ParamMode,
+    pub ty: Type,
+    pub location: Span,
+}
+
+/// How an argument is passed to a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamMode {
+    /// `Type`, the function receives its own copy of any object
+    Value,
+    /// `ref Type`, the function shares the caller's object but can not change it
+    Ref,
+    /// `mut Type`, the function shares the caller's object and its changes are seen by the
+    /// caller
+    Mut,
+}
+
+impl ParamMode {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            ParamMode::Value => "",
+            ParamMode::Ref => "ref",
+            ParamMode::Mut => "mut",
+        }
+    }
+}
+
+/// `Name` or `Name<Arg, Arg>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Type {
+    pub path: Path,
+    pub args: Vec<Type>,
+    pub location: Span,
+}
+
+/// `{ statement; statement; tail }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub statements: Vec<Pipeline>,
+    /// The final pipeline if it was not terminated with a `;`, its value is the value of the block
+    pub tail: Option<Box<Pipeline>>,
+    pub location: Span,
+}
+
+/// A chain of stages, `value |> stage |= name |? ... \?`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+    pub location: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub kind: StageKind,
+    pub location: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageKind {
+    /// An expression that replaces the current pipe value
+    Value(Expr),
+    /// `|> expr`
+    Next(Expr),
+    /// `|= name`
+    Set(Ident),
+    /// `|? pattern -> pipeline ... \?`
+    Match(Vec<MatchArm>),
+    /// `|! handler`
+    Error(Expr),
+    /// `|.`, returns the pipe value from the innermost function or `func { ... }` it is written
+    /// in, even from inside a `|?` arm
+    Return,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Pipeline,
+    pub location: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub location: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternKind {
+    /// `_`
+    Wildcard,
+    /// `name`
+    Binding(Ident),
+    /// `1`, `true`, `"str"`
+    Literal(Literal),
+    /// `< 1`, `>= n`, `!= "text"`
+    Compare(BinaryOp, Expr),
+    /// `{ field: pattern }`
+    Record(Vec<(Ident, Pattern)>),
+    /// `(pattern, pattern)`, matches the tuple a function with several parameters receives
+    Tuple(Vec<Pattern>),
+    /// `Enum::Variant pattern pattern`, the patterns match the payload. Without any patterns
+    /// every payload matches
+    Variant(Path, Vec<Pattern>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Unit,
+    /// An integer with the type named by its suffix, if it has one
+    Integer(i128, Option<&'static str>),
+    Float(f64, Option<&'static str>),
+    Bool(bool),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub location: Span,
+}
+
+impl Expr {
+    /// Whether the expression references the current pipe value with `.`
+    pub fn uses_current(&self) -> bool {
+        match &self.kind {
+            ExprKind::Current | ExprKind::Update(..) | ExprKind::Section(..) => true,
+            ExprKind::Call(_, args) => args.iter().any(Expr::uses_current),
+            ExprKind::Binary(_, lhs, rhs) => lhs.uses_current() || rhs.uses_current(),
+            ExprKind::Not(expr) | ExprKind::Field(expr, _) => expr.uses_current(),
+            ExprKind::Range { start, end, .. } => start.uses_current() || end.uses_current(),
+            ExprKind::Record(fields) => fields.iter().any(|(_, value)| value.uses_current()),
+            ExprKind::Interpolation(parts) => parts.iter().any(|part| match part {
+                InterpolationPart::Expr(expr) => expr.uses_current(),
+                InterpolationPart::Text(_) => false,
+            }),
+            ExprKind::Group(pipeline) => matches!(
+                pipeline.stages.first(),
+                Some(Stage { kind: StageKind::Value(expr), .. }) if expr.uses_current()
+            ),
+            // The body of a closure has its own pipe value
+            ExprKind::Literal(_) | ExprKind::Path(_) | ExprKind::Closure(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    Literal(Literal),
+    /// `.`, the current pipe value
+    Current,
+    /// A variable or function, `n`, `Std::CLI::parse_args`
+    Path(Path),
+    /// `function arg arg`
+    Call(Path, Vec<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `!expr`
+    Not(Box<Expr>),
+    /// `|> + n`, an operator with the current pipe value as its left operand
+    Section(BinaryOp, Box<Expr>),
+    /// `start..end` or `start..=end`, a range of integers
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+    /// `{ field: value }`
+    Record(Vec<(Ident, Expr)>),
+    /// `.{field} value`, the current pipe value with `field` replaced by `value`. The value is the
+    /// rest of the expression, `.{count} .count + 1` sets `count` to `.count + 1`
+    Update(Ident, Box<Expr>),
+    /// `expr.field` or `.field`, a field of a record or of the current pipe value
+    Field(Box<Expr>, Ident),
+    /// `( pipeline )`
+    Group(Box<Pipeline>),
+    /// `"text #{expr} text"`, the values are formatted and joined into a string
+    Interpolation(Vec<InterpolationPart>),
+    /// `func { body }`, an anonymous function whose arguments are its initial pipe value. The
+    /// body is shared with the function values created from it
+    Closure(Rc<Block>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpolationPart {
+    Text(String),
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    LessThan,
+    GreaterThan,
+    LessEqual,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    Plus,
+    Minus,
+    Times,
+    Divide,
+    Modulo,
+    Power,
+}
+
+impl BinaryOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::LessThan => "<",
+            BinaryOp::GreaterThan => ">",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::Plus => "+",
+            BinaryOp::Minus => "-",
+            BinaryOp::Times => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Power => "^",
+        }
+    }
+
+    /// Whether the operator produces a `Bool` rather than a value like its operands
+    pub fn is_boolean(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Or
+                | BinaryOp::And
+                | BinaryOp::LessThan
+                | BinaryOp::GreaterThan
+                | BinaryOp::LessEqual
+                | BinaryOp::GreaterEqual
+                | BinaryOp::Equal
+                | BinaryOp::NotEqual
+        )
+    }
+}
@@ -0,0 +1,328 @@
+//! Exhaustiveness and reachability of `|?` arms, following the usefulness algorithm from
+//! Maranget's "Warnings for pattern matching"
+
+use super::{segments, Checker, Type};
+use crate::{
+    ast::{Literal, MatchArm, Pattern, PatternKind, Span},
+    error::CompileWarningEnum,
+    resolver::Callee,
+};
+
+/// A pattern reduced to what matters for coverage
+#[derive(Debug, Clone)]
+enum Space {
+    /// Matches every value, `_` or a binding
+    Any,
+    /// Matches some values of an unknown set, such as `< 1`, so it never covers anything
+    Opaque,
+    Constructor(Constructor, Vec<Space>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Constructor {
+    Unit,
+    Bool(bool),
+    /// A record with its field names in order
+    Record(Vec<String>),
+    /// A tuple with the given number of values
+    Tuple(usize),
+    /// `Enum::Variant` with the types of its payload
+    Variant {
+        name: String,
+        args: Vec<Type>,
+    },
+    /// A literal of a type with infinitely many values
+    Literal(String),
+}
+
+impl Constructor {
+    fn arity(&self) -> usize {
+        match self {
+            Constructor::Record(fields) => fields.len(),
+            Constructor::Tuple(len) => *len,
+            Constructor::Variant { args, .. } => args.len(),
+            _ => 0,
+        }
+    }
+
+    /// Formats the constructor applied to `args`, used to describe an uncovered value
+    fn describe(&self, args: &[String]) -> String {
+        match self {
+            Constructor::Unit => "()".into(),
+            Constructor::Bool(value) => value.to_string(),
+            Constructor::Record(fields) => {
+                let fields = fields
+                    .iter()
+                    .zip(args)
+                    .map(|(name, arg)| format!("{name}: {arg}"))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", fields.join(", "))
+            }
+            Constructor::Tuple(_) => format!("({})", args.join(", ")),
+            Constructor::Variant { name, .. } => std::iter::once(name.clone())
+                .chain(args.iter().map(|arg| {
+                    // Payloads that are variants with payloads of their own need parentheses
+                    if arg.contains(' ') {
+                        format!("({arg})")
+                    } else {
+                        arg.clone()
+                    }
+                }))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Constructor::Literal(name) => name.clone(),
+        }
+    }
+}
+
+type Row = Vec<Space>;
+
+impl Checker<'_> {
+    /// Warns about arms that can never be reached and values no arm matches
+    pub(super) fn exhaustiveness(&mut self, arms: &[MatchArm], ty: &Type, location: Span) {
+        let mut rows: Vec<Row> = Vec::new();
+        for arm in arms {
+            let row = vec![self.space(&arm.pattern, ty)];
+            if self.useful(&rows, &row, std::slice::from_ref(ty)).is_none() {
+                self.warning(arm.location, CompileWarningEnum::UnreachableArm);
+            }
+            rows.push(row);
+        }
+
+        if let Some(witness) = self.useful(&rows, &[Space::Any], std::slice::from_ref(ty)) {
+            self.warning(
+                location,
+                CompileWarningEnum::NonExhaustive(witness.join(", ")),
+            );
+        }
+    }
+
+    fn space(&self, pattern: &Pattern, ty: &Type) -> Space {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Binding(_) => Space::Any,
+            PatternKind::Compare(..) => Space::Opaque,
+            PatternKind::Literal(literal) => {
+                let constructor = match literal {
+                    Literal::Unit => Constructor::Unit,
+                    Literal::Bool(value) => Constructor::Bool(*value),
+                    Literal::Integer(value, _) => Constructor::Literal(value.to_string()),
+                    Literal::Float(value, _) => Constructor::Literal(value.to_string()),
+                    Literal::String(value) => Constructor::Literal(format!("{value:?}")),
+                };
+                Space::Constructor(constructor, Vec::new())
+            }
+            PatternKind::Record(patterns) => {
+                let Some(fields) = self.fields(ty) else {
+                    return Space::Opaque;
+                };
+                let args = fields
+                    .iter()
+                    .map(|(name, field_ty)| {
+                        patterns
+                            .iter()
+                            .find(|(field, _)| &field.name == name)
+                            .map_or(Space::Any, |(_, pattern)| self.space(pattern, field_ty))
+                    })
+                    .collect();
+                Space::Constructor(Constructor::Record(fields.into_keys().collect()), args)
+            }
+            PatternKind::Tuple(patterns) => match ty.owned() {
+                Type::Tuple(types) if types.len() == patterns.len() => Space::Constructor(
+                    Constructor::Tuple(types.len()),
+                    patterns
+                        .iter()
+                        .zip(types)
+                        .map(|(pattern, ty)| self.space(pattern, ty))
+                        .collect(),
+                ),
+                _ => Space::Opaque,
+            },
+            PatternKind::Variant(path, patterns) => {
+                let Some(Callee::Variant {
+                    module,
+                    r#enum,
+                    variant,
+                }) = self.program.lookup(self.module, &segments(path))
+                else {
+                    return Space::Opaque;
+                };
+                let (enum_path, args) = self.variant(module, r#enum, variant);
+                let Type::Named(path, type_args) = ty else {
+                    return Space::Opaque;
+                };
+                if *path != enum_path {
+                    return Space::Opaque;
+                }
+                let bindings = self.bindings(path, type_args);
+                let args: Vec<_> = args.iter().map(|arg| arg.substitute(&bindings)).collect();
+                let spaces = match patterns.len() {
+                    0 => vec![Space::Any; args.len()],
+                    len if len == args.len() => patterns
+                        .iter()
+                        .zip(&args)
+                        .map(|(pattern, ty)| self.space(pattern, ty))
+                        .collect(),
+                    _ => return Space::Opaque,
+                };
+                let name = format!("{}::{}", r#enum.name.name, variant.name.name);
+                Space::Constructor(Constructor::Variant { name, args }, spaces)
+            }
+        }
+    }
+
+    /// Every constructor of `ty` with the types of its arguments, [None] if there are too many
+    /// to list
+    fn constructors(&self, ty: &Type) -> Option<Vec<(Constructor, Vec<Type>)>> {
+        match ty {
+            Type::Unit => Some(vec![(Constructor::Unit, Vec::new())]),
+            Type::Bool => Some(vec![
+                (Constructor::Bool(true), Vec::new()),
+                (Constructor::Bool(false), Vec::new()),
+            ]),
+            Type::Tuple(types) => Some(vec![(Constructor::Tuple(types.len()), types.clone())]),
+            Type::Named(path, type_args) if self.enums.contains_key(path) => {
+                let bindings = self.bindings(path, type_args);
+                Some(
+                    self.enums[path]
+                        .iter()
+                        .map(|(variant, args)| {
+                            let name = format!("{}::{variant}", path[path.len() - 1]);
+                            let args: Vec<_> =
+                                args.iter().map(|arg| arg.substitute(&bindings)).collect();
+                            let constructor = Constructor::Variant {
+                                name,
+                                args: args.clone(),
+                            };
+                            (constructor, args)
+                        })
+                        .collect(),
+                )
+            }
+            _ => {
+                let fields = self.fields(ty)?;
+                let names = fields.keys().cloned().collect();
+                Some(vec![(
+                    Constructor::Record(names),
+                    fields.into_values().collect(),
+                )])
+            }
+        }
+    }
+
+    /// Whether a value matched by `row` is not matched by any of `rows`, returning a
+    /// description of such a value for each column
+    fn useful(&self, rows: &[Row], row: &[Space], types: &[Type]) -> Option<Vec<String>> {
+        let Some((first, rest)) = row.split_first() else {
+            return rows.is_empty().then(Vec::new);
+        };
+        // Opaque patterns may fail to match any given value, so they cover nothing
+        let rows = rows
+            .iter()
+            .filter(|row| !matches!(row[0], Space::Opaque))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        match first {
+            Space::Constructor(constructor, args) => {
+                let arg_types = self.arg_types(constructor, &types[0]);
+                let specialized = specialize(&rows, constructor);
+                let row = args.iter().chain(rest).cloned().collect::<Vec<_>>();
+                let types = arg_types
+                    .into_iter()
+                    .chain(types[1..].to_vec())
+                    .collect::<Vec<_>>();
+                let witness = self.useful(&specialized, &row, &types)?;
+                Some(rebuild(constructor, witness))
+            }
+            Space::Any | Space::Opaque => {
+                let used = rows
+                    .iter()
+                    .filter_map(|row| match &row[0] {
+                        Space::Constructor(constructor, _) => Some(constructor.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                let all = self.constructors(&types[0]);
+                if let Some(all) = all.as_ref().filter(|all| {
+                    all.iter()
+                        .all(|(constructor, _)| used.contains(constructor))
+                }) {
+                    return all.iter().find_map(|(constructor, arg_types)| {
+                        let specialized = specialize(&rows, constructor);
+                        let row = std::iter::repeat_n(Space::Any, constructor.arity())
+                            .chain(rest.iter().cloned())
+                            .collect::<Vec<_>>();
+                        let types = arg_types
+                            .iter()
+                            .chain(&types[1..])
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        let witness = self.useful(&specialized, &row, &types)?;
+                        Some(rebuild(constructor, witness))
+                    });
+                }
+
+                let defaults = rows
+                    .iter()
+                    .filter(|row| matches!(row[0], Space::Any))
+                    .map(|row| row[1..].to_vec())
+                    .collect::<Vec<_>>();
+                let mut witness = self.useful(&defaults, rest, &types[1..])?;
+                let missing = match all
+                    .into_iter()
+                    .flatten()
+                    .find(|(constructor, _)| !used.contains(constructor))
+                {
+                    Some((constructor, _)) if !used.is_empty() => {
+                        let args = vec!["_".to_string(); constructor.arity()];
+                        constructor.describe(&args)
+                    }
+                    _ => "_".into(),
+                };
+                witness.insert(0, missing);
+                Some(witness)
+            }
+        }
+    }
+
+    fn arg_types(&self, constructor: &Constructor, ty: &Type) -> Vec<Type> {
+        match constructor {
+            Constructor::Record(names) => {
+                let fields = self.fields(ty).unwrap_or_default();
+                names
+                    .iter()
+                    .map(|name| fields.get(name).cloned().unwrap_or(Type::Unknown))
+                    .collect()
+            }
+            Constructor::Variant { args, .. } => args.clone(),
+            Constructor::Tuple(len) => match ty.owned() {
+                Type::Tuple(types) => types.clone(),
+                _ => vec![Type::Unknown; *len],
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The rows that match values built with `constructor`, with its arguments expanded into columns
+fn specialize(rows: &[Row], constructor: &Constructor) -> Vec<Row> {
+    rows.iter()
+        .filter_map(|row| {
+            let args = match &row[0] {
+                Space::Constructor(other, args) if other == constructor => args.clone(),
+                Space::Constructor(..) | Space::Opaque => return None,
+                Space::Any => vec![Space::Any; constructor.arity()],
+            };
+            Some(args.into_iter().chain(row[1..].iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// Collapses the witnesses for the arguments of `constructor` back into a single column
+fn rebuild(constructor: &Constructor, mut witness: Vec<String>) -> Vec<String> {
+    let rest = witness.split_off(constructor.arity());
+    std::iter::once(constructor.describe(&witness))
+        .chain(rest)
+        .collect()
+}
@@ -0,0 +1,72 @@
+use super::Type;
+use crate::ast::Span;
+
+/// The integer literals without a suffix, which take the type of whatever they are used as.
+/// Literals that are combined with each other, such as the operands of `1 + 2`, share a
+/// variable so they end up with the same type
+#[derive(Default)]
+pub(super) struct Literals {
+    /// For each variable, the variable it was merged into or the type it was used as
+    variables: Vec<Variable>,
+    /// Every literal with the module it is in, its location, its value and its variable
+    literals: Vec<(usize, Span, i128, usize)>,
+}
+
+enum Variable {
+    /// Not used as a particular type yet
+    Free,
+    Merged(usize),
+    /// Used as an integer or float type, or as a float literal
+    Bound(Type),
+}
+
+impl Literals {
+    /// A new variable for the literal `value` at `location`
+    pub fn add(&mut self, module: usize, location: Span, value: i128) -> usize {
+        let variable = self.variables.len();
+        self.variables.push(Variable::Free);
+        self.literals.push((module, location, value, variable));
+        variable
+    }
+
+    fn root(&self, mut variable: usize) -> usize {
+        while let Variable::Merged(next) = self.variables[variable] {
+            variable = next;
+        }
+        variable
+    }
+
+    /// Records that the literals of `variable` are used as `ty`. The first type a literal is
+    /// used as is the one it gets
+    pub fn bind(&mut self, variable: usize, ty: &Type) {
+        let root = self.root(variable);
+        if let Variable::Free = self.variables[root] {
+            self.variables[root] = Variable::Bound(ty.clone());
+        }
+    }
+
+    /// Records that the literals of `lhs` and `rhs` are used together and have the same type
+    pub fn merge(&mut self, lhs: usize, rhs: usize) {
+        let (lhs, rhs) = (self.root(lhs), self.root(rhs));
+        match (&self.variables[lhs], &self.variables[rhs]) {
+            _ if lhs == rhs => {}
+            (Variable::Free, _) => self.variables[lhs] = Variable::Merged(rhs),
+            (_, Variable::Free) => self.variables[rhs] = Variable::Merged(lhs),
+            _ => {}
+        }
+    }
+
+    /// Every literal with the module it is in, its location, its value and the type it is used
+    /// as, literals that are only ever used as integers of any type are `Int32`
+    pub fn types(&self) -> impl Iterator<Item = (usize, Span, i128, Type)> + '_ {
+        self.literals
+            .iter()
+            .map(|(module, location, value, variable)| {
+                let ty = match &self.variables[self.root(*variable)] {
+                    Variable::Bound(ty) => ty.clone(),
+                    _ => Type::Integer("Int32"),
+                };
+                (*module, *location, *value, ty)
+            })
+    }
+}
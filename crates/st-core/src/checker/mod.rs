@@ -0,0 +1,1982 @@
+mod exhaustive;
+mod literals;
+#[cfg(test)]
+mod tests;
+mod types;
+
+pub use types::Type;
+
+use literals::Literals;
+use types::GENERIC_TYPES;
+
+use crate::{
+    ast::{self, *},
+    error::{
+        CompileError, CompileErrorEnum, CompileWarning, CompileWarningEnum, ProjectError,
+        ProjectWarning,
+    },
+    interpreter::Value,
+    resolver::{impl_method_name, method_name, Callee, Program, TypeRef},
+};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+/// The variables visible at a point in a function, innermost scope last
+type Scope = Vec<HashMap<String, Type>>;
+
+/// The parameter and return types of a function
+#[derive(Debug, Clone)]
+struct Signature {
+    /// [None] for builtins such as `println` that accept any arguments
+    params: Option<Vec<Type>>,
+    /// How each parameter is passed, empty if every argument is passed by value
+    modes: Vec<ParamMode>,
+    returns: Type,
+    /// The type parameters used by `params` and `returns`, which are inferred at each call
+    type_params: Vec<String>,
+    /// The traits the types inferred for `type_params` must implement, by parameter name and
+    /// the full path of the trait
+    bounds: Vec<(String, Vec<String>)>,
+}
+
+/// Checks the types flowing through every function of `program` against the declared
+/// signatures and record fields, returning warnings about `|?` arms that are not exhaustive or
+/// can not be reached. The integer literals without a suffix are given the type they are used
+/// as in `program`, so the interpreter can check their arithmetic for overflow
+pub fn check(program: &mut Program) -> Result<Vec<ProjectWarning>, ProjectError> {
+    let mut checker = Checker {
+        program,
+        literals: RefCell::default(),
+        records: std_records(),
+        traits: builtin_traits(),
+        implementations: HashSet::new(),
+        bounds: HashMap::new(),
+        objects: HashSet::new(),
+        generics: HashMap::new(),
+        enums: HashMap::new(),
+        signatures: HashMap::new(),
+        module: 0,
+        type_params: Vec::new(),
+        returns: Type::Unit,
+        returns_location: None,
+        returned: None,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    checker.declarations();
+    for (index, module) in program.modules().iter().enumerate() {
+        checker.module = index;
+        for item in &module.ast.items {
+            match item {
+                Item::Func(func) => checker.func(&func.name.name, func),
+                Item::Object(object) => {
+                    for method in &object.methods {
+                        checker.func(&method_name(object, method), method);
+                    }
+                }
+                Item::Impl(implementation) => {
+                    for method in &implementation.methods {
+                        checker.func(&impl_method_name(implementation, method), method);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut types = HashMap::new();
+    for (module, location, value, ty) in checker.literals.take().types() {
+        let Type::Integer(ty) = ty else {
+            continue;
+        };
+        if Value::integer(value, Some(ty)).is_some() {
+            types.insert((module, location), ty);
+        } else {
+            checker.module = module;
+            checker.error(
+                location,
+                CompileErrorEnum::NumberOutOfRange {
+                    literal: value.to_string(),
+                    ty,
+                },
+            );
+        }
+    }
+    let Checker {
+        errors, warnings, ..
+    } = checker;
+    program.set_literal_types(types);
+
+    match ProjectError::combine(errors) {
+        Some(error) => Err(error),
+        None => Ok(warnings),
+    }
+}
+
+/// The record types provided by the standard library
+fn std_records() -> HashMap<Vec<String>, BTreeMap<String, Type>> {
+    let path = |path: &str| path.split("::").map(String::from).collect::<Vec<_>>();
+    HashMap::from([
+        (
+            path("Std::Build::ProjectDescriptor"),
+            BTreeMap::from([
+                ("name".into(), Type::String),
+                (
+                    "bin".into(),
+                    Type::Named(path("Std::Build::Binary"), Vec::new()),
+                ),
+            ]),
+        ),
+        (
+            path("Std::Build::Binary"),
+            BTreeMap::from([("name".into(), Type::String), ("src".into(), Type::String)]),
+        ),
+    ])
+}
+
+/// The methods of the traits provided by the language, `Self` is the implementing type
+fn builtin_traits() -> HashMap<Vec<String>, Vec<(String, Signature)>> {
+    let this = || Type::Param("Self".into());
+    let method = |r#trait: &str, name: &str, params, returns| {
+        let signature = Signature {
+            params: Some(params),
+            modes: Vec::new(),
+            returns,
+            type_params: vec!["Self".into()],
+            bounds: vec![("Self".into(), vec![r#trait.to_string()])],
+        };
+        (
+            vec![r#trait.to_string()],
+            vec![(name.to_string(), signature)],
+        )
+    };
+    HashMap::from([
+        method("Add", "add", vec![this(), this()], this()),
+        method(
+            "Compare",
+            "compare",
+            vec![this(), this()],
+            Type::Integer("Int32"),
+        ),
+        method("Display", "display", vec![this()], Type::String),
+    ])
+}
+
+/// The signature of a function provided by the interpreter
+fn builtin_signature(path: &[String]) -> Option<Signature> {
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    let (params, returns) = match path.as_slice() {
+        ["print"] | ["println"] => (None, Type::Unit),
+        ["read_line"] => (Some(Vec::new()), Type::String),
+        ["read_int"] => (Some(Vec::new()), Type::Integer("Int32")),
+        ["panic"] => (None, Type::Never),
+        ["fail"] => (None, Type::Result(Box::new(Type::Never))),
+        ["Std", "Build", "ProjectDescriptor", "init"] => (
+            Some(Vec::new()),
+            Type::Named(
+                vec!["Std".into(), "Build".into(), "ProjectDescriptor".into()],
+                Vec::new(),
+            ),
+        ),
+        _ => return None,
+    };
+    Some(Signature {
+        params,
+        modes: Vec::new(),
+        returns,
+        type_params: Vec::new(),
+        bounds: Vec::new(),
+    })
+}
+
+/// Builtins that call a function for each element of a range or list, their types depend on
+/// the function they are given so they have no fixed [Signature]
+const ITERATION_BUILTINS: &[&str] = &["map", "filter", "fold", "for_each"];
+
+/// The type assumed for an operator applied to operands it does not support, so that the
+/// mistake is only reported once
+fn invalid_operator(op: BinaryOp) -> Type {
+    if op.is_boolean() {
+        Type::Bool
+    } else {
+        Type::Unknown
+    }
+}
+
+/// The type of the function one of [ITERATION_BUILTINS] expects as its last argument, given
+/// the type of the elements it iterates over
+fn iteration_function(name: &str, element: &Type, args: &[(Type, Span)]) -> Type {
+    let (params, returns) = match name {
+        "fold" => (vec![args[1].0.clone(), element.clone()], args[1].0.clone()),
+        "filter" => (vec![element.clone()], Type::Bool),
+        _ => (vec![element.clone()], Type::Unknown),
+    };
+    Type::Function(params, Box::new(returns))
+}
+
+fn idents(idents: &[Ident]) -> Vec<String> {
+    idents.iter().map(|ident| ident.name.clone()).collect()
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.name.clone())
+        .collect()
+}
+
+fn mismatch(location: Span, expected: &Type, found: &Type) -> CompileError {
+    CompileError::new(
+        location,
+        CompileErrorEnum::TypeMismatch {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        },
+    )
+}
+
+struct Checker<'a> {
+    program: &'a Program,
+    /// The integer literals without a suffix, with the types they are used as
+    literals: RefCell<Literals>,
+    /// The fields of every `data` and `object`, by full path
+    records: HashMap<Vec<String>, BTreeMap<String, Type>>,
+    /// The methods of every trait with `Self` as a type parameter, by full path
+    traits: HashMap<Vec<String>, Vec<(String, Signature)>>,
+    /// Every trait implemented by a type declared in the program, as the full paths of the
+    /// trait and the type
+    implementations: HashSet<(Vec<String>, Vec<String>)>,
+    /// The traits each type parameter of the function being checked implements
+    bounds: HashMap<String, Vec<Vec<String>>>,
+    /// The full paths of every `object`, whose values are shared rather than copied
+    objects: HashSet<Vec<String>>,
+    /// The type parameters of every generic `data` and `enum`, by full path
+    generics: HashMap<Vec<String>, Vec<String>>,
+    /// The variants of every `enum` with the types of the arguments that construct them, by
+    /// full path
+    enums: HashMap<Vec<String>, Vec<(String, Vec<Type>)>>,
+    /// The signature of every function, by module index and name, methods are named like
+    /// `Counter::get`
+    signatures: HashMap<(usize, String), Signature>,
+    /// The index of the module whose code is being checked
+    module: usize,
+    /// The type parameters of the declaration whose types are being resolved
+    type_params: Vec<String>,
+    /// The declared return type of the function being checked
+    returns: Type,
+    /// Where the return type of the function being checked is written, if it is not `()`
+    returns_location: Option<Span>,
+    /// The type of the values returned with `|.` from a `func { ... }` whose return type is
+    /// being inferred
+    returned: Option<Type>,
+    errors: Vec<ProjectError>,
+    warnings: Vec<ProjectWarning>,
+}
+
+impl Checker<'_> {
+    fn error(&mut self, location: Span, error: CompileErrorEnum) {
+        self.report(CompileError::new(location, error));
+    }
+
+    fn report(&mut self, error: CompileError) {
+        self.errors.push(ProjectError::Compile {
+            path: self.program.modules()[self.module].file.clone(),
+            error,
+        });
+    }
+
+    fn warning(&mut self, location: Span, warning: CompileWarningEnum) {
+        self.warnings.push(ProjectWarning {
+            path: self.program.modules()[self.module].file.clone(),
+            warning: CompileWarning::new(location, warning),
+        });
+    }
+
+    /// The type of a literal at `location`, an integer without a suffix takes the type it is used
+    /// as
+    fn literal(&self, literal: &Literal, location: Span) -> Type {
+        match literal {
+            Literal::Unit => Type::Unit,
+            Literal::Integer(_, Some(ty)) => Type::Integer(ty),
+            Literal::Float(_, Some(ty)) => Type::Float(ty),
+            Literal::Integer(value, None) => {
+                let mut literals = self.literals.borrow_mut();
+                Type::IntegerLiteral(literals.add(self.module, location, *value))
+            }
+            Literal::Float(_, None) => Type::FloatLiteral,
+            Literal::Bool(_) => Type::Bool,
+            Literal::String(_) => Type::String,
+        }
+    }
+
+    fn mismatch(&mut self, location: Span, expected: &Type, found: &Type) {
+        self.report(mismatch(location, expected, found));
+    }
+
+    /// Reports a value that does not match the declared return type, pointing at the declaration
+    fn return_mismatch(&mut self, location: Span, found: &Type) {
+        let mut error = mismatch(location, &self.returns, found);
+        if let Some(returns) = self.returns_location {
+            error = error.with_label(returns, "return type declared here");
+        }
+        self.report(error);
+    }
+
+    /// Resolves the field types of every record and the signature of every function
+    fn declarations(&mut self) {
+        let program = self.program;
+        // Types can be used before they are declared, so which are objects and how many type
+        // arguments they take must be known before any type is resolved
+        for module in program.modules() {
+            for item in &module.ast.items {
+                let (name, type_params) = match item {
+                    Item::Object(object) => (&object.name, &[][..]),
+                    Item::Data(data) => (&data.name, &data.type_params[..]),
+                    Item::Enum(r#enum) => (&r#enum.name, &r#enum.type_params[..]),
+                    Item::Func(_) | Item::Using(_) | Item::Trait(_) | Item::Impl(_) => continue,
+                };
+                let mut path = module.name.clone();
+                path.push(name.name.clone());
+                if let Item::Object(_) = item {
+                    self.objects.insert(path.clone());
+                }
+                if !type_params.is_empty() {
+                    let names = type_params.iter().map(|param| param.name.clone()).collect();
+                    self.generics.insert(path, names);
+                }
+            }
+        }
+
+        for (index, module) in program.modules().iter().enumerate() {
+            self.module = index;
+            for item in &module.ast.items {
+                self.type_params = match item {
+                    Item::Data(data) => idents(&data.type_params),
+                    Item::Enum(r#enum) => idents(&r#enum.type_params),
+                    _ => Vec::new(),
+                };
+                match item {
+                    Item::Data(Data { name, fields, .. })
+                    | Item::Object(Object { name, fields, .. }) => {
+                        let fields = fields
+                            .iter()
+                            .map(|field| (field.name.name.clone(), self.resolve_type(&field.ty)))
+                            .collect();
+                        let mut path = module.name.clone();
+                        path.push(name.name.clone());
+                        self.records.insert(path, fields);
+                        if let Item::Object(object) = item {
+                            for method in &object.methods {
+                                let signature = self.signature(method);
+                                self.signatures
+                                    .insert((index, method_name(object, method)), signature);
+                            }
+                        }
+                    }
+                    Item::Func(func) => {
+                        let signature = self.signature(func);
+                        self.signatures
+                            .insert((index, func.name.name.clone()), signature);
+                    }
+                    Item::Enum(Enum { name, variants, .. }) => {
+                        let mut path = module.name.clone();
+                        path.push(name.name.clone());
+                        let variants = variants
+                            .iter()
+                            .map(|variant| {
+                                let args = match &variant.kind {
+                                    VariantKind::Unit => Vec::new(),
+                                    VariantKind::Tuple(types) => {
+                                        types.iter().map(|ty| self.resolve_type(ty)).collect()
+                                    }
+                                    VariantKind::Record(fields) => vec![Type::Record(
+                                        fields
+                                            .iter()
+                                            .map(|field| {
+                                                (
+                                                    field.name.name.clone(),
+                                                    self.resolve_type(&field.ty),
+                                                )
+                                            })
+                                            .collect(),
+                                    )],
+                                };
+                                (variant.name.name.clone(), args)
+                            })
+                            .collect();
+                        self.enums.insert(path, variants);
+                    }
+                    Item::Trait(r#trait) => {
+                        let mut path = module.name.clone();
+                        path.push(r#trait.name.name.clone());
+                        let methods = r#trait
+                            .methods
+                            .iter()
+                            .map(|method| {
+                                let signature = self.trait_signature(&path, method);
+                                (method.name.name.clone(), signature)
+                            })
+                            .collect();
+                        self.traits.insert(path, methods);
+                    }
+                    Item::Using(_) | Item::Impl(_) => {}
+                }
+            }
+        }
+        self.type_params = Vec::new();
+
+        // Every trait is known by now, wherever it is declared
+        for (index, module) in program.modules().iter().enumerate() {
+            self.module = index;
+            for item in &module.ast.items {
+                if let Item::Impl(implementation) = item {
+                    self.implementation(implementation);
+                }
+            }
+        }
+    }
+
+    /// The signature of a method of the trait at `path`, `Self` is a type parameter that has to
+    /// implement the trait
+    fn trait_signature(&mut self, path: &[String], method: &TraitMethod) -> Signature {
+        self.type_params = vec!["Self".into()];
+        let params = self.param_types(&method.params);
+        let returns = method
+            .returns
+            .iter()
+            .map(|ty| self.resolve_type(ty))
+            .collect();
+        self.type_params = Vec::new();
+
+        // Calls are sent to the implementation for the type of the `Self` argument
+        if !params
+            .iter()
+            .any(|ty| *ty.owned() == Type::Param("Self".into()))
+        {
+            self.error(
+                method.name.location,
+                CompileErrorEnum::UninferableTypeParameter {
+                    param: "Self".into(),
+                    func: method.name.name.clone(),
+                },
+            );
+        }
+
+        Signature {
+            params: Some(params),
+            modes: method.params.iter().map(|param| param.mode).collect(),
+            returns: Type::from_list(returns),
+            type_params: vec!["Self".into()],
+            bounds: vec![("Self".into(), path.to_vec())],
+        }
+    }
+
+    /// Checks that an `impl` provides exactly the methods of its trait, with the signatures the
+    /// trait declares for its type
+    fn implementation(&mut self, implementation: &Impl) {
+        for method in &implementation.methods {
+            let signature = self.signature(method);
+            self.signatures.insert(
+                (self.module, impl_method_name(implementation, method)),
+                signature,
+            );
+        }
+
+        let program = self.program;
+        let trait_name = segments(&implementation.r#trait).join("::");
+        let Some(r#trait) = program.lookup_trait(self.module, &segments(&implementation.r#trait))
+        else {
+            self.error(
+                implementation.r#trait.location,
+                CompileErrorEnum::NotATrait(trait_name),
+            );
+            return;
+        };
+        let trait_path = program.trait_path(&r#trait);
+        let ty = self.resolve_type(&implementation.ty);
+        let Type::Named(type_path, _) = &ty else {
+            if !ty.is_unknown() {
+                self.error(
+                    implementation.ty.location,
+                    CompileErrorEnum::InvalidImplementation(ty.to_string()),
+                );
+            }
+            return;
+        };
+        if !self
+            .implementations
+            .insert((trait_path.clone(), type_path.clone()))
+        {
+            self.error(
+                implementation.ty.location,
+                CompileErrorEnum::DuplicateImplementation {
+                    r#trait: trait_name.clone(),
+                    ty: ty.to_string(),
+                },
+            );
+        }
+
+        let methods = self.traits.get(&trait_path).cloned().unwrap_or_default();
+        let bindings = HashMap::from([("Self".to_string(), ty.clone())]);
+        for (name, expected) in &methods {
+            let Some(method) = implementation.method(name) else {
+                self.error(
+                    implementation.ty.location,
+                    CompileErrorEnum::MissingTraitMethod {
+                        r#trait: trait_name.clone(),
+                        ty: ty.to_string(),
+                        method: name.clone(),
+                    },
+                );
+                continue;
+            };
+            let found = &self.signatures[&(self.module, impl_method_name(implementation, method))];
+            let function = |signature: &Signature| {
+                Type::Function(
+                    signature.params.clone().unwrap_or_default(),
+                    Box::new(signature.returns.clone()),
+                )
+            };
+            let expected = function(expected).substitute(&bindings);
+            let found = function(found);
+            if expected != found {
+                self.mismatch(method.name.location, &expected, &found);
+            }
+        }
+        for method in &implementation.methods {
+            if !methods.iter().any(|(name, _)| *name == method.name.name) {
+                self.error(
+                    method.name.location,
+                    CompileErrorEnum::UnknownTraitMethod {
+                        r#trait: trait_name.clone(),
+                        method: method.name.name.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn signature(&mut self, func: &Func) -> Signature {
+        let type_params: Vec<String> = func
+            .type_params
+            .iter()
+            .map(|param| param.name.name.clone())
+            .collect();
+        self.type_params = type_params.clone();
+        let params = self.param_types(&func.params);
+        let returns = func
+            .returns
+            .iter()
+            .map(|ty| self.resolve_type(ty))
+            .collect();
+        self.type_params = Vec::new();
+
+        // Type parameters are only ever inferred from the arguments of a call
+        for param in &func.type_params {
+            if !params.iter().any(|ty| ty.mentions(&param.name.name)) {
+                self.error(
+                    param.name.location,
+                    CompileErrorEnum::UninferableTypeParameter {
+                        param: param.name.name.clone(),
+                        func: func.name.name.clone(),
+                    },
+                );
+            }
+        }
+
+        let program = self.program;
+        let mut bounds = Vec::new();
+        for param in &func.type_params {
+            for bound in &param.bounds {
+                match program.lookup_trait(self.module, &segments(bound)) {
+                    Some(r#trait) => {
+                        bounds.push((param.name.name.clone(), program.trait_path(&r#trait)))
+                    }
+                    None => self.error(
+                        bound.location,
+                        CompileErrorEnum::NotATrait(segments(bound).join("::")),
+                    ),
+                }
+            }
+        }
+
+        Signature {
+            params: Some(params),
+            modes: func.params.iter().map(|param| param.mode).collect(),
+            returns: Type::from_list(returns),
+            type_params,
+            bounds,
+        }
+    }
+
+    /// The types of `params`, a `ref` parameter is a borrowed object
+    fn param_types(&mut self, params: &[Param]) -> Vec<Type> {
+        params
+            .iter()
+            .map(|param| {
+                let ty = self.resolve_type(&param.ty);
+                match param.mode {
+                    ParamMode::Value => ty,
+                    // Only objects can implement a trait method that borrows `Self`
+                    _ if !self.is_object(&ty) && ty != Type::Param("Self".into()) => {
+                        if !ty.is_unknown() {
+                            self.error(
+                                param.location,
+                                CompileErrorEnum::InvalidParamMode {
+                                    mode: param.mode.keyword(),
+                                    ty: ty.to_string(),
+                                },
+                            );
+                        }
+                        ty
+                    }
+                    ParamMode::Ref => Type::Ref(Box::new(ty)),
+                    ParamMode::Mut => ty,
+                }
+            })
+            .collect()
+    }
+
+    fn resolve_type(&mut self, ty: &ast::Type) -> Type {
+        let path = segments(&ty.path);
+        if let [name] = path.as_slice() {
+            if self.type_params.contains(name) {
+                self.type_arguments(ty, 0);
+                return Type::Param(name.clone());
+            }
+        }
+
+        let program = self.program;
+        let found = program.lookup_type(self.module, &path);
+        if let Some(TypeRef::Builtin(path)) = &found {
+            if let [name] = path.as_slice() {
+                if GENERIC_TYPES.contains(&name.as_str()) {
+                    let Some(arg) = self.type_arguments(ty, 1).pop() else {
+                        return Type::Unknown;
+                    };
+                    return match name.as_str() {
+                        "Result" => Type::Result(Box::new(arg)),
+                        "List" => Type::List(Box::new(arg)),
+                        _ => Type::Range(Box::new(arg)),
+                    };
+                }
+            }
+        }
+
+        match found {
+            Some(TypeRef::Item { module, item }) => match item {
+                Item::Data(Data { name, .. })
+                | Item::Object(Object { name, .. })
+                | Item::Enum(Enum { name, .. }) => {
+                    let mut path = self.program.modules()[module].name.clone();
+                    path.push(name.name.clone());
+                    let expected = self.generics.get(&path).map_or(0, Vec::len);
+                    let mut args = self.type_arguments(ty, expected);
+                    args.resize(expected, Type::Unknown);
+                    Type::Named(path, args)
+                }
+                Item::Using(_) | Item::Func(_) | Item::Trait(_) | Item::Impl(_) => {
+                    unreachable!("Only types are looked up")
+                }
+            },
+            Some(TypeRef::Builtin(path)) => {
+                self.type_arguments(ty, 0);
+                let builtin = match path.as_slice() {
+                    [name] => Type::builtin(name),
+                    _ if self.records.contains_key(&path) => {
+                        Some(Type::Named(path.clone(), Vec::new()))
+                    }
+                    _ => None,
+                };
+                builtin.unwrap_or_else(|| {
+                    self.error(ty.location, CompileErrorEnum::UnknownType(path.join("::")));
+                    Type::Unknown
+                })
+            }
+            None => {
+                self.error(ty.location, CompileErrorEnum::UnknownType(path.join("::")));
+                Type::Unknown
+            }
+        }
+    }
+
+    /// Resolves the type arguments of `ty`, reporting them if there are not `expected` of them.
+    /// Nothing is returned in that case
+    fn type_arguments(&mut self, ty: &ast::Type, expected: usize) -> Vec<Type> {
+        if ty.args.len() != expected {
+            self.error(
+                ty.location,
+                CompileErrorEnum::TypeArgumentCount {
+                    ty: segments(&ty.path).join("::"),
+                    expected,
+                    found: ty.args.len(),
+                },
+            );
+            return Vec::new();
+        }
+        ty.args.iter().map(|arg| self.resolve_type(arg)).collect()
+    }
+
+    /// The types given for the type parameters of the generic declaration at `path`, by name
+    fn bindings(&self, path: &[String], args: &[Type]) -> HashMap<String, Type> {
+        let params = self.generics.get(path).map_or(&[][..], Vec::as_slice);
+        params.iter().cloned().zip(args.iter().cloned()).collect()
+    }
+
+    /// The fields of a record type, [None] if the type is not a record
+    fn fields(&self, ty: &Type) -> Option<BTreeMap<String, Type>> {
+        match ty {
+            Type::Named(path, args) => {
+                let bindings = self.bindings(path, args);
+                let fields = self.records.get(path)?;
+                Some(
+                    fields
+                        .iter()
+                        .map(|(name, ty)| (name.clone(), ty.substitute(&bindings)))
+                        .collect(),
+                )
+            }
+            Type::Record(fields) => Some(fields.clone()),
+            // Objects reached through a `ref` can not be changed either
+            Type::Ref(ty) => Some(
+                self.fields(ty)?
+                    .into_iter()
+                    .map(|(name, ty)| {
+                        if self.is_object(&ty) {
+                            (name, Type::Ref(Box::new(ty)))
+                        } else {
+                            (name, ty)
+                        }
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Whether values of type `ty` implement the trait at `trait_path`
+    fn implements(&self, ty: &Type, trait_path: &[String]) -> bool {
+        match ty {
+            _ if ty.is_unknown() => true,
+            Type::Ref(ty) => self.implements(ty, trait_path),
+            Type::Param(name) => self
+                .bounds
+                .get(name)
+                .is_some_and(|bounds| bounds.iter().any(|bound| bound == trait_path)),
+            Type::Named(path, _) => self
+                .implementations
+                .contains(&(trait_path.to_vec(), path.clone())),
+            // The language implements its own traits for the types it provides
+            _ => match trait_path {
+                [name] if name == "Add" || name == "Compare" => {
+                    ty.is_numeric() || *ty == Type::String
+                }
+                [name] if name == "Display" => {
+                    ty.is_numeric() || matches!(ty, Type::Unit | Type::Bool | Type::String)
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Reports a value of a type parameter that is printed without being bound by `Display`,
+    /// values of other types always have a way to be printed
+    fn displayable(&mut self, ty: &Type, location: Span) {
+        let ty = ty.owned();
+        if let Type::Param(_) = ty {
+            if !self.implements(ty, &["Display".into()]) {
+                self.error(
+                    location,
+                    CompileErrorEnum::NotImplemented {
+                        r#trait: "Display".into(),
+                        ty: ty.to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn is_object(&self, ty: &Type) -> bool {
+        matches!(ty, Type::Named(path, _) if self.objects.contains(path))
+    }
+
+    /// Whether a value of type `found` can be used where `expected` is required, an integer
+    /// literal takes the type it is used as
+    fn compatible(&self, expected: &Type, found: &Type) -> bool {
+        match (expected, found) {
+            _ if expected.is_unknown() || found.is_unknown() => true,
+            (Type::IntegerLiteral(expected), Type::IntegerLiteral(found)) => {
+                self.literals.borrow_mut().merge(*expected, *found);
+                true
+            }
+            (
+                Type::Integer(_) | Type::Float(_) | Type::FloatLiteral,
+                Type::IntegerLiteral(literal),
+            )
+            | (
+                Type::IntegerLiteral(literal),
+                Type::Integer(_) | Type::Float(_) | Type::FloatLiteral,
+            ) => {
+                let ty = match expected {
+                    Type::IntegerLiteral(_) => found,
+                    _ => expected,
+                };
+                self.literals.borrow_mut().bind(*literal, ty);
+                true
+            }
+            (Type::Float(_), Type::FloatLiteral) | (Type::FloatLiteral, Type::Float(_)) => true,
+            (Type::Tuple(expected), Type::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected
+                        .iter()
+                        .zip(found)
+                        .all(|(expected, found)| self.compatible(expected, found))
+            }
+            (Type::Named(expected_path, expected), Type::Named(found_path, found)) => {
+                expected_path == found_path
+                    && expected.len() == found.len()
+                    && expected
+                        .iter()
+                        .zip(found)
+                        .all(|(expected, found)| self.compatible(expected, found))
+            }
+            // A borrowed object can only be passed on as borrowed
+            (Type::Ref(expected), found) => self.compatible(expected, found.owned()),
+            (_, Type::Ref(_)) => false,
+            // Objects are only created by naming their type
+            (Type::Named(..), Type::Record(_)) | (Type::Record(_), Type::Named(..))
+                if self.is_object(expected) || self.is_object(found) =>
+            {
+                false
+            }
+            (Type::Named(..), Type::Record(_)) | (Type::Record(_), Type::Named(..)) => {
+                match (self.fields(expected), self.fields(found)) {
+                    (Some(expected), Some(found)) => {
+                        expected.len() == found.len()
+                            && expected.iter().all(|(name, expected)| {
+                                found
+                                    .get(name)
+                                    .is_some_and(|found| self.compatible(expected, found))
+                            })
+                    }
+                    _ => false,
+                }
+            }
+            (Type::Range(expected), Type::Range(found))
+            | (Type::List(expected), Type::List(found))
+            | (Type::Result(expected), Type::Result(found)) => self.compatible(expected, found),
+            // A plain value is a successful result
+            (Type::Result(expected), found) => self.compatible(expected, found),
+            (
+                Type::Function(expected_params, expected_returns),
+                Type::Function(found_params, found_returns),
+            ) => {
+                expected_params.len() == found_params.len()
+                    && expected_params
+                        .iter()
+                        .zip(found_params)
+                        .all(|(expected, found)| self.compatible(expected, found))
+                    && self.compatible(expected_returns, found_returns)
+            }
+            (Type::Record(expected), Type::Record(found)) => {
+                expected.len() == found.len()
+                    && expected.iter().all(|(name, expected)| {
+                        found
+                            .get(name)
+                            .is_some_and(|found| self.compatible(expected, found))
+                    })
+            }
+            _ => expected == found,
+        }
+    }
+
+    /// The more precise of two compatible types, [None] if they are not compatible
+    fn unify(&self, lhs: Type, rhs: Type) -> Option<Type> {
+        if let (Type::Result(_), _) | (_, Type::Result(_)) = (&lhs, &rhs) {
+            let ok = self.unify(lhs.ok().clone(), rhs.ok().clone())?;
+            return Some(Type::Result(Box::new(ok)));
+        }
+        if !self.compatible(&lhs, &rhs) {
+            return None;
+        }
+        Some(match (&lhs, &rhs) {
+            (Type::Never, _) => rhs,
+            (_, Type::Never) => lhs,
+            (Type::Unknown | Type::IntegerLiteral(_) | Type::Record(_), _) => rhs,
+            (Type::FloatLiteral, Type::Integer(_) | Type::IntegerLiteral(_)) => lhs,
+            (Type::FloatLiteral, _) => rhs,
+            (Type::Named(path, lhs_args), Type::Named(_, rhs_args)) => Type::Named(
+                path.clone(),
+                lhs_args
+                    .iter()
+                    .zip(rhs_args)
+                    .map(|(lhs, rhs)| {
+                        self.unify(lhs.clone(), rhs.clone())
+                            .unwrap_or_else(|| lhs.clone())
+                    })
+                    .collect(),
+            ),
+            _ => lhs,
+        })
+    }
+
+    /// Checks a function or method, whose signature is known by `name`
+    fn func(&mut self, name: &str, func: &Func) {
+        let signature = self.signatures[&(self.module, name.to_string())].clone();
+        self.bounds = HashMap::new();
+        for (param, r#trait) in &signature.bounds {
+            self.bounds
+                .entry(param.clone())
+                .or_default()
+                .push(r#trait.clone());
+        }
+        let input = Type::from_list(signature.params.unwrap_or_default());
+        self.returns = signature.returns.clone();
+        self.returns_location = func
+            .returns
+            .first()
+            .zip(func.returns.last())
+            .map(|(first, last)| first.location.to(last.location));
+
+        let found = self.block(&func.body, input, &mut Vec::new());
+        if !self.compatible(&signature.returns, &found) {
+            let location = match &func.body.tail {
+                Some(tail) => tail.location,
+                None => func.body.location,
+            };
+            self.return_mismatch(location, &found);
+        }
+    }
+
+    fn block(&mut self, block: &Block, input: Type, scope: &mut Scope) -> Type {
+        scope.push(HashMap::new());
+        for statement in &block.statements {
+            self.statement(statement, input.clone(), scope);
+        }
+        let result = match &block.tail {
+            Some(tail) => self.pipeline(tail, input, scope),
+            None => Type::Unit,
+        };
+        scope.pop();
+        result
+    }
+
+    fn pipeline(&mut self, pipeline: &Pipeline, input: Type, scope: &mut Scope) -> Type {
+        self.stages(&pipeline.stages, input, false, scope)
+    }
+
+    /// Checks a pipeline whose value is discarded, so the arms of a final `|?` do not need to
+    /// produce the same type
+    fn statement(&mut self, pipeline: &Pipeline, input: Type, scope: &mut Scope) {
+        if let Type::Result(_) = self.stages(&pipeline.stages, input, true, scope) {
+            self.warning(pipeline.location, CompileWarningEnum::UnhandledError);
+        }
+    }
+
+    /// Checks the stages of a pipeline, once a stage produces a `Result` the following stages
+    /// receive its success value and the pipeline produces a `Result` until a `|!` handles it
+    ///
+    /// A `|=` between a failing stage and its `|!` is skipped on failure and bound to the result
+    /// of the handler instead, so the handler has to produce the type of the binding
+    fn stages(
+        &mut self,
+        stages: &[Stage],
+        input: Type,
+        statement: bool,
+        scope: &mut Scope,
+    ) -> Type {
+        let mut current = input;
+        let mut fallible = false;
+        // The `|=` bindings a failure would skip, with the value each one shadows
+        let mut pending: Vec<(String, Type, Option<Type>)> = Vec::new();
+        for (index, stage) in stages.iter().enumerate() {
+            if index > 0 && matches!(stages[index - 1].kind, StageKind::Return) {
+                self.warning(stage.location, CompileWarningEnum::UnreachableStage);
+            }
+            current = match &stage.kind {
+                StageKind::Match(arms) if statement && index + 1 == stages.len() => {
+                    self.r#match(arms, &current, stage.location, false, scope);
+                    Type::Unit
+                }
+                StageKind::Set(name) if fallible => {
+                    let shadowed = scope
+                        .last()
+                        .and_then(|names| names.get(&name.name))
+                        .cloned();
+                    pending.push((name.name.clone(), current.clone(), shadowed));
+                    self.stage(stage, current, scope)
+                }
+                StageKind::Error(handler) => {
+                    fallible = false;
+                    let handled = self.stage(stage, current, scope);
+                    for (_, binding, _) in pending.drain(..) {
+                        if !self.compatible(&binding, &handled) {
+                            self.mismatch(handler.location, &binding, &handled);
+                        }
+                    }
+                    handled
+                }
+                StageKind::Return if fallible => {
+                    fallible = false;
+                    pending.clear();
+                    self.stage(stage, Type::Result(Box::new(current)), scope)
+                }
+                _ => self.stage(stage, current, scope),
+            };
+            if let Type::Result(ok) = current {
+                fallible = true;
+                current = *ok;
+            }
+        }
+        // Without a handler a failure leaves the skipped names unbound
+        let names = scope
+            .last_mut()
+            .expect("Pipelines are always checked inside a block");
+        for (name, _, shadowed) in pending.into_iter().rev() {
+            match shadowed {
+                Some(shadowed) => names.insert(name, shadowed),
+                None => names.remove(&name),
+            };
+        }
+        if fallible {
+            Type::Result(Box::new(current))
+        } else {
+            current
+        }
+    }
+
+    fn stage(&mut self, stage: &Stage, current: Type, scope: &mut Scope) -> Type {
+        match &stage.kind {
+            StageKind::Value(expr) => self.expr(expr, &current, scope),
+            StageKind::Next(expr) => self.apply(expr, current, scope),
+            StageKind::Set(name) => {
+                scope
+                    .last_mut()
+                    .expect("Pipelines are always checked inside a block")
+                    .insert(name.name.clone(), current.clone());
+                current
+            }
+            StageKind::Match(arms) => self.r#match(arms, &current, stage.location, true, scope),
+            StageKind::Error(handler) => {
+                let handled = self.apply(handler, Type::String, scope);
+                match self.unify(current.clone(), handled.clone()) {
+                    Some(unified) => unified,
+                    None => {
+                        self.mismatch(handler.location, &current, &handled);
+                        current
+                    }
+                }
+            }
+            StageKind::Return => {
+                match self.returned.take() {
+                    Some(returned) => {
+                        self.returned = Some(
+                            self.unify(returned.clone(), current.clone())
+                                .unwrap_or_else(|| {
+                                    self.mismatch(stage.location, &returned, &current);
+                                    returned
+                                }),
+                        );
+                    }
+                    None if !self.compatible(&self.returns, &current) => {
+                        self.return_mismatch(stage.location, &current);
+                    }
+                    None => {}
+                }
+                Type::Never
+            }
+        }
+    }
+
+    /// Checks the arms of a `|?` stage, when `unify` is set they must all produce the same type
+    fn r#match(
+        &mut self,
+        arms: &[MatchArm],
+        current: &Type,
+        location: Span,
+        unify: bool,
+        scope: &mut Scope,
+    ) -> Type {
+        let mut result = Type::Never;
+        for arm in arms {
+            scope.push(HashMap::new());
+            self.pattern(&arm.pattern, current, scope);
+            let body = self.pipeline(&arm.body, current.clone(), scope);
+            scope.pop();
+
+            result = match self.unify(result.clone(), body.clone()) {
+                Some(unified) => unified,
+                None if !unify => Type::Unknown,
+                None => {
+                    self.mismatch(arm.body.location, &result, &body);
+                    result
+                }
+            };
+        }
+        self.exhaustiveness(arms, current, location);
+        result
+    }
+
+    /// Checks a `|>` stage, functions receive the pipe value as their first argument unless it
+    /// is referenced explicitly with `.`
+    fn apply(&mut self, expr: &Expr, current: Type, scope: &mut Scope) -> Type {
+        match &expr.kind {
+            ExprKind::Path(path) if lookup(path, scope).is_none() => {
+                self.call(path, vec![(current, expr.location)], expr.location)
+            }
+            ExprKind::Call(path, args) if !args.iter().any(Expr::uses_current) => {
+                let first = (current.clone(), expr.location);
+                let types = self.args(path, vec![first], args, &current, scope);
+                self.call(path, types, expr.location)
+            }
+            // A closure or a variable holding a function is called with the pipe value
+            ExprKind::Closure(body) => {
+                let expected = Type::Function(vec![current], Box::new(Type::Unknown));
+                match self.closure(body, Some(&expected), scope) {
+                    Type::Function(_, returns) => *returns,
+                    _ => Type::Unknown,
+                }
+            }
+            ExprKind::Path(path) => match lookup(path, scope).cloned() {
+                Some(Type::Function(params, returns)) => {
+                    let expected = Type::from_list(params);
+                    if !self.compatible(&expected, &current) {
+                        self.mismatch(expr.location, &expected, &current);
+                    }
+                    *returns
+                }
+                _ => self.expr(expr, &current, scope),
+            },
+            _ => self.expr(expr, &current, scope),
+        }
+    }
+
+    fn call(&mut self, path: &Path, args: Vec<(Type, Span)>, location: Span) -> Type {
+        let segments = segments(path);
+        let program = self.program;
+        // Qualified paths that do not resolve have already been reported by the resolver
+        let Some(callee) = program.lookup(self.module, &segments) else {
+            return Type::Unknown;
+        };
+        let (name, declared) = match &callee {
+            Callee::Builtin(absolute) if matches!(absolute.as_slice(), [name] if ITERATION_BUILTINS.contains(&name.as_str())) =>
+            {
+                return self.iteration(&absolute[0], args, location);
+            }
+            Callee::Builtin(absolute) if absolute.as_slice() == ["Std", "CLI", "parse_args"] => {
+                return self.parse_args(args, location);
+            }
+            Callee::Builtin(absolute) => (absolute.join("::"), None),
+            // Labels can only point into the file the error is reported in
+            Callee::Func { module, func } => (
+                func.name.name.clone(),
+                (*module == self.module).then_some(*func),
+            ),
+            Callee::Method {
+                module,
+                object,
+                func,
+            } => (
+                method_name(object, func),
+                (*module == self.module).then_some(*func),
+            ),
+            Callee::Variant { .. } | Callee::Object { .. } | Callee::TraitMethod { .. } => {
+                (segments.join("::"), None)
+            }
+        };
+        let Some(signature) = self.callee_signature(&callee) else {
+            self.error(location, CompileErrorEnum::UnknownName(name));
+            return Type::Unknown;
+        };
+        if let Callee::Builtin(absolute) = &callee {
+            // These turn their arguments into text
+            if matches!(absolute.as_slice(), [name] if ["print", "println", "panic", "fail"].contains(&name.as_str()))
+            {
+                for (arg, location) in &args {
+                    self.displayable(arg, *location);
+                }
+            }
+        }
+        let bindings = self.infer_bindings(&signature, &args);
+        for (param, r#trait) in &signature.bounds {
+            let Some(ty) = bindings.get(param) else {
+                continue;
+            };
+            if !self.implements(ty, r#trait) {
+                self.error(
+                    location,
+                    CompileErrorEnum::NotImplemented {
+                        r#trait: r#trait.last().cloned().unwrap_or_default(),
+                        ty: ty.to_string(),
+                    },
+                );
+            }
+        }
+        let signature = self.instantiate(signature, &args);
+
+        if let Some(params) = &signature.params {
+            if params.len() != args.len() {
+                self.error(
+                    location,
+                    CompileErrorEnum::ArgumentCount {
+                        name,
+                        expected: params.len(),
+                        found: args.len(),
+                    },
+                );
+            } else {
+                for (i, (param, (arg, location))) in params.iter().zip(&args).enumerate() {
+                    let arg = match signature.modes.get(i) {
+                        Some(ParamMode::Mut) if matches!(arg, Type::Ref(_)) => {
+                            self.error(*location, CompileErrorEnum::Immutable(arg.to_string()));
+                            continue;
+                        }
+                        // The function receives its own copy, which it is free to change
+                        Some(ParamMode::Value) | None => arg.owned(),
+                        Some(_) => arg,
+                    };
+                    if !self.compatible(param, arg) {
+                        let mut error = mismatch(*location, param, arg);
+                        if let Some(declared) = declared.and_then(|func| func.params.get(i)) {
+                            error = error.with_label(declared.location, "parameter declared here");
+                        }
+                        self.report(error);
+                    }
+                }
+            }
+        }
+        signature.returns
+    }
+
+    /// The signature of whatever `callee` names, [None] for builtins the checker does not know
+    fn callee_signature(&self, callee: &Callee) -> Option<Signature> {
+        match callee {
+            Callee::Func { module, func } => {
+                Some(self.signatures[&(*module, func.name.name.clone())].clone())
+            }
+            Callee::Method {
+                module,
+                object,
+                func,
+            } => Some(self.signatures[&(*module, method_name(object, func))].clone()),
+            Callee::Builtin(absolute) => builtin_signature(absolute),
+            Callee::Variant {
+                module,
+                r#enum,
+                variant,
+            } => {
+                let (path, args) = self.variant(*module, r#enum, variant);
+                Some(self.constructor(path, args))
+            }
+            Callee::Object { module, object } => {
+                let (path, fields) = self.object(*module, object);
+                Some(self.constructor(path, vec![fields]))
+            }
+            Callee::TraitMethod {
+                module,
+                r#trait,
+                method,
+            } => {
+                let mut path = self.program.modules()[*module].name.clone();
+                path.push(r#trait.name.name.clone());
+                self.traits[&path]
+                    .iter()
+                    .find(|(name, _)| *name == method.name.name)
+                    .map(|(_, signature)| signature.clone())
+            }
+        }
+    }
+
+    /// The signature of a variant or object, which creates a value of the type at `path` from
+    /// arguments of the types in `params`
+    fn constructor(&self, path: Vec<String>, params: Vec<Type>) -> Signature {
+        let type_params = self.generics.get(&path).cloned().unwrap_or_default();
+        let args = type_params.iter().cloned().map(Type::Param).collect();
+        Signature {
+            params: Some(params),
+            modes: Vec::new(),
+            returns: Type::Named(path, args),
+            type_params,
+            bounds: Vec::new(),
+        }
+    }
+
+    /// Replaces the type parameters of `signature` with the types inferred from the arguments
+    /// of a call. Parameters the arguments say nothing about can be any type, such as the `T`
+    /// of `Option::None`
+    fn instantiate(&self, signature: Signature, args: &[(Type, Span)]) -> Signature {
+        if signature.type_params.is_empty() {
+            return signature;
+        }
+        let mut bindings = self.infer_bindings(&signature, args);
+        for param in &signature.type_params {
+            bindings.entry(param.clone()).or_insert(Type::Unknown);
+        }
+
+        Signature {
+            params: signature.params.map(|params| {
+                params
+                    .iter()
+                    .map(|param| param.substitute(&bindings))
+                    .collect()
+            }),
+            modes: signature.modes,
+            returns: signature.returns.substitute(&bindings),
+            type_params: Vec::new(),
+            bounds: Vec::new(),
+        }
+    }
+
+    /// The types the arguments of a call give the type parameters of `signature`, parameters
+    /// the arguments say nothing about are left out
+    fn infer_bindings(
+        &self,
+        signature: &Signature,
+        args: &[(Type, Span)],
+    ) -> HashMap<String, Type> {
+        let mut bindings = HashMap::new();
+        if signature.type_params.is_empty() {
+            return bindings;
+        }
+        for (i, (param, (arg, _))) in signature.params.iter().flatten().zip(args).enumerate() {
+            let arg = match signature.modes.get(i) {
+                Some(ParamMode::Value) | None => arg.owned(),
+                Some(_) => arg,
+            };
+            self.infer(param, arg, &mut bindings);
+        }
+        bindings
+    }
+
+    /// Binds the type parameters in `param` to the parts of `arg` in the same position
+    fn infer(&self, param: &Type, arg: &Type, bindings: &mut HashMap<String, Type>) {
+        match (param, arg) {
+            (Type::Param(name), _) => {
+                let bound = match bindings.remove(name) {
+                    Some(bound) => self.unify(bound.clone(), arg.clone()).unwrap_or(bound),
+                    None => arg.clone(),
+                };
+                bindings.insert(name.clone(), bound);
+            }
+            (Type::Named(_, params), Type::Named(_, args))
+            | (Type::Tuple(params), Type::Tuple(args)) => {
+                for (param, arg) in params.iter().zip(args) {
+                    self.infer(param, arg, bindings);
+                }
+            }
+            (Type::Named(..) | Type::Record(_), Type::Record(args)) => {
+                for (name, param) in self.fields(param).unwrap_or_default() {
+                    if let Some(arg) = args.get(&name) {
+                        self.infer(&param, arg, bindings);
+                    }
+                }
+            }
+            (Type::Range(param), Type::Range(arg))
+            | (Type::List(param), Type::List(arg))
+            | (Type::Result(param), Type::Result(arg))
+            | (Type::Ref(param), Type::Ref(arg)) => self.infer(param, arg, bindings),
+            (Type::Result(param) | Type::Ref(param), arg) => self.infer(param, arg, bindings),
+            (Type::Function(params, returns), Type::Function(args, found)) => {
+                for (param, arg) in params.iter().zip(args) {
+                    self.infer(param, arg, bindings);
+                }
+                self.infer(returns, found, bindings);
+            }
+            _ => {}
+        }
+    }
+
+    /// The full path of `object` and the record type it is created from
+    fn object(&self, module: usize, object: &Object) -> (Vec<String>, Type) {
+        let mut path = self.program.modules()[module].name.clone();
+        path.push(object.name.name.clone());
+        let fields = Type::Record(self.records[&path].clone());
+        (path, fields)
+    }
+
+    /// The full path of the enum declaring `variant` and the types of the arguments that
+    /// construct it
+    fn variant(&self, module: usize, r#enum: &Enum, variant: &Variant) -> (Vec<String>, Vec<Type>) {
+        let mut path = self.program.modules()[module].name.clone();
+        path.push(r#enum.name.name.clone());
+        let args = self.enums[&path]
+            .iter()
+            .find(|(name, _)| *name == variant.name.name)
+            .map(|(_, args)| args.clone())
+            .expect("Every variant is collected from the declarations");
+        (path, args)
+    }
+
+    /// Checks the arguments of a call after the `first` ones that are already known. Closures are
+    /// checked last so that their parameter types can be inferred from the function being called
+    /// and the other arguments
+    fn args(
+        &mut self,
+        path: &Path,
+        first: Vec<(Type, Span)>,
+        args: &[Expr],
+        current: &Type,
+        scope: &mut Scope,
+    ) -> Vec<(Type, Span)> {
+        let offset = first.len();
+        let mut types = first;
+        for arg in args {
+            let ty = match &arg.kind {
+                ExprKind::Closure(_) => Type::Unknown,
+                _ => self.expr(arg, current, scope),
+            };
+            types.push((ty, arg.location));
+        }
+
+        for (i, arg) in args.iter().enumerate() {
+            if let ExprKind::Closure(body) = &arg.kind {
+                let expected = self.expected_arg(path, &types, offset + i);
+                types[offset + i].0 = self.closure(body, expected.as_ref(), scope);
+            }
+        }
+        types
+    }
+
+    /// The type the function named by `path` requires for its argument at `index`
+    fn expected_arg(&self, path: &Path, args: &[(Type, Span)], index: usize) -> Option<Type> {
+        match self.program.lookup(self.module, &segments(path))? {
+            Callee::Builtin(absolute) => match absolute.as_slice() {
+                [name] if ITERATION_BUILTINS.contains(&name.as_str()) => {
+                    let arity = if name == "fold" { 3 } else { 2 };
+                    (args.len() == arity && index == arity - 1).then(|| {
+                        let element = args[0].0.element().unwrap_or(Type::Unknown);
+                        iteration_function(name, &element, args)
+                    })
+                }
+                _ => None,
+            },
+            callee => {
+                let signature = self.instantiate(self.callee_signature(&callee)?, args);
+                signature.params?.get(index).cloned()
+            }
+        }
+    }
+
+    /// Checks the body of a `func { ... }` against the function type it is `expected` to have,
+    /// the body can use any variable visible where it is written
+    fn closure(&mut self, body: &Block, expected: Option<&Type>, scope: &mut Scope) -> Type {
+        let Some(Type::Function(params, returns)) = expected else {
+            // Without a function type to infer from its parameters could be anything
+            self.with_returns(Type::Unknown, |checker| {
+                checker.block(body, Type::Unknown, scope)
+            });
+            return Type::Unknown;
+        };
+
+        let found = self.with_returns(returns.as_ref().clone(), |checker| {
+            let found = checker.block(body, Type::from_list(params.clone()), scope);
+            let location = match &body.tail {
+                Some(tail) => tail.location,
+                None => body.location,
+            };
+            match checker.returned.clone() {
+                // The body produces a value at its end and at every `|.`
+                Some(returned) => checker
+                    .unify(returned.clone(), found.clone())
+                    .unwrap_or_else(|| {
+                        checker.mismatch(location, &returned, &found);
+                        returned
+                    }),
+                None if !checker.compatible(&checker.returns, &found) => {
+                    checker.return_mismatch(location, &found);
+                    found
+                }
+                None => found,
+            }
+        });
+        let returns = self
+            .unify(returns.as_ref().clone(), found)
+            .unwrap_or_else(|| returns.as_ref().clone());
+        Type::Function(params.clone(), Box::new(returns))
+    }
+
+    /// Runs `check` with `returns` as the type `|.` must return, as it is inside a closure. When
+    /// `returns` is not known the values returned with `|.` are collected in
+    /// [Checker::returned] instead
+    fn with_returns<T>(&mut self, returns: Type, check: impl FnOnce(&mut Self) -> T) -> T {
+        let returned = matches!(returns, Type::Unknown).then_some(Type::Never);
+        let outer_returned = std::mem::replace(&mut self.returned, returned);
+        let outer_returns = std::mem::replace(&mut self.returns, returns);
+        let outer_location = self.returns_location.take();
+        let result = check(self);
+        self.returns = outer_returns;
+        self.returns_location = outer_location;
+        self.returned = outer_returned;
+        result
+    }
+
+    /// Checks a call to `Std::CLI::parse_args`, which fills in the `data Args` of the calling
+    /// module so its fields must be types that can be read from the command line
+    fn parse_args(&mut self, args: Vec<(Type, Span)>, location: Span) -> Type {
+        if !args.is_empty() {
+            self.error(
+                location,
+                CompileErrorEnum::ArgumentCount {
+                    name: "Std::CLI::parse_args".into(),
+                    expected: 0,
+                    found: args.len(),
+                },
+            );
+        }
+
+        let mut path = self.program.modules()[self.module].name.clone();
+        path.push("Args".into());
+        let Some(fields) = self.records.get(&path).cloned() else {
+            self.error(location, CompileErrorEnum::UnknownType("Args".into()));
+            return Type::Result(Box::new(Type::Unknown));
+        };
+        for ty in fields.values() {
+            if !matches!(ty, Type::Bool | Type::String | Type::Integer(_)) {
+                self.error(
+                    location,
+                    CompileErrorEnum::TypeMismatch {
+                        expected: "Bool, String or an integer".into(),
+                        found: ty.to_string(),
+                    },
+                );
+            }
+        }
+        Type::Result(Box::new(Type::Named(path, Vec::new())))
+    }
+
+    /// Checks a call to one of [ITERATION_BUILTINS], the function argument must accept the
+    /// elements of the range or list, and the accumulator as well for `fold`
+    fn iteration(&mut self, name: &str, args: Vec<(Type, Span)>, location: Span) -> Type {
+        let expected = if name == "fold" { 3 } else { 2 };
+        if args.len() != expected {
+            self.error(
+                location,
+                CompileErrorEnum::ArgumentCount {
+                    name: name.into(),
+                    expected,
+                    found: args.len(),
+                },
+            );
+            return Type::Unknown;
+        }
+
+        let (iterable, iterable_location) = &args[0];
+        let element = iterable.element().unwrap_or_else(|| {
+            self.error(
+                *iterable_location,
+                CompileErrorEnum::TypeMismatch {
+                    expected: "Range or List".into(),
+                    found: iterable.to_string(),
+                },
+            );
+            Type::Unknown
+        });
+        let expected = iteration_function(name, &element, &args);
+
+        let (function, function_location) = &args[args.len() - 1];
+        if !self.compatible(&expected, function) {
+            self.mismatch(*function_location, &expected, function);
+        }
+        let returns = match function {
+            Type::Function(_, returns) => returns.as_ref().clone(),
+            _ => Type::Unknown,
+        };
+
+        match name {
+            "map" => Type::List(Box::new(returns)),
+            "filter" => Type::List(Box::new(element)),
+            "fold" => self
+                .unify(args[1].0.clone(), returns)
+                .unwrap_or_else(|| args[1].0.clone()),
+            _ => Type::Unit,
+        }
+    }
+
+    /// The type of the function named by `path` as a value, [None] if naming it calls it because
+    /// it takes no parameters
+    fn function_type(&self, path: &Path) -> Option<Type> {
+        let callee = self.program.lookup(self.module, &segments(path))?;
+        let takes_params = match &callee {
+            Callee::Func { func, .. } | Callee::Method { func, .. } => !func.params.is_empty(),
+            Callee::TraitMethod { method, .. } => !method.params.is_empty(),
+            _ => false,
+        };
+        if !takes_params {
+            return None;
+        }
+        // A generic function used as a value is not called, so nothing pins down its type
+        // parameters
+        let signature = self.instantiate(self.callee_signature(&callee)?, &[]);
+        Some(Type::Function(
+            signature.params.unwrap_or_default(),
+            Box::new(signature.returns),
+        ))
+    }
+
+    fn expr(&mut self, expr: &Expr, current: &Type, scope: &mut Scope) -> Type {
+        match &expr.kind {
+            ExprKind::Literal(literal) => self.literal(literal, expr.location),
+            ExprKind::Current => current.clone(),
+            ExprKind::Path(path) => match lookup(path, scope) {
+                Some(ty) => ty.clone(),
+                None => match self.function_type(path) {
+                    Some(ty) => ty,
+                    None => self.call(path, Vec::new(), expr.location),
+                },
+            },
+            ExprKind::Call(path, args) => {
+                let args = self.args(path, Vec::new(), args, current, scope);
+                self.call(path, args, expr.location)
+            }
+            ExprKind::Binary(op, lhs, rhs) => {
+                let lhs = self.expr(lhs, current, scope);
+                let rhs = self.expr(rhs, current, scope);
+                self.binary(*op, lhs, rhs, expr.location)
+            }
+            ExprKind::Section(op, operand) => {
+                let rhs = self.expr(operand, current, scope);
+                self.section(*op, current, rhs, expr.location)
+            }
+            ExprKind::Not(operand) => {
+                let found = self.expr(operand, current, scope);
+                if !self.compatible(&Type::Bool, &found) {
+                    self.mismatch(operand.location, &Type::Bool, &found);
+                }
+                Type::Bool
+            }
+            ExprKind::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let start = self.expr(start, current, scope);
+                let end = self.expr(end, current, scope);
+                self.range(start, end, *inclusive, expr.location)
+            }
+            ExprKind::Record(fields) => Type::Record(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.name.clone(), self.expr(value, current, scope)))
+                    .collect(),
+            ),
+            ExprKind::Update(field, value) => {
+                let found = self.expr(value, current, scope);
+                if current.is_unknown() {
+                    return current.clone();
+                }
+                if let Type::Ref(_) = current {
+                    self.error(
+                        expr.location,
+                        CompileErrorEnum::Immutable(current.to_string()),
+                    );
+                    return current.clone();
+                }
+                let Some(fields) = self.fields(current) else {
+                    self.error(
+                        expr.location,
+                        CompileErrorEnum::UnknownField {
+                            ty: current.to_string(),
+                            field: field.name.clone(),
+                        },
+                    );
+                    return current.clone();
+                };
+                match fields.get(&field.name) {
+                    Some(expected) if !self.compatible(expected, &found) => {
+                        self.mismatch(value.location, expected, &found)
+                    }
+                    Some(_) => {}
+                    None => self.error(
+                        field.location,
+                        CompileErrorEnum::UnknownField {
+                            ty: current.to_string(),
+                            field: field.name.clone(),
+                        },
+                    ),
+                }
+                current.clone()
+            }
+            ExprKind::Field(record, field) => {
+                let ty = self.expr(record, current, scope);
+                if ty.is_unknown() {
+                    return Type::Unknown;
+                }
+                match self
+                    .fields(&ty)
+                    .and_then(|mut fields| fields.remove(&field.name))
+                {
+                    Some(field) => field,
+                    None => {
+                        self.error(
+                            field.location,
+                            CompileErrorEnum::UnknownField {
+                                ty: ty.to_string(),
+                                field: field.name.clone(),
+                            },
+                        );
+                        Type::Unknown
+                    }
+                }
+            }
+            ExprKind::Group(pipeline) => self.pipeline(pipeline, current.clone(), scope),
+            ExprKind::Closure(body) => self.closure(body, None, scope),
+            ExprKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Expr(expr) = part {
+                        let ty = self.expr(expr, current, scope);
+                        self.displayable(&ty, expr.location);
+                    }
+                }
+                Type::String
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: Type, rhs: Type, location: Span) -> Type {
+        self.operator(op, &lhs, &rhs).unwrap_or_else(|| {
+            self.error(
+                location,
+                CompileErrorEnum::InvalidOperands {
+                    op: op.symbol(),
+                    lhs: lhs.to_string(),
+                    rhs: rhs.to_string(),
+                },
+            );
+            invalid_operator(op)
+        })
+    }
+
+    /// Checks an operator section such as `|> + n` or `|? < 1`, which applies `op` to the pipe
+    /// value and `rhs`
+    fn section(&mut self, op: BinaryOp, value: &Type, rhs: Type, location: Span) -> Type {
+        self.operator(op, value, &rhs).unwrap_or_else(|| {
+            self.error(
+                location,
+                CompileErrorEnum::InvalidSection {
+                    op: op.symbol(),
+                    value: value.to_string(),
+                    rhs: rhs.to_string(),
+                },
+            );
+            invalid_operator(op)
+        })
+    }
+
+    /// The type `op` produces for operands of type `lhs` and `rhs`, [None] if it does not apply
+    /// to them
+    fn operator(&self, op: BinaryOp, lhs: &Type, rhs: &Type) -> Option<Type> {
+        if lhs.is_unknown() || rhs.is_unknown() {
+            return Some(match (op.is_boolean(), lhs.is_unknown()) {
+                (true, _) => Type::Bool,
+                (false, true) => rhs.clone(),
+                (false, false) => lhs.clone(),
+            });
+        }
+
+        match (op, lhs, rhs) {
+            (BinaryOp::Or | BinaryOp::And, Type::Bool, Type::Bool) => Some(Type::Bool),
+            (BinaryOp::Equal | BinaryOp::NotEqual, _, _) => {
+                self.compatible(lhs, rhs).then_some(Type::Bool)
+            }
+            (
+                BinaryOp::LessThan
+                | BinaryOp::GreaterThan
+                | BinaryOp::LessEqual
+                | BinaryOp::GreaterEqual,
+                Type::String,
+                Type::String,
+            ) => Some(Type::Bool),
+            (BinaryOp::Plus, Type::String, Type::String) => Some(Type::String),
+            (BinaryOp::Or | BinaryOp::And, _, _) => None,
+            _ if lhs.is_numeric() && rhs.is_numeric() => self
+                .unify(lhs.clone(), rhs.clone())
+                .map(|unified| if op.is_boolean() { Type::Bool } else { unified }),
+            // Other types get `+` and the orderings from their `Add` and `Compare` implementations
+            (BinaryOp::Plus, _, _)
+                if self.implements(lhs, &["Add".into()])
+                    && self.compatible(lhs.owned(), rhs.owned()) =>
+            {
+                Some(lhs.owned().clone())
+            }
+            (
+                BinaryOp::LessThan
+                | BinaryOp::GreaterThan
+                | BinaryOp::LessEqual
+                | BinaryOp::GreaterEqual,
+                _,
+                _,
+            ) if self.implements(lhs, &["Compare".into()])
+                && self.compatible(lhs.owned(), rhs.owned()) =>
+            {
+                Some(Type::Bool)
+            }
+            _ => None,
+        }
+    }
+
+    /// The type of `start..end`, both ends must be integers of the same type
+    fn range(&mut self, start: Type, end: Type, inclusive: bool, location: Span) -> Type {
+        let integer =
+            |ty: &Type| matches!(ty, Type::Integer(_) | Type::IntegerLiteral(_)) || ty.is_unknown();
+        match self.unify(start.clone(), end.clone()) {
+            Some(element) if integer(&start) && integer(&end) => Type::Range(Box::new(element)),
+            _ => {
+                self.error(
+                    location,
+                    CompileErrorEnum::InvalidOperands {
+                        op: if inclusive { "..=" } else { ".." },
+                        lhs: start.to_string(),
+                        rhs: end.to_string(),
+                    },
+                );
+                Type::Range(Box::new(Type::Unknown))
+            }
+        }
+    }
+
+    /// Checks that `pattern` can match a value of type `ty`, adding any bindings to the innermost
+    /// scope
+    fn pattern(&mut self, pattern: &Pattern, ty: &Type, scope: &mut Scope) {
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::Binding(name) => {
+                scope
+                    .last_mut()
+                    .expect("Patterns are always checked inside a scope")
+                    .insert(name.name.clone(), ty.clone());
+            }
+            PatternKind::Literal(literal) => {
+                let found = self.literal(literal, pattern.location);
+                if !self.compatible(ty, &found) {
+                    self.mismatch(pattern.location, ty, &found);
+                }
+            }
+            PatternKind::Compare(op, expr) => {
+                let rhs = self.expr(expr, ty, scope);
+                self.section(*op, ty, rhs, pattern.location);
+            }
+            PatternKind::Variant(path, patterns) => {
+                let program = self.program;
+                let args = match program.lookup(self.module, &segments(path)) {
+                    Some(Callee::Variant {
+                        module,
+                        r#enum,
+                        variant,
+                    }) => {
+                        let (enum_path, args) = self.variant(module, r#enum, variant);
+                        // The payload types follow the type arguments of the matched value
+                        let bindings = match ty.owned() {
+                            Type::Named(path, type_args) if *path == enum_path => {
+                                self.bindings(path, type_args)
+                            }
+                            _ => self
+                                .generics
+                                .get(&enum_path)
+                                .into_iter()
+                                .flatten()
+                                .map(|param| (param.clone(), Type::Unknown))
+                                .collect(),
+                        };
+                        let count = self.generics.get(&enum_path).map_or(0, Vec::len);
+                        let found = Type::Named(enum_path, vec![Type::Unknown; count]);
+                        if !self.compatible(ty, &found) {
+                            self.mismatch(pattern.location, ty, &found);
+                        }
+                        Some(
+                            args.iter()
+                                .map(|arg| arg.substitute(&bindings))
+                                .collect::<Vec<_>>(),
+                        )
+                    }
+                    Some(_) => {
+                        self.error(
+                            path.location,
+                            CompileErrorEnum::NotAVariant(segments(path).join("::")),
+                        );
+                        None
+                    }
+                    // Paths that do not resolve have already been reported by the resolver
+                    None => None,
+                };
+
+                match args {
+                    Some(args) if patterns.is_empty() || patterns.len() == args.len() => {
+                        for (pattern, ty) in patterns.iter().zip(&args) {
+                            self.pattern(pattern, ty, scope);
+                        }
+                    }
+                    _ => {
+                        if let Some(args) = args {
+                            self.error(
+                                pattern.location,
+                                CompileErrorEnum::ArgumentCount {
+                                    name: segments(path).join("::"),
+                                    expected: args.len(),
+                                    found: patterns.len(),
+                                },
+                            );
+                        }
+                        for pattern in patterns {
+                            self.pattern(pattern, &Type::Unknown, scope);
+                        }
+                    }
+                }
+            }
+            PatternKind::Tuple(patterns) => {
+                let unknown = vec![Type::Unknown; patterns.len()];
+                let types = match ty.owned() {
+                    Type::Tuple(types) if types.len() == patterns.len() => types.clone(),
+                    ty => {
+                        if !ty.is_unknown() {
+                            self.mismatch(pattern.location, ty, &Type::Tuple(unknown.clone()));
+                        }
+                        unknown
+                    }
+                };
+                for (pattern, ty) in patterns.iter().zip(&types) {
+                    self.pattern(pattern, ty, scope);
+                }
+            }
+            PatternKind::Record(fields) => {
+                if ty.is_unknown() {
+                    for (_, field) in fields {
+                        self.pattern(field, &Type::Unknown, scope);
+                    }
+                    return;
+                }
+                let Some(types) = self.fields(ty) else {
+                    let found = Type::Record(
+                        fields
+                            .iter()
+                            .map(|(name, _)| (name.name.clone(), Type::Unknown))
+                            .collect(),
+                    );
+                    self.mismatch(pattern.location, ty, &found);
+                    return;
+                };
+                for (name, field) in fields {
+                    match types.get(&name.name) {
+                        Some(field_ty) => self.pattern(field, field_ty, scope),
+                        None => self.error(
+                            name.location,
+                            CompileErrorEnum::UnknownField {
+                                ty: ty.to_string(),
+                                field: name.name.clone(),
+                            },
+                        ),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lookup<'s>(path: &Path, scope: &'s Scope) -> Option<&'s Type> {
+    match path.segments.as_slice() {
+        [name] => scope.iter().rev().find_map(|vars| vars.get(&name.name)),
+        _ => None,
+    }
+}
@@ -0,0 +1,1063 @@
+use super::*;
+use crate::{
+    parser::parse,
+    resolver::{resolve, SourceModule},
+    tokenizer::{tokenize, TokenEnum},
+};
+
+fn program(source: &str) -> Program {
+    let ast = parse(tokenize(source).map(|token| token.unwrap())).unwrap();
+    resolve(vec![SourceModule::new(
+        vec!["Test".into()],
+        "test.st".into(),
+        ast,
+    )])
+    .unwrap()
+}
+
+/// The start of each error's span with the error, spans are tested separately
+fn check_source(source: &str) -> Vec<(usize, usize, CompileErrorEnum)> {
+    let errors = match check(&mut program(source)) {
+        Ok(_) => return Vec::new(),
+        Err(ProjectError::Errors(errors)) => errors,
+        Err(error) => vec![error],
+    };
+    errors
+        .into_iter()
+        .map(|error| match error {
+            ProjectError::Compile { error, .. } => (
+                error.span().start.row,
+                error.span().start.column,
+                error.error().clone(),
+            ),
+            error => panic!("Expected a compile error, found {error}"),
+        })
+        .collect()
+}
+
+/// The type given to each unsuffixed integer literal, by the start of its location
+fn program_types(source: &str) -> Vec<((usize, usize), Option<&'static str>)> {
+    let mut program = program(source);
+    let _ = check(&mut program);
+    tokenize(source)
+        .map(|token| token.unwrap())
+        .filter(|token| matches!(token.token, TokenEnum::Integer(_, None)))
+        .map(|token| {
+            let start = (token.span.start.row, token.span.start.column);
+            (start, program.literal_type(0, token.span))
+        })
+        .collect()
+}
+
+fn warnings(source: &str) -> Vec<(usize, usize, CompileWarningEnum)> {
+    check(&mut program(source))
+        .unwrap()
+        .into_iter()
+        .map(|ProjectWarning { warning, .. }| {
+            (
+                warning.span().start.row,
+                warning.span().start.column,
+                warning.warning().clone(),
+            )
+        })
+        .collect()
+}
+
+fn mismatch(
+    row: usize,
+    column: usize,
+    expected: &str,
+    found: &str,
+) -> (usize, usize, CompileErrorEnum) {
+    (
+        row,
+        column,
+        CompileErrorEnum::TypeMismatch {
+            expected: expected.into(),
+            found: found.into(),
+        },
+    )
+}
+
+#[test]
+fn test_check_pipes() {
+    let errors = check_source(
+        r##"data Args {
+            all: Bool;
+        }
+
+        func double (Int32) (Int32) { . * 2 }
+        func describe (Int32, String) (String) { "number" }
+
+        func main () () {
+            read_int |> double |> double |= n;
+            n |> describe "text" |> println;
+            n |> println "#{.} doubled is #{double .}";
+            { all: false } |? { all: true } -> 1 |? _ -> 2 \? |> double;
+            ()
+        }"##,
+    );
+
+    assert_eq!(errors, Vec::new());
+}
+
+#[test]
+fn test_check_mismatches() {
+    let errors = check_source(
+        r#"data Args {
+            all: Bool;
+            count: Count;
+        }
+
+        func double (Int32) (Int32) { . * 2 }
+
+        func main () (Int32) {
+            "text" |> double;
+            double 1 2;
+            read_line |? { all: true } -> 1 \?;
+            true + 1;
+            "text" |.
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            (3, 20, CompileErrorEnum::UnknownType("Count".into())),
+            mismatch(9, 23, "Int32", "String"),
+            (
+                10,
+                13,
+                CompileErrorEnum::ArgumentCount {
+                    name: "double".into(),
+                    expected: 1,
+                    found: 2
+                }
+            ),
+            mismatch(11, 26, "String", "{all: _}"),
+            (
+                12,
+                13,
+                CompileErrorEnum::InvalidOperands {
+                    op: "+",
+                    lhs: "Bool".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+            mismatch(13, 20, "Int32", "String"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_literal_suffixes() {
+    let errors = check_source(
+        r#"func double (Int32) (Int32) { . * 2 }
+
+        func main () () {
+            10i32 |> double;
+            10 |> double;
+            10i64 |> double;
+            1.5f32 |> double;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(6, 22, "Int32", "Int64"),
+            mismatch(7, 23, "Int32", "Float32"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_literal_types() {
+    let source = r#"func small (Int8) () { () }
+
+        func main () () {
+            127 |> small;
+            128 |> small;
+            100 |= x;
+            x * 2 |> small;
+            2147483648 |> println;
+            2147483648 + 0.5 |> println;
+            1 |> + 9223372036854775807i64;
+        }"#;
+    let out_of_range = |row, column, literal: &str, ty| {
+        (
+            row,
+            column,
+            CompileErrorEnum::NumberOutOfRange {
+                literal: literal.into(),
+                ty,
+            },
+        )
+    };
+
+    // Literals have the type they are used as, or `Int32` if nothing says otherwise
+    assert_eq!(
+        check_source(source),
+        vec![
+            out_of_range(5, 13, "128", "Int8"),
+            out_of_range(8, 13, "2147483648", "Int32"),
+        ]
+    );
+    assert_eq!(
+        program_types(source),
+        vec![
+            ((4, 13), Some("Int8")),
+            ((5, 13), None),
+            ((6, 13), Some("Int8")),
+            ((7, 17), Some("Int8")),
+            ((8, 13), None),
+            ((9, 13), None),
+            ((10, 13), Some("Int64")),
+        ]
+    );
+}
+
+#[test]
+fn test_check_ranges() {
+    let errors = check_source(
+        r#"func double (Int32) (Int32) { . * 2 }
+        func describe (Int32) (String) { "number" }
+        func even (Int32) (Bool) { true }
+        func show (Int32) () { println . }
+
+        func main () () {
+            read_int |= n;
+            0..n |> map double |> filter even |> for_each show;
+            0..=10 |> map describe |> map double;
+            1..n |> filter double;
+            "a".."z";
+            n |> map double;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(9, 43, "func (String) (_)", "func (Int32) (Int32)"),
+            mismatch(10, 28, "func (Int32) (Bool)", "func (Int32) (Int32)"),
+            (
+                11,
+                13,
+                CompileErrorEnum::InvalidOperands {
+                    op: "..",
+                    lhs: "String".into(),
+                    rhs: "String".into()
+                }
+            ),
+            (
+                12,
+                18,
+                CompileErrorEnum::TypeMismatch {
+                    expected: "Range or List".into(),
+                    found: "Int32".into()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_closures() {
+    let errors = check_source(
+        r#"func main () () {
+            read_int |= n;
+            "text" |= label;
+            0..n |> map func { . * n } |> map func { . + label };
+            0..n |> filter func { . * 2 };
+            0..n |> fold "" func { label };
+            0..n |> fold 0 func { |? (total, i) -> total + i \? } |> + label;
+            0..n |> fold 0 func { |? (total, i, extra) -> total \? };
+            n |> func { . + 1 } |> + label;
+            double |= twice;
+            label |> twice;
+            n |> twice |> + label;
+            read_int
+            |? 0 -> 0..n |> for_each func { |= i |> println label }
+            |? _ -> ()
+            \?;
+        }
+
+        func double (Int32) (Int32) { . * 2 }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                4,
+                54,
+                CompileErrorEnum::InvalidOperands {
+                    op: "+",
+                    lhs: "Int32".into(),
+                    rhs: "String".into()
+                }
+            ),
+            mismatch(5, 35, "Bool", "Int32"),
+            (
+                7,
+                70,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Int32".into(),
+                    rhs: "String".into()
+                }
+            ),
+            mismatch(8, 38, "({integer}, Int32)", "(_, _, _)"),
+            (
+                9,
+                36,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Int32".into(),
+                    rhs: "String".into()
+                }
+            ),
+            mismatch(11, 22, "Int32", "String"),
+            (
+                12,
+                27,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Int32".into(),
+                    rhs: "String".into()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_comparisons() {
+    let errors = check_source(
+        r#"func main () () {
+            read_int |= n;
+            n >= 1 && n != 2 || !(n == 3);
+            n <= "text";
+            n == "text";
+            n && true;
+            !n;
+            read_line |? <= 1 -> 1 |? _ -> 2 \?;
+        }"#,
+    );
+
+    let invalid = |row, column, op, lhs: &str, rhs: &str| {
+        (
+            row,
+            column,
+            CompileErrorEnum::InvalidOperands {
+                op,
+                lhs: lhs.into(),
+                rhs: rhs.into(),
+            },
+        )
+    };
+    assert_eq!(
+        errors,
+        vec![
+            invalid(4, 13, "<=", "Int32", "String"),
+            invalid(5, 13, "==", "Int32", "String"),
+            invalid(6, 13, "&&", "Int32", "Bool"),
+            mismatch(7, 14, "Bool", "Int32"),
+            (
+                8,
+                26,
+                CompileErrorEnum::InvalidSection {
+                    op: "<=",
+                    value: "String".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_sections() {
+    let errors = check_source(
+        r#"func main () () {
+            read_int |> + 1 |> * 2 |> >= 3 |> && true;
+            read_line |> + "!" |> - 1;
+            read_int |> && true;
+            read_line |? < 1 -> 1 |? _ -> 2 \?;
+        }"#,
+    );
+
+    let invalid = |row, column, op, value: &str, rhs: &str| {
+        (
+            row,
+            column,
+            CompileErrorEnum::InvalidSection {
+                op,
+                value: value.into(),
+                rhs: rhs.into(),
+            },
+        )
+    };
+    assert_eq!(
+        errors,
+        vec![
+            invalid(3, 35, "-", "String", "{integer}"),
+            invalid(4, 25, "&&", "Int32", "Bool"),
+            invalid(5, 26, "<", "String", "{integer}"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_returns() {
+    let errors = check_source(
+        r#"func sign (Int32) (String) {
+            |? < 0 -> (|? < 10 -> "negative" |. |? _ -> 1 |. \?)
+            |? _ -> ()
+            \?;
+            "positive"
+        }
+        func same (Int32, Int32) (Int32, Int32) { |. }
+        func first (Int32, Int32) (Int32) { |. }
+        func main () () {
+            0..3 |> map func { |? 0 -> "zero" |. |? _ -> () \?; "other" } |> map func { . + 1 };
+            0..3 |> map func { |? 0 -> 1 |. |? _ -> () \?; "other" };
+            1 |.;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(2, 59, "String", "{integer}"),
+            mismatch(8, 45, "Int32", "(Int32, Int32)"),
+            (
+                10,
+                89,
+                CompileErrorEnum::InvalidOperands {
+                    op: "+",
+                    lhs: "String".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+            mismatch(11, 60, "{integer}", "String"),
+            mismatch(12, 15, "()", "{integer}"),
+        ]
+    );
+    assert_eq!(
+        warnings("func main () () { () |. |> println; }"),
+        vec![(1, 25, CompileWarningEnum::UnreachableStage)]
+    );
+}
+
+#[test]
+fn test_check_results() {
+    let source = r#"
+        func parse (String) (Result<Int32>) {
+            |? "" -> fail "empty"
+            |? _ -> 1
+            \?
+        }
+
+        func first (String) (Result<Int32>) {
+            |> parse |> + 1 |.
+        }
+
+        func unwrap (String) (Int32) {
+            |> parse |! 0
+        }
+
+        func main () () {
+            "1" |> first |! 0 |> + 1 |> println;
+            "1" |> parse;
+            "" |> parse |! func { 0 } |> + 1 |> println;
+        }"#;
+    assert_eq!(check_source(source), Vec::new());
+    assert_eq!(
+        warnings(source),
+        vec![(18, 13, CompileWarningEnum::UnhandledError)]
+    );
+
+    let errors = check_source(
+        r#"func parse (String) (Result<Int32>) { fail "no" }
+        func bad (String) (Int32) {
+            |> parse |> + 1 |.
+        }
+        func main () () {
+            "1" |> parse |> + "x";
+            "1" |> parse |! func { . + 1 };
+        }
+        func args (Result) (Result<Int32, String>) { 1 }
+        func plain (Int32<Bool>) () {}"#,
+    );
+    let count = |row, column, ty: &str, expected, found| {
+        (
+            row,
+            column,
+            CompileErrorEnum::TypeArgumentCount {
+                ty: ty.into(),
+                expected,
+                found,
+            },
+        )
+    };
+    assert_eq!(
+        errors,
+        vec![
+            count(9, 20, "Result", 1, 0),
+            count(9, 29, "Result", 1, 2),
+            count(10, 21, "Int32", 0, 1),
+            mismatch(3, 29, "Int32", "Result<Int32>"),
+            (
+                6,
+                29,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Int32".into(),
+                    rhs: "String".into(),
+                }
+            ),
+            (
+                7,
+                36,
+                CompileErrorEnum::InvalidOperands {
+                    op: "+",
+                    lhs: "String".into(),
+                    rhs: "{integer}".into(),
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_records() {
+    let errors = check_source(
+        r#"data Point {
+            x: Int64;
+            y: Int64;
+        }
+
+        func origin () (Point) { {x: 0, y: 0} }
+        func broken () (Point) { {x: 0} }
+
+        func main () () {
+            origin |> .{x} 1 |> .{z} 2 |> .{y} "far";
+            origin |= point;
+            {point, name: "a"} |= named;
+            named.point.x + point.y |> + .z;
+            named.name |> - 1;
+            named
+            |? {point: {x: 0}, name} -> name
+            |? {point: {w}} -> "w"
+            |? _ -> "other"
+            \?;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(7, 34, "Point", "{x: {integer}}"),
+            (
+                10,
+                35,
+                CompileErrorEnum::UnknownField {
+                    ty: "Point".into(),
+                    field: "z".into()
+                }
+            ),
+            mismatch(10, 48, "Int64", "String"),
+            (
+                13,
+                43,
+                CompileErrorEnum::UnknownField {
+                    ty: "Int64".into(),
+                    field: "z".into()
+                }
+            ),
+            (
+                14,
+                27,
+                CompileErrorEnum::InvalidSection {
+                    op: "-",
+                    value: "String".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+            (
+                17,
+                25,
+                CompileErrorEnum::UnknownField {
+                    ty: "Point".into(),
+                    field: "w".into()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_enums() {
+    let errors = check_source(
+        r#"enum Shape {
+            Empty,
+            Circle(Float64),
+            Pair(Int64, Int64),
+        }
+
+        enum Other { One }
+
+        func main () () {
+            Shape::Circle "big" |= shape;
+            Shape::Pair 1 |> Shape::Circle;
+            shape
+            |? Shape::Pair a -> a
+            |? Other::One -> 1
+            |? Std::CLI::parse_args -> 1
+            |? Shape::Circle r -> r + 1
+            |? _ -> 0
+            \?;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(10, 27, "Float64", "String"),
+            (
+                11,
+                13,
+                CompileErrorEnum::ArgumentCount {
+                    name: "Shape::Pair".into(),
+                    expected: 2,
+                    found: 1
+                }
+            ),
+            mismatch(11, 30, "Float64", "Shape"),
+            (
+                13,
+                16,
+                CompileErrorEnum::ArgumentCount {
+                    name: "Shape::Pair".into(),
+                    expected: 2,
+                    found: 1
+                }
+            ),
+            mismatch(14, 16, "Shape", "Other"),
+            (
+                15,
+                16,
+                CompileErrorEnum::NotAVariant("Std::CLI::parse_args".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_binding_before_handler() {
+    let source = r#"
+        func check (Int32) (Result<Int32>) { . }
+        func zero (String) (Int32) { 0 }
+        func name (String) (String) { . }
+        func show (Int32) (String) { "shown" }
+
+        func main () () {
+            0 - 3 |> check |= x |! zero;
+            x |> println;
+            0 - 3 |> check |= y |> show |! name;
+            1 |> check |= z;
+            z |> println;
+        }"#;
+    assert_eq!(
+        check_source(source),
+        vec![
+            mismatch(10, 44, "Int32", "String"),
+            (12, 13, CompileErrorEnum::UnknownName("z".into())),
+        ]
+    );
+}
+
+#[test]
+fn test_check_parse_args() {
+    let source = std::fs::read_to_string("../../examples/fib.st").unwrap();
+    assert_eq!(check_source(&source), Vec::new());
+
+    let errors = check_source(
+        r#"data Args {
+            verbose: Bool;
+            name: String;
+            count: Int64;
+            ratio: Float64;
+        }
+
+        func main () () {
+            Std::CLI::parse_args |= args |! panic;
+            args.count + 1 |> + args.name;
+            Std::CLI::parse_args |> + 1;
+        }"#,
+    );
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(9, 13, "Bool, String or an integer", "Float64"),
+            (
+                10,
+                31,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Int64".into(),
+                    rhs: "String".into()
+                }
+            ),
+            mismatch(11, 13, "Bool, String or an integer", "Float64"),
+            (
+                11,
+                37,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "Args".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+        ]
+    );
+
+    assert_eq!(
+        check_source("func main () () { Std::CLI::parse_args; }"),
+        vec![(1, 19, CompileErrorEnum::UnknownType("Args".into()))]
+    );
+
+    // Only the full path names the builtin
+    assert_eq!(
+        check_source(
+            "func main () () {
+                Std::Build::ProjectDescriptor::init |> + 1;
+                Std::CLI::ProjectDescriptor::init |> + 1;
+            }"
+        ),
+        vec![
+            (
+                2,
+                56,
+                CompileErrorEnum::InvalidSection {
+                    op: "+",
+                    value: "ProjectDescriptor".into(),
+                    rhs: "{integer}".into()
+                }
+            ),
+            (
+                3,
+                17,
+                CompileErrorEnum::UnknownName("Std::CLI::ProjectDescriptor::init".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_objects() {
+    let errors = check_source(
+        r#"object Counter {
+            count: Int32;
+            inner: Inner;
+
+            func increment (mut Counter) () { .{count} (.count + 1); }
+            func peek (ref Counter) (Int32) {
+                .{count} 1;
+                . |> Counter::increment;
+                .inner |> Inner::clear;
+                . |> Counter::copy;
+                .count
+            }
+            func copy (Counter) (Counter) { .{count} 0 }
+            func leak (ref Counter) (Counter) { . }
+        }
+
+        object Inner {
+            value: Int32;
+
+            func clear (mut Inner) () { .{value} 0; }
+        }
+
+        data Point { x: Int32; }
+
+        func move (mut Point) () { () }
+
+        func main () () {
+            {count: 0, inner: Inner {value: 1}} |> Counter::increment;
+            Counter {count: "a"} |> Counter::peek;
+        }"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                25,
+                20,
+                CompileErrorEnum::InvalidParamMode {
+                    mode: "mut",
+                    ty: "Point".into()
+                }
+            ),
+            (7, 17, CompileErrorEnum::Immutable("ref Counter".into())),
+            (8, 22, CompileErrorEnum::Immutable("ref Counter".into())),
+            (9, 27, CompileErrorEnum::Immutable("ref Inner".into())),
+            mismatch(14, 49, "Counter", "ref Counter"),
+            mismatch(28, 52, "Counter", "{count: {integer}, inner: Inner}"),
+            mismatch(29, 21, "{count: Int32, inner: Inner}", "{count: String}"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_generics() {
+    let errors = check_source(
+        r#"data Pair<A, B> { first: A; second: B; }
+
+        enum Option<T> { None, Some(T) }
+
+        func first<A, B> (Pair<A, B>) (A) { .first }
+        func increment<T> (T) (T) { . + 1 }
+        func make<T> () (T) { () }
+        func count<T> (List<T>) (Int32) { 0 }
+        func broken (Pair<Int32>) () { () }
+        func label (Pair<Int32, String>) (String) { .second + .first }
+
+        func main () () {
+            {first: 1, second: "a"} |> first |> + "b";
+            Option::Some 1 |= some;
+            some
+            |? Option::Some value -> value + "c"
+            |? Option::None -> 0
+            \?;
+            Option::None |= none;
+            none |> + 1;
+            0..3 |> map func { . } |> count |> + "d";
+        }"#,
+    );
+
+    let section = |row, column, value: &str, rhs: &str| {
+        (
+            row,
+            column,
+            CompileErrorEnum::InvalidSection {
+                op: "+",
+                value: value.into(),
+                rhs: rhs.into(),
+            },
+        )
+    };
+    let operands = |row, column, lhs: &str, rhs: &str| {
+        (
+            row,
+            column,
+            CompileErrorEnum::InvalidOperands {
+                op: "+",
+                lhs: lhs.into(),
+                rhs: rhs.into(),
+            },
+        )
+    };
+    assert_eq!(
+        errors,
+        vec![
+            (
+                7,
+                19,
+                CompileErrorEnum::UninferableTypeParameter {
+                    param: "T".into(),
+                    func: "make".into()
+                }
+            ),
+            (
+                9,
+                22,
+                CompileErrorEnum::TypeArgumentCount {
+                    ty: "Pair".into(),
+                    expected: 2,
+                    found: 1
+                }
+            ),
+            operands(6, 37, "T", "{integer}"),
+            mismatch(7, 31, "T", "()"),
+            operands(10, 53, "String", "Int32"),
+            section(13, 49, "{integer}", "String"),
+            operands(16, 38, "{integer}", "String"),
+            section(20, 21, "Option<_>", "{integer}"),
+            section(21, 48, "Int32", "String"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_traits() {
+    let errors = check_source(
+        r##"trait Shape { func area (Self) (Float64); }
+
+        data Square { side: Float64; }
+        data Point { x: Int32; }
+
+        impl Shape for Square { func perimeter (Square) (Float64) { 1.0 } }
+        impl Shape for Point { func area (Point) (Int32) { 1 } }
+        impl Shape for Point { func area (Point) (Float64) { 1.0 } }
+        impl Square for Point {}
+        impl Display for Int32 { func display (Int32) (String) { "x" } }
+        impl Add for Point { func add (Point, Point) (Point) { |? ({x: a}, {x: b}) -> {x: a + b} \? } }
+
+        func twice<T> (T) (T) { . + . }
+        func show<T> (T) (String) { "#{.}" }
+        func total<T: Add> (T) (T) { . + . }
+        func label<T: Display + Shape> (T) (String) { "#{.} #{Shape::area .}" }
+        func point (Int32) (Point) { {x: .} }
+
+        func main () () {
+            1 |> point |> total |> Shape::area |> + 1.0;
+            1 |> total |> label;
+            true |> Shape::area;
+        }"##,
+    );
+
+    let not_implemented = |row, column, r#trait: &str, ty: &str| {
+        (
+            row,
+            column,
+            CompileErrorEnum::NotImplemented {
+                r#trait: r#trait.into(),
+                ty: ty.into(),
+            },
+        )
+    };
+    assert_eq!(
+        errors,
+        vec![
+            (
+                6,
+                24,
+                CompileErrorEnum::MissingTraitMethod {
+                    r#trait: "Shape".into(),
+                    ty: "Square".into(),
+                    method: "area".into()
+                }
+            ),
+            (
+                6,
+                38,
+                CompileErrorEnum::UnknownTraitMethod {
+                    r#trait: "Shape".into(),
+                    method: "perimeter".into()
+                }
+            ),
+            mismatch(7, 37, "func (Point) (Float64)", "func (Point) (Int32)"),
+            (
+                8,
+                24,
+                CompileErrorEnum::DuplicateImplementation {
+                    r#trait: "Shape".into(),
+                    ty: "Point".into()
+                }
+            ),
+            (9, 14, CompileErrorEnum::NotATrait("Square".into())),
+            (
+                10,
+                26,
+                CompileErrorEnum::InvalidImplementation("Int32".into())
+            ),
+            (
+                13,
+                33,
+                CompileErrorEnum::InvalidOperands {
+                    op: "+",
+                    lhs: "T".into(),
+                    rhs: "T".into()
+                }
+            ),
+            not_implemented(14, 40, "Display", "T"),
+            not_implemented(21, 27, "Shape", "{integer}"),
+            not_implemented(22, 21, "Shape", "Bool"),
+        ]
+    );
+}
+
+#[test]
+fn test_exhaustiveness() {
+    let warnings = warnings(
+        r#"data Args {
+            all: Bool;
+            count: Int32;
+        }
+
+        enum Color { Red, Green }
+
+        func color () (Color) { color }
+        func args () (Args) { {all: true, count: 1} }
+
+        func main () () {
+            true |? true -> 1 \?;
+            true |? true -> 1 |? false -> 2 \?;
+            args |? { all: true } -> 1 \?;
+            args |? { all: true } -> 1 |? { all: false, count: 0 } -> 2 \?;
+            args |? { all: true } -> 1 |? { all: false } -> 2 \?;
+            read_int |? < 1 -> 1 |? 2 -> 2 \?;
+            read_int |? < 1 -> 1 |? _ -> 2 \?;
+            color |? _ -> 1 \?;
+            read_int |? x -> 1 |? _ -> 2 |? < 1 -> 3 \?;
+            color |? Color::Red -> 1 \?;
+            shape |? Shape::Circle Color::Red -> 1 |? Shape::Pair -> 2 \?;
+            shape |? Shape::Circle _ -> 1 |? Shape::Pair true _ -> 2 \?;
+            shape |? Shape::Circle -> 1 |? Shape::Pair _ _ -> 2 |? Shape::Circle _ -> 3 \?;
+        }
+
+        enum Shape { Circle(Color), Pair(Bool, Bool) }
+        func shape () (Shape) { Shape::Circle Color::Red }"#,
+    );
+
+    assert_eq!(
+        warnings,
+        vec![
+            (12, 18, CompileWarningEnum::NonExhaustive("false".into())),
+            (
+                14,
+                18,
+                CompileWarningEnum::NonExhaustive("{all: false, count: _}".into())
+            ),
+            (
+                15,
+                18,
+                CompileWarningEnum::NonExhaustive("{all: false, count: _}".into())
+            ),
+            (17, 22, CompileWarningEnum::NonExhaustive("_".into())),
+            (20, 32, CompileWarningEnum::UnreachableArm),
+            (20, 42, CompileWarningEnum::UnreachableArm),
+            (
+                21,
+                19,
+                CompileWarningEnum::NonExhaustive("Color::Green".into())
+            ),
+            (
+                22,
+                19,
+                CompileWarningEnum::NonExhaustive("Shape::Circle Color::Green".into())
+            ),
+            (
+                23,
+                19,
+                CompileWarningEnum::NonExhaustive("Shape::Pair false _".into())
+            ),
+            (24, 65, CompileWarningEnum::UnreachableArm),
+        ]
+    );
+}
+
+#[test]
+fn test_error_spans() {
+    let source = "func main () (Int32) { \"some text\" }";
+    let Err(ProjectError::Compile { error, .. }) = check(&mut program(source)) else {
+        panic!("Expected a single compile error");
+    };
+
+    assert_eq!(&source[error.span().range()], "\"some text\"");
+    assert_eq!(error.labels().len(), 1);
+    assert_eq!(&source[error.labels()[0].span.range()], "Int32");
+}
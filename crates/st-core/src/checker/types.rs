@@ -0,0 +1,201 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+/// Integer types that can be named in signatures
+pub const INTEGER_TYPES: &[&str] = &[
+    "Int8", "Int16", "Int32", "Int64", "Int128", "UInt8", "UInt16", "UInt32", "UInt64",
+];
+
+/// Floating point types that can be named in signatures
+pub const FLOAT_TYPES: &[&str] = &["Float32", "Float64"];
+
+/// Types provided by the language that take a single type argument, `List<Int32>`
+pub const GENERIC_TYPES: &[&str] = &["Result", "List", "Range"];
+
+/// The static type of a value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Unit,
+    Bool,
+    String,
+    Integer(&'static str),
+    Float(&'static str),
+    /// An integer literal, which can be used as any integer or float type. It takes the type it
+    /// is used as, the index is the variable the checker infers that type with
+    IntegerLiteral(usize),
+    /// A float literal, which can be used as any float type
+    FloatLiteral,
+    Tuple(Vec<Type>),
+    /// A declared `data`, `object` or `enum`, identified by its full path, with the types given
+    /// for its type parameters
+    Named(Vec<String>, Vec<Type>),
+    /// A type parameter of the declaration being checked, `T` in `func first<T> (List<T>) (T)`.
+    /// Inside the declaration it could be any type, so it is only compatible with itself
+    Param(String),
+    /// An anonymous record, `{ name: "x" }`
+    Record(BTreeMap<String, Type>),
+    /// `start..end`, with the type of its elements
+    Range(Box<Type>),
+    /// The values produced by iterating, such as piping a range into `map`
+    List(Box<Type>),
+    /// A function used as a value, with its parameter and return types
+    Function(Vec<Type>, Box<Type>),
+    /// `Result<T>`, a value of type `T` or an error raised with `fail`
+    Result(Box<Type>),
+    /// An object borrowed with `ref`, its fields can be read but not changed
+    Ref(Box<Type>),
+    /// The type of a stage that never produces a value, such as `|.` or a call to `panic`
+    Never,
+    /// A type that could not be determined, it is compatible with every other type so a single
+    /// mistake is only reported once
+    Unknown,
+}
+
+impl Type {
+    /// The type of the pipe value at the start of a function taking `params`, which is also the
+    /// type a function returning `params` produces
+    pub fn from_list(mut types: Vec<Type>) -> Type {
+        match types.len() {
+            0 => Type::Unit,
+            1 => types.remove(0),
+            _ => Type::Tuple(types),
+        }
+    }
+
+    /// Looks up a type that is provided by the language rather than declared in the program
+    pub fn builtin(name: &str) -> Option<Type> {
+        if let Some(name) = INTEGER_TYPES.iter().find(|ty| **ty == name) {
+            return Some(Type::Integer(name));
+        }
+        if let Some(name) = FLOAT_TYPES.iter().find(|ty| **ty == name) {
+            return Some(Type::Float(name));
+        }
+        match name {
+            "Bool" => Some(Type::Bool),
+            "String" => Some(Type::String),
+            _ => None,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Type::Integer(_) | Type::Float(_) | Type::IntegerLiteral(_) | Type::FloatLiteral
+        )
+    }
+
+    /// The type of the elements produced by iterating over a value of this type
+    pub fn element(&self) -> Option<Type> {
+        match self {
+            Type::Range(element) | Type::List(element) => Some(element.as_ref().clone()),
+            _ if self.is_unknown() => Some(Type::Unknown),
+            _ => None,
+        }
+    }
+
+    /// The type of the value a `Result` holds when it succeeds, or the type itself otherwise
+    pub fn ok(&self) -> &Type {
+        match self {
+            Type::Result(ok) => ok,
+            _ => self,
+        }
+    }
+
+    /// The type of the object behind a `ref`, or the type itself otherwise
+    pub fn owned(&self) -> &Type {
+        match self {
+            Type::Ref(ty) => ty,
+            _ => self,
+        }
+    }
+
+    /// Replaces the type parameters that have a binding, others are left as they are
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> Type {
+        let all = |types: &[Type]| types.iter().map(|ty| ty.substitute(bindings)).collect();
+        match self {
+            Type::Param(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::Named(path, args) => Type::Named(path.clone(), all(args)),
+            Type::Tuple(types) => Type::Tuple(all(types)),
+            Type::Record(fields) => Type::Record(
+                fields
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), ty.substitute(bindings)))
+                    .collect(),
+            ),
+            Type::Range(ty) => Type::Range(Box::new(ty.substitute(bindings))),
+            Type::List(ty) => Type::List(Box::new(ty.substitute(bindings))),
+            Type::Result(ty) => Type::Result(Box::new(ty.substitute(bindings))),
+            Type::Ref(ty) => Type::Ref(Box::new(ty.substitute(bindings))),
+            Type::Function(params, returns) => {
+                Type::Function(all(params), Box::new(returns.substitute(bindings)))
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether the type parameter `name` appears anywhere in the type
+    pub fn mentions(&self, name: &str) -> bool {
+        match self {
+            Type::Param(param) => param == name,
+            Type::Named(_, types) | Type::Tuple(types) => types.iter().any(|ty| ty.mentions(name)),
+            Type::Record(fields) => fields.values().any(|ty| ty.mentions(name)),
+            Type::Range(ty) | Type::List(ty) | Type::Result(ty) | Type::Ref(ty) => {
+                ty.mentions(name)
+            }
+            Type::Function(params, returns) => {
+                params.iter().any(|ty| ty.mentions(name)) || returns.mentions(name)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the type is not known precisely enough to report an error about it
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Type::Unknown | Type::Never)
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Unit => write!(f, "()"),
+            Type::Bool => write!(f, "Bool"),
+            Type::String => write!(f, "String"),
+            Type::Integer(name) | Type::Float(name) => write!(f, "{name}"),
+            Type::IntegerLiteral(_) => write!(f, "{{integer}}"),
+            Type::FloatLiteral => write!(f, "{{float}}"),
+            Type::Tuple(types) => {
+                let types = types.iter().map(Type::to_string).collect::<Vec<_>>();
+                write!(f, "({})", types.join(", "))
+            }
+            Type::Named(path, args) => {
+                write!(f, "{}", path.last().map_or("", String::as_str))?;
+                if !args.is_empty() {
+                    let args = args.iter().map(Type::to_string).collect::<Vec<_>>();
+                    write!(f, "<{}>", args.join(", "))?;
+                }
+                Ok(())
+            }
+            Type::Param(name) => write!(f, "{name}"),
+            Type::Record(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<_>>();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            Type::Range(element) => write!(f, "Range<{element}>"),
+            Type::List(element) => write!(f, "List<{element}>"),
+            Type::Function(params, returns) => {
+                let params = params.iter().map(Type::to_string).collect::<Vec<_>>();
+                write!(f, "func ({}) ({returns})", params.join(", "))
+            }
+            Type::Result(ok) => write!(f, "Result<{ok}>"),
+            Type::Ref(ty) => write!(f, "ref {ty}"),
+            Type::Never => write!(f, "!"),
+            Type::Unknown => write!(f, "_"),
+        }
+    }
+}
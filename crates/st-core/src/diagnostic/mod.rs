@@ -0,0 +1,345 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    error::{CompileError, CompileErrorEnum, ProjectError, ProjectWarning, RuntimeError},
+    tokenizer::{Position, Span},
+};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A span of source text with a message explaining its part in a diagnostic
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+/// Where a diagnostic points in its file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// The whole file, or a directory for errors that are not about a single file
+    File,
+    /// A single position, for errors that do not know the extent of the code they are about
+    Position {
+        row: usize,
+        column: usize,
+    },
+    Span(Span),
+}
+
+/// An error or warning ready to be shown to the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub path: PathBuf,
+    pub location: Location,
+    /// Describes the primary span, may be empty
+    pub label: String,
+    /// Other spans in the same file that explain the diagnostic
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+impl Diagnostic {
+    pub fn new(severity: Severity, path: PathBuf, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            path,
+            location: Location::File,
+            label: String::new(),
+            secondary: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// The diagnostics for every error contained in `error`
+    pub fn from_project_error(error: &ProjectError) -> Vec<Diagnostic> {
+        match error {
+            ProjectError::Errors(errors) => {
+                errors.iter().flat_map(Self::from_project_error).collect()
+            }
+            ProjectError::Compile { path, error } => vec![Self::from_compile_error(path, error)],
+            ProjectError::Runtime { path, error } => vec![Self::from_runtime_error(path, error)],
+            ProjectError::Io { path, error } => vec![Self::new(
+                Severity::Error,
+                path.clone(),
+                format!("Could not read file: {error}"),
+            )],
+            ProjectError::MissingProjectFunc { path } => vec![Self::new(
+                Severity::Error,
+                path.clone(),
+                "Expected a function 'project () (ProjectDescriptor)'",
+            )],
+            ProjectError::InvalidDescriptor { path, reason } => vec![Self::new(
+                Severity::Error,
+                path.clone(),
+                format!("Invalid project descriptor, {reason}"),
+            )],
+            ProjectError::OutsideSource { path, src } => vec![Self::new(
+                Severity::Error,
+                path.clone(),
+                format!("Not inside the source directory {}", src.display()),
+            )],
+            ProjectError::Glob { pattern, error } => vec![Self::new(
+                Severity::Error,
+                pattern.into(),
+                format!("Invalid source directory: {error}"),
+            )],
+        }
+    }
+
+    pub fn from_compile_error(path: &Path, error: &CompileError) -> Self {
+        let mut diagnostic = Self::new(Severity::Error, path.into(), error.error().to_string());
+        diagnostic.location = Location::Span(error.span());
+        diagnostic.secondary = error.labels().to_vec();
+
+        match error.error() {
+            CompileErrorEnum::TypeMismatch { expected, .. } => {
+                diagnostic.label = format!("expected {expected}");
+            }
+            CompileErrorEnum::PrivateItem(_) => diagnostic
+                .notes
+                .push("Mark the item `pub` to use it from other modules".into()),
+            CompileErrorEnum::InvalidEscape(_) => diagnostic
+                .notes
+                .push("The escape sequences are \\n \\t \\r \\0 \\\\ \\\" \\# and \\u{...}, or use a raw string r\"...\"".into()),
+            CompileErrorEnum::InvalidUnicodeEscape(_) => diagnostic.notes.push(
+                "A unicode escape is up to 6 hex digits naming a character, such as \\u{1F600}"
+                    .into(),
+            ),
+            CompileErrorEnum::IncompletePipe(_) => diagnostic
+                .notes
+                .push("The pipe operators are |> |= |? |! |. and \\?".into()),
+            CompileErrorEnum::InvalidSection { op, .. } => diagnostic.notes.push(format!(
+                "The pipe value is the left operand of `{op}`, `|> {op} x` is `|> (. {op} x)`"
+            )),
+            CompileErrorEnum::ImportCycle(_) => diagnostic
+                .notes
+                .push("Modules can not import each other in a cycle".into()),
+            _ => {}
+        }
+        diagnostic
+    }
+
+    /// `path` is used when the error does not know the file it happened in
+    pub fn from_runtime_error(path: &Path, error: &RuntimeError) -> Self {
+        let path = error.file().unwrap_or(path);
+        let mut diagnostic = Self::new(Severity::Error, path.into(), error.error().to_string());
+        let (row, column) = error.position();
+        diagnostic.location = Location::Position { row, column };
+        // Recursion repeats the same call many times, it is listed once with a count
+        diagnostic
+            .notes
+            .extend(error.trace().chunk_by(|a, b| a == b).map(|frames| {
+                let frame = &frames[0];
+                let note = format!(
+                    "in {}, called at {}:{}:{}",
+                    frame.function,
+                    frame.file.display(),
+                    frame.row,
+                    frame.column
+                );
+                match frames.len() {
+                    1 => note,
+                    count => format!("{note} ({count} times)"),
+                }
+            }));
+        diagnostic
+    }
+
+    pub fn from_warning(warning: &ProjectWarning) -> Self {
+        let mut diagnostic = Self::new(
+            Severity::Warning,
+            warning.path.clone(),
+            warning.warning.warning().to_string(),
+        );
+        diagnostic.location = Location::Span(warning.warning.span());
+        diagnostic
+    }
+
+    /// Formats the diagnostic like rustc, quoting the lines of `source` it points at.
+    ///
+    /// `source` is the content of [Diagnostic::path], if it could not be read the diagnostic is
+    /// shown without a snippet. `colour` adds ANSI escape codes for terminals
+    pub fn render(&self, source: Option<&str>, colour: bool) -> String {
+        let paint = |style: &'static str| if colour { style } else { "" };
+        let (severity, style) = match self.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+        let (reset, bold, blue) = (paint(RESET), paint(BOLD), paint(BLUE));
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}{severity}{reset}{bold}: {}{reset}",
+            paint(style),
+            self.message
+        );
+
+        let primary = match (&self.location, source) {
+            (Location::Span(span), _) => Some(*span),
+            (Location::Position { row, column }, Some(source)) => {
+                Some(position_span(source, *row, *column))
+            }
+            _ => None,
+        };
+        let Some(primary) = primary else {
+            let position = match self.location {
+                Location::Position { row, column } => format!(":{row}:{column}"),
+                _ => String::new(),
+            };
+            let _ = writeln!(out, "{blue}-->{reset} {}{position}", self.path.display());
+            self.render_notes(&mut out, "", blue, reset);
+            return out;
+        };
+
+        let mut labels = vec![(primary, self.label.as_str(), true)];
+        labels.extend(
+            self.secondary
+                .iter()
+                .map(|label| (label.span, label.message.as_str(), false)),
+        );
+        labels.sort_by_key(|(span, ..)| span.start.offset);
+
+        let last_row = labels
+            .iter()
+            .map(|(span, ..)| span.end.row)
+            .max()
+            .unwrap_or(primary.start.row);
+        let gutter = " ".repeat(last_row.to_string().len());
+
+        let _ = writeln!(
+            out,
+            "{gutter}{blue}-->{reset} {}:{}:{}",
+            self.path.display(),
+            primary.start.row,
+            primary.start.column
+        );
+
+        let Some(source) = source else {
+            self.render_notes(&mut out, &gutter, blue, reset);
+            return out;
+        };
+        let lines = source.split('\n').collect::<Vec<_>>();
+
+        let _ = writeln!(out, "{gutter} {blue}|{reset}");
+        let mut previous_row = None;
+        for (span, message, is_primary) in labels {
+            let (marker, style) = if is_primary {
+                ('^', paint(style))
+            } else {
+                ('-', blue)
+            };
+
+            let end_row = match span.end.column {
+                // A span ending at the start of a line does not include that line
+                1 if span.end.row > span.start.row => span.end.row - 1,
+                _ => span.end.row,
+            };
+            for row in span.start.row..=end_row {
+                let Some(line) = lines.get(row - 1) else {
+                    break;
+                };
+                let line = line.trim_end_matches('\r');
+
+                if previous_row.is_some_and(|previous| row > previous + 1) {
+                    let _ = writeln!(out, "{blue}...{reset}");
+                }
+                if previous_row != Some(row) {
+                    let _ = writeln!(
+                        out,
+                        "{blue}{row:>width$} |{reset} {line}",
+                        width = gutter.len()
+                    );
+                }
+                previous_row = Some(row);
+
+                let line_length = line.chars().count();
+                // Continuation lines are marked from their first character, not their indentation
+                let start = if row == span.start.row {
+                    span.start.column
+                } else {
+                    line.chars().position(|ch| !ch.is_whitespace()).unwrap_or(0) + 1
+                };
+                let end = if row == span.end.row {
+                    span.end.column
+                } else {
+                    line_length + 1
+                };
+                let width = end.saturating_sub(start).max(1);
+                let message = if row == end_row && !message.is_empty() {
+                    format!(" {message}")
+                } else {
+                    String::new()
+                };
+
+                // Tabs are copied so the markers line up however wide the terminal shows them
+                let padding = line
+                    .chars()
+                    .chain(std::iter::repeat(' '))
+                    .take(start - 1)
+                    .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                    .collect::<String>();
+                let _ = writeln!(
+                    out,
+                    "{gutter} {blue}|{reset} {padding}{style}{}{message}{reset}",
+                    marker.to_string().repeat(width),
+                );
+            }
+        }
+
+        self.render_notes(&mut out, &gutter, blue, reset);
+        out
+    }
+
+    fn render_notes(&self, out: &mut String, gutter: &str, blue: &str, reset: &str) {
+        for note in &self.notes {
+            let _ = writeln!(out, "{gutter} {blue}={reset} note: {note}");
+        }
+    }
+}
+
+/// A single character span at a row and column of `source`
+fn position_span(source: &str, row: usize, column: usize) -> Span {
+    let line_start = source
+        .split_inclusive('\n')
+        .take(row.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let offset = source[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(source.len(), |(offset, _)| line_start + offset);
+
+    Span::new(
+        Position::new(offset, row, column),
+        Position::new(offset + 1, row, column + 1),
+    )
+}
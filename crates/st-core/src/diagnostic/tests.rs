@@ -0,0 +1,196 @@
+use super::*;
+use crate::{error::RuntimeErrorEnum, tokenizer::TokenEnum};
+
+fn span(start: (usize, usize, usize), end: (usize, usize, usize)) -> Span {
+    Span::new(
+        Position::new(start.0, start.1, start.2),
+        Position::new(end.0, end.1, end.2),
+    )
+}
+
+#[test]
+fn test_render_compile_error() {
+    let source =
+        "func double (Int32) (Int32) { . * 2 }\n\nfunc main () () {\n    \"text\" |> double;\n}\n";
+    let error = CompileError::new(
+        span((60, 4, 5), (66, 4, 11)),
+        CompileErrorEnum::TypeMismatch {
+            expected: "Int32".into(),
+            found: "String".into(),
+        },
+    )
+    .with_label(span((13, 1, 14), (18, 1, 19)), "parameter declared here");
+
+    let diagnostic = Diagnostic::from_compile_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Expected type Int32, found String
+ --> main.st:4:5
+  |
+1 | func double (Int32) (Int32) { . * 2 }
+  |              ----- parameter declared here
+...
+4 |     \"text\" |> double;
+  |     ^^^^^^ expected Int32
+"
+    );
+}
+
+#[test]
+fn test_render_multiline_span() {
+    let source = "func main () () {\n    1\n        |> println;\n}\n";
+    let error = CompileError::new(
+        span((22, 2, 5), (42, 3, 19)),
+        CompileErrorEnum::UnknownPath("println".into()),
+    );
+
+    let diagnostic = Diagnostic::from_compile_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Unknown module or item println
+ --> main.st:2:5
+  |
+2 |     1
+  |     ^
+3 |         |> println;
+  |         ^^^^^^^^^^
+"
+    );
+}
+
+#[test]
+fn test_render_tabs() {
+    let source = "func main () () {\n\tfoo 1;\n}\n";
+    let error = CompileError::new(
+        span((19, 2, 2), (22, 2, 5)),
+        CompileErrorEnum::UnknownPath("foo".into()),
+    );
+
+    let diagnostic = Diagnostic::from_compile_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Unknown module or item foo
+ --> main.st:2:2
+  |
+2 | \tfoo 1;
+  | \t^^^
+"
+    );
+}
+
+#[test]
+fn test_render_notes() {
+    let source = "using Other::secret;\n";
+    let error = CompileError::new(
+        span((6, 1, 7), (19, 1, 20)),
+        CompileErrorEnum::PrivateItem("Other::secret".into()),
+    );
+
+    let diagnostic = Diagnostic::from_compile_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Other::secret is private to its module
+ --> main.st:1:7
+  |
+1 | using Other::secret;
+  |       ^^^^^^^^^^^^^
+  = note: Mark the item `pub` to use it from other modules
+"
+    );
+}
+
+#[test]
+fn test_render_syntax_error() {
+    let source = "func main () () {\n    1 +;\n}\n";
+    let error = CompileError::new(
+        span((25, 2, 8), (26, 2, 9)),
+        CompileErrorEnum::ExpectedOneOf {
+            expected: [TokenEnum::Integer(0, None), TokenEnum::OpenBrace][..].into(),
+            found: TokenEnum::SemiColon,
+        },
+    );
+
+    let diagnostic = Diagnostic::from_compile_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Expected one of '<integer>', '(', found ';'
+ --> main.st:2:8
+  |
+2 |     1 +;
+  |        ^
+"
+    );
+    assert_eq!(
+        CompileErrorEnum::ExpectedToken {
+            expected: TokenEnum::CloseBrace.into(),
+            found: TokenEnum::Identifier("main".into()),
+        }
+        .to_string(),
+        "Expected ')', found '<identifier>'"
+    );
+}
+
+#[test]
+fn test_render_runtime_error() {
+    let source = "func main () () {\n    panic \"oh no\";\n}\n";
+    let error = RuntimeError::new(2, 5, RuntimeErrorEnum::Panic("oh no".into()));
+
+    let diagnostic = Diagnostic::from_runtime_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(Some(source), false),
+        "error: Panicked: oh no
+ --> main.st:2:5
+  |
+2 |     panic \"oh no\";
+  |     ^
+"
+    );
+    assert_eq!(
+        diagnostic.render(None, false),
+        "error: Panicked: oh no\n--> main.st:2:5\n"
+    );
+}
+
+#[test]
+fn test_render_stack_trace() {
+    let error = RuntimeError::new(2, 5, RuntimeErrorEnum::Panic("oh no".into()))
+        .called_from("check", "main.st", 6, 9)
+        .called_from("func { ... }", "lib.st", 7, 20);
+
+    let diagnostic = Diagnostic::from_runtime_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(None, false),
+        "error: Panicked: oh no
+--> main.st:2:5
+ = note: in check, called at main.st:6:9
+ = note: in func { ... }, called at lib.st:7:20
+"
+    );
+}
+
+#[test]
+fn test_render_recursive_stack_trace() {
+    let error = RuntimeError::new(4, 30, RuntimeErrorEnum::StackOverflow(3))
+        .called_from("down", "main.st", 4, 30)
+        .called_from("down", "main.st", 4, 30)
+        .called_from("down", "main.st", 9, 25);
+
+    let diagnostic = Diagnostic::from_runtime_error(Path::new("main.st"), &error);
+    assert_eq!(
+        diagnostic.render(None, false),
+        "error: Stack overflow, more than 3 function calls are nested
+--> main.st:4:30
+ = note: in down, called at main.st:4:30 (2 times)
+ = note: in down, called at main.st:9:25
+"
+    );
+}
+
+#[test]
+fn test_render_colour() {
+    let diagnostic = Diagnostic::new(Severity::Warning, "main.st".into(), "careful");
+    assert_eq!(
+        diagnostic.render(None, true),
+        format!("{YELLOW}warning{RESET}{BOLD}: careful{RESET}\n{BLUE}-->{RESET} main.st\n")
+    );
+}
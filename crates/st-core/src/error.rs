@@ -1,18 +1,48 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-use crate::tokenizer::TokenEnum;
+use crate::{
+    diagnostic::Label,
+    tokenizer::{Span, TokenEnum},
+};
 
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
-#[error("{row}:{column}: {error}")]
+#[error("{}:{}: {error}", span.start.row, span.start.column)]
 pub struct CompileError {
-    row: usize,
-    column: usize,
-    error: CompileErrorEnum,
+    span: Span,
+    /// Boxed to keep `Result<_, CompileError>` small now that the error carries a whole span
+    error: Box<CompileErrorEnum>,
+    /// Other code that explains the error, such as the declaration of an expected type
+    labels: Vec<Label>,
 }
 
 impl CompileError {
-    pub fn new(row: usize, column: usize, error: CompileErrorEnum) -> Self {
-        Self { row, column, error }
+    pub fn new(span: Span, error: CompileErrorEnum) -> Self {
+        Self {
+            span,
+            error: Box::new(error),
+            labels: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// The source text the error is about
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn error(&self) -> &CompileErrorEnum {
+        &self.error
     }
 }
 
@@ -21,26 +51,355 @@ pub enum CompileErrorEnum {
     #[error("Unclosed string")]
     UnclosedString,
 
-    #[error("Expected ''")]
-    ExpectedPipe(String),
+    #[error("Unclosed comment")]
+    UnclosedComment,
+
+    #[error("Unexpected character {0:?}")]
+    UnexpectedCharacter(char),
+
+    #[error("Unknown escape sequence \\{0}")]
+    InvalidEscape(char),
+
+    #[error("Invalid unicode escape \\u{{{0}}}")]
+    InvalidUnicodeEscape(String),
+
+    #[error("Malformed number {0}")]
+    MalformedNumber(String),
 
-    #[error("Unexpected Token {0:?}")]
+    #[error("{literal} does not fit in {ty}")]
+    NumberOutOfRange { literal: String, ty: &'static str },
+
+    #[error("Incomplete pipe operator {0}")]
+    IncompletePipe(char),
+
+    #[error("Unexpected token {}", PrintToken::from(.0.clone()))]
     UnexpectedToken(TokenEnum),
 
     #[error("Enexpected end of input")]
     UnexpectedEndOfInput { expected: PrintToken },
 
-    #[error("Expected {expected}, found {found:?}")]
+    #[error("Expected {expected}, found {}", PrintToken::from(.found.clone()))]
     ExpectedToken {
         expected: PrintToken,
         found: TokenEnum,
     },
 
-    #[error("Expected one of {expected}, found {found:?}")]
+    #[error("Expected one of {expected}, found {}", PrintToken::from(.found.clone()))]
     ExpectedOneOf {
         expected: PrintTokens,
         found: TokenEnum,
     },
+
+    #[error("Unknown module or item {0}")]
+    UnknownPath(String),
+
+    #[error("{0} is private to its module")]
+    PrivateItem(String),
+
+    #[error("Import cycle {}", .0.join(" -> "))]
+    ImportCycle(Vec<String>),
+
+    #[error("Unknown type {0}")]
+    UnknownType(String),
+
+    #[error("{ty} expects {expected} type argument(s), found {found}")]
+    TypeArgumentCount {
+        ty: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Unknown variable or function {0}")]
+    UnknownName(String),
+
+    #[error("Expected type {expected}, found {found}")]
+    TypeMismatch { expected: String, found: String },
+
+    #[error("{name} expects {expected} argument(s), found {found}")]
+    ArgumentCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Cannot apply {op} to {lhs} and {rhs}")]
+    InvalidOperands {
+        op: &'static str,
+        lhs: String,
+        rhs: String,
+    },
+
+    #[error("Cannot apply {op} {rhs} to a pipe value of type {value}")]
+    InvalidSection {
+        op: &'static str,
+        value: String,
+        rhs: String,
+    },
+
+    #[error("{ty} has no field named {field}")]
+    UnknownField { ty: String, field: String },
+
+    #[error("{0} is not an enum variant")]
+    NotAVariant(String),
+
+    #[error("Cannot change a {0}, it is borrowed without `mut`")]
+    Immutable(String),
+
+    #[error("`{mode}` can only be used with objects, {ty} is always copied")]
+    InvalidParamMode { mode: &'static str, ty: String },
+
+    #[error(
+        "Type parameter {param} of {func} can not be inferred, it is not used by any parameter"
+    )]
+    UninferableTypeParameter { param: String, func: String },
+
+    #[error("{0} is not a trait")]
+    NotATrait(String),
+
+    #[error("Only data, object and enum types can implement traits, found {0}")]
+    InvalidImplementation(String),
+
+    #[error("{ty} already implements {r#trait}")]
+    DuplicateImplementation { r#trait: String, ty: String },
+
+    #[error("The implementation of {r#trait} for {ty} is missing {method}")]
+    MissingTraitMethod {
+        r#trait: String,
+        ty: String,
+        method: String,
+    },
+
+    #[error("{method} is not a method of {r#trait}")]
+    UnknownTraitMethod { r#trait: String, method: String },
+
+    #[error("{ty} does not implement {r#trait}")]
+    NotImplemented { r#trait: String, ty: String },
+}
+
+/// A problem that does not stop the program from compiling
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{}:{}: warning: {warning}", span.start.row, span.start.column)]
+pub struct CompileWarning {
+    span: Span,
+    warning: CompileWarningEnum,
+}
+
+impl CompileWarning {
+    pub fn new(span: Span, warning: CompileWarningEnum) -> Self {
+        Self { span, warning }
+    }
+
+    /// The source text the warning is about
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn warning(&self) -> &CompileWarningEnum {
+        &self.warning
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum CompileWarningEnum {
+    #[error("Match is not exhaustive, {0} is not covered")]
+    NonExhaustive(String),
+
+    #[error("Unreachable match arm, earlier arms already cover every value it matches")]
+    UnreachableArm,
+
+    #[error("Unreachable stage, the |. before it always returns")]
+    UnreachableStage,
+
+    #[error("The error this pipeline can fail with is ignored, handle it with |!")]
+    UnhandledError,
+}
+
+/// A [CompileWarning] in a file of a project
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{}:{warning}", path.display())]
+pub struct ProjectWarning {
+    pub path: PathBuf,
+    pub warning: CompileWarning,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{row}:{column}: {error}")]
+pub struct RuntimeError {
+    /// The source file of the module the error happened in, [None] for errors that are not
+    /// about a line of code such as a missing `main`
+    file: Option<PathBuf>,
+    row: usize,
+    column: usize,
+    /// Boxed to keep `Result<_, RuntimeError>` small now that the error carries a file
+    error: Box<RuntimeErrorEnum>,
+    /// The calls the error unwound through, innermost first
+    trace: Vec<Frame>,
+}
+
+/// A call to `function` at `row` and `column` of `file` that a [RuntimeError] unwound through
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub function: String,
+    pub file: PathBuf,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl RuntimeError {
+    pub fn new(row: usize, column: usize, error: RuntimeErrorEnum) -> Self {
+        Self {
+            file: None,
+            row,
+            column,
+            error: Box::new(error),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Records the file the error happened in, unless a function it unwound out of already did
+    pub fn in_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file.get_or_insert_with(|| file.into());
+        self
+    }
+
+    /// Records that the error unwound out of `function`, which was called at `row` and `column`
+    /// of `file`
+    pub fn called_from(
+        mut self,
+        function: impl Into<String>,
+        file: impl Into<PathBuf>,
+        row: usize,
+        column: usize,
+    ) -> Self {
+        self.trace.push(Frame {
+            function: function.into(),
+            file: file.into(),
+            row,
+            column,
+        });
+        self
+    }
+
+    pub fn trace(&self) -> &[Frame] {
+        &self.trace
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// The row and column the error happened at
+    pub fn position(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    pub fn error(&self) -> &RuntimeErrorEnum {
+        &self.error
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum RuntimeErrorEnum {
+    #[error("No main function found")]
+    MissingMain,
+
+    #[error("Unknown variable or function {0}")]
+    UnknownName(String),
+
+    #[error("{name} expects {expected} argument(s), found {found}")]
+    ArgumentCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Cannot apply {op} to {lhs} and {rhs}")]
+    InvalidOperands {
+        op: &'static str,
+        lhs: &'static str,
+        rhs: &'static str,
+    },
+
+    #[error("Cannot apply {op} {rhs} to a pipe value of type {value}")]
+    InvalidSection {
+        op: &'static str,
+        value: &'static str,
+        rhs: &'static str,
+    },
+
+    #[error("Expected a value of type {expected}, found {found}")]
+    InvalidType {
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("No field named {0}")]
+    UnknownField(String),
+
+    #[error("Integer overflow, the result does not fit in {0}")]
+    Overflow(&'static str),
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("Stack overflow, more than {0} function calls are nested")]
+    StackOverflow(usize),
+
+    #[error("No match arm matched the value {0}")]
+    NoMatch(String),
+
+    #[error("Could not read input: {0}")]
+    Input(String),
+
+    #[error("Panicked: {0}")]
+    Panic(String),
+
+    #[error("{ty} does not implement {r#trait}")]
+    NotImplemented { r#trait: String, ty: String },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProjectError {
+    #[error("{}: Could not read file: {error}", path.display())]
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{}:{error}", path.display())]
+    Compile { path: PathBuf, error: CompileError },
+
+    #[error("{}:{error}", path.display())]
+    Runtime { path: PathBuf, error: RuntimeError },
+
+    #[error("{}: Expected a function 'project () (ProjectDescriptor)'", path.display())]
+    MissingProjectFunc { path: PathBuf },
+
+    #[error("{}: Invalid project descriptor, {reason}", path.display())]
+    InvalidDescriptor { path: PathBuf, reason: String },
+
+    #[error("{}: Not inside the source directory {}", path.display(), src.display())]
+    OutsideSource { path: PathBuf, src: PathBuf },
+
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    Errors(Vec<ProjectError>),
+
+    #[error("Invalid source directory {pattern}: {error}")]
+    Glob {
+        pattern: String,
+        error: glob::PatternError,
+    },
+}
+
+impl ProjectError {
+    /// Combines the errors found by a pass into one, [None] if there were none
+    pub fn combine(mut errors: Vec<ProjectError>) -> Option<ProjectError> {
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => Some(ProjectError::Errors(errors)),
+        }
+    }
 }
 
 macro_rules! token_name {
@@ -54,14 +413,21 @@ macro_rules! token_name {
             crate::tokenizer::TokenEnum::KWRef => "ref",
             crate::tokenizer::TokenEnum::KWMut => "mut",
             crate::tokenizer::TokenEnum::KWPub => "pub",
+            crate::tokenizer::TokenEnum::KWTrait => "trait",
+            crate::tokenizer::TokenEnum::KWImpl => "impl",
+            crate::tokenizer::TokenEnum::KWFor => "for",
             crate::tokenizer::TokenEnum::PipeMatch => "|?",
             crate::tokenizer::TokenEnum::PipeNext => "|>",
             crate::tokenizer::TokenEnum::PipeSet => "|=",
             crate::tokenizer::TokenEnum::PipeError => "|!",
             crate::tokenizer::TokenEnum::PipeReturn => "|.",
-            crate::tokenizer::TokenEnum::PipeMatchEnd => "/?",
+            crate::tokenizer::TokenEnum::PipeMatchEnd => "\\?",
             crate::tokenizer::TokenEnum::LessThan => "<",
             crate::tokenizer::TokenEnum::GreaterThan => ">",
+            crate::tokenizer::TokenEnum::LessEqual => "<=",
+            crate::tokenizer::TokenEnum::GreaterEqual => ">=",
+            crate::tokenizer::TokenEnum::Equal => "==",
+            crate::tokenizer::TokenEnum::NotEqual => "!=",
             crate::tokenizer::TokenEnum::Plus => "+",
             crate::tokenizer::TokenEnum::Minus => "-",
             crate::tokenizer::TokenEnum::Times => "*",
@@ -70,6 +436,10 @@ macro_rules! token_name {
             crate::tokenizer::TokenEnum::Modulo => "%",
             crate::tokenizer::TokenEnum::Arrow => "->",
             crate::tokenizer::TokenEnum::Or => "||",
+            crate::tokenizer::TokenEnum::And => "&&",
+            crate::tokenizer::TokenEnum::Not => "!",
+            crate::tokenizer::TokenEnum::Range => "..",
+            crate::tokenizer::TokenEnum::RangeInclusive => "..=",
             crate::tokenizer::TokenEnum::OpenBrace => "(",
             crate::tokenizer::TokenEnum::CloseBrace => ")",
             crate::tokenizer::TokenEnum::OpenCurlyBrace => "{",
@@ -78,12 +448,15 @@ macro_rules! token_name {
             crate::tokenizer::TokenEnum::Colon => ":",
             crate::tokenizer::TokenEnum::SemiColon => ";",
             crate::tokenizer::TokenEnum::Period => ".",
+            crate::tokenizer::TokenEnum::Field(_) => ".<field>",
             crate::tokenizer::TokenEnum::Comma => ",",
             crate::tokenizer::TokenEnum::Identifier(_) => "<identifier>",
             crate::tokenizer::TokenEnum::Bool(_) => "<true|false>",
-            crate::tokenizer::TokenEnum::Integer(_) => "<integer>",
-            crate::tokenizer::TokenEnum::Float(_) => "<float>",
-            crate::tokenizer::TokenEnum::String(_) => "\"<string>\"",
+            crate::tokenizer::TokenEnum::Integer(..) => "<integer>",
+            crate::tokenizer::TokenEnum::Float(..) => "<float>",
+            crate::tokenizer::TokenEnum::String(_)
+            | crate::tokenizer::TokenEnum::InterpolatedString(_) => "\"<string>\"",
+            crate::tokenizer::TokenEnum::DocComment(_) => "///",
         }
     };
 }
@@ -99,12 +472,13 @@ impl From<&'static [TokenEnum]> for PrintTokens {
 
 impl Display for PrintTokens {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut str = String::new();
-        for token in self.0 {
-            str += token_name!(token);
-        }
+        let names = self
+            .0
+            .iter()
+            .map(|token| format!("'{}'", token_name!(token)))
+            .collect::<Vec<_>>();
 
-        write!(f, "{str}")
+        write!(f, "{}", names.join(", "))
     }
 }
 
@@ -0,0 +1,326 @@
+use super::{Interpreter, Value};
+use crate::{
+    ast::{Item, Span},
+    error::{RuntimeError, RuntimeErrorEnum},
+    resolver::TypeRef,
+};
+use std::collections::BTreeMap;
+
+pub(super) type Builtin = fn(&mut Interpreter, Vec<Value>, Span) -> Result<Value, RuntimeError>;
+
+/// Looks up a function provided by the interpreter rather than the program
+pub(super) fn builtin(path: &[&str]) -> Option<Builtin> {
+    Some(match path {
+        ["print"] => print,
+        ["println"] => println,
+        ["read_line"] => read_line,
+        ["read_int"] => read_int,
+        ["panic"] => panic,
+        ["fail"] => fail,
+        ["map"] => map,
+        ["filter"] => filter,
+        ["fold"] => fold,
+        ["for_each"] => for_each,
+        ["Std", "CLI", "parse_args"] => parse_args,
+        ["Std", "Build", "ProjectDescriptor", "init"] => project_descriptor_init,
+        _ => return None,
+    })
+}
+
+fn error(location: Span, error: RuntimeErrorEnum) -> RuntimeError {
+    RuntimeError::new(location.start.row, location.start.column, error)
+}
+
+/// Takes exactly `N` arguments for the builtin called `name`
+fn arguments<const N: usize>(
+    name: &str,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<[Value; N], RuntimeError> {
+    let found = args.len();
+    args.try_into().map_err(|_| {
+        error(
+            location,
+            RuntimeErrorEnum::ArgumentCount {
+                name: name.into(),
+                expected: N,
+                found,
+            },
+        )
+    })
+}
+
+/// The elements of a range or list, in order
+fn elements(value: Value, location: Span) -> Result<Box<dyn Iterator<Item = Value>>, RuntimeError> {
+    match value {
+        Value::Range { start, end, ty } => Ok(Box::new(
+            (start..end).map(move |value| Value::Integer(value, ty)),
+        )),
+        Value::List(values) => Ok(Box::new(values.into_iter())),
+        other => Err(error(
+            location,
+            RuntimeErrorEnum::InvalidType {
+                expected: "Range or List",
+                found: other.type_name(),
+            },
+        )),
+    }
+}
+
+/// The arguments of `print`, `println`, `panic` and `fail` as text, separated by spaces
+fn join(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<String, RuntimeError> {
+    let mut parts = Vec::with_capacity(args.len());
+    for arg in &args {
+        parts.push(interpreter.display(arg, location)?);
+    }
+    Ok(parts.join(" "))
+}
+
+fn print(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let text = join(interpreter, args, location)?;
+    write!(interpreter.output, "{text}")
+        .map_err(|e| error(location, RuntimeErrorEnum::Input(e.to_string())))?;
+    Ok(Value::Unit)
+}
+
+fn println(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let text = join(interpreter, args, location)?;
+    writeln!(interpreter.output, "{text}")
+        .map_err(|e| error(location, RuntimeErrorEnum::Input(e.to_string())))?;
+    Ok(Value::Unit)
+}
+
+fn read_line(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    if !args.is_empty() {
+        return Err(error(
+            location,
+            RuntimeErrorEnum::ArgumentCount {
+                name: "read_line".into(),
+                expected: 0,
+                found: args.len(),
+            },
+        ));
+    }
+
+    interpreter
+        .output
+        .flush()
+        .map_err(|e| error(location, RuntimeErrorEnum::Input(e.to_string())))?;
+    let mut line = String::new();
+    interpreter
+        .input
+        .read_line(&mut line)
+        .map_err(|e| error(location, RuntimeErrorEnum::Input(e.to_string())))?;
+
+    Ok(Value::String(line.trim_end_matches(['\r', '\n']).into()))
+}
+
+fn read_int(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let Value::String(line) = read_line(interpreter, args, location)? else {
+        unreachable!("read_line always returns a string");
+    };
+
+    line.trim()
+        .parse()
+        .ok()
+        .and_then(|value| Value::integer(value, Some("Int32")))
+        .ok_or_else(|| {
+            error(
+                location,
+                RuntimeErrorEnum::Input(format!("{line:?} is not an Int32")),
+            )
+        })
+}
+
+fn panic(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let message = join(interpreter, args, location)?;
+    Err(error(location, RuntimeErrorEnum::Panic(message)))
+}
+
+/// Fails the current pipeline with a message, which is passed to the next `|!` stage
+fn fail(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    Ok(Value::Error(join(interpreter, args, location)?))
+}
+
+/// `iterable |> map function`, a list of the results of calling `function` on each element
+fn map(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let [iterable, function] = arguments("map", args, location)?;
+    elements(iterable, location)?
+        .map(|value| interpreter.call_value(&function, vec![value], location))
+        .collect::<Result<_, _>>()
+        .map(Value::List)
+}
+
+/// `iterable |> filter function`, a list of the elements `function` returns true for
+fn filter(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let [iterable, function] = arguments("filter", args, location)?;
+    let mut kept = Vec::new();
+    for value in elements(iterable, location)? {
+        match interpreter.call_value(&function, vec![value.clone()], location)? {
+            Value::Bool(true) => kept.push(value),
+            Value::Bool(false) => {}
+            other => {
+                return Err(error(
+                    location,
+                    RuntimeErrorEnum::InvalidType {
+                        expected: "Bool",
+                        found: other.type_name(),
+                    },
+                ))
+            }
+        }
+    }
+    Ok(Value::List(kept))
+}
+
+/// `iterable |> fold initial function`, calls `function` with the result so far and each
+/// element in turn
+fn fold(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let [iterable, initial, function] = arguments("fold", args, location)?;
+    elements(iterable, location)?.try_fold(initial, |accumulator, value| {
+        interpreter.call_value(&function, vec![accumulator, value], location)
+    })
+}
+
+/// `iterable |> for_each function`, calls `function` on each element for its side effects
+fn for_each(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let [iterable, function] = arguments("for_each", args, location)?;
+    for value in elements(iterable, location)? {
+        interpreter.call_value(&function, vec![value], location)?;
+    }
+    Ok(Value::Unit)
+}
+
+/// `Std::CLI::parse_args`, the `data Args` of the calling module filled in from the command
+/// line. `Bool` fields are set by a `--name` flag, other fields take the argument after it
+fn parse_args(
+    interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    let [] = arguments("Std::CLI::parse_args", args, location)?;
+    let program = interpreter.program;
+    let Some(TypeRef::Item {
+        module,
+        item: Item::Data(data),
+    }) = program.lookup_type(interpreter.module, &["Args".into()])
+    else {
+        return Err(error(
+            location,
+            RuntimeErrorEnum::UnknownName("Args".into()),
+        ));
+    };
+
+    let mut given = BTreeMap::new();
+    let mut arguments = interpreter.args.iter();
+    while let Some(argument) = arguments.next() {
+        let Some(field) = argument
+            .strip_prefix("--")
+            .and_then(|name| data.fields.iter().find(|field| field.name.name == name))
+        else {
+            return Ok(Value::Error(format!("Unexpected argument {argument}")));
+        };
+        let ty = field.ty.path.name().name.as_str();
+        let value = if ty == "Bool" {
+            Value::Bool(true)
+        } else {
+            let Some(value) = arguments.next() else {
+                return Ok(Value::Error(format!("{argument} needs a value")));
+            };
+            match Value::integer_type(ty) {
+                Some(integer) => match value
+                    .parse()
+                    .ok()
+                    .and_then(|value| Value::integer(value, Some(integer)))
+                {
+                    Some(value) => value,
+                    None => return Ok(Value::Error(format!("{argument} needs an {integer}"))),
+                },
+                None => Value::String(value.clone()),
+            }
+        };
+        given.insert(field.name.name.clone(), value);
+    }
+
+    let mut fields = BTreeMap::new();
+    for field in &data.fields {
+        let name = &field.name.name;
+        let value = match given.remove(name) {
+            Some(value) => value,
+            None if field.ty.path.name().name == "Bool" => Value::Bool(false),
+            None => return Ok(Value::Error(format!("Missing argument --{name}"))),
+        };
+        fields.insert(name.clone(), value);
+    }
+    Ok(Value::Data {
+        ty: program.item_path(module, &data.name.name),
+        fields,
+    })
+}
+
+/// An empty `Std::Build::ProjectDescriptor`, filled in by `project.st`
+fn project_descriptor_init(
+    _interpreter: &mut Interpreter,
+    args: Vec<Value>,
+    location: Span,
+) -> Result<Value, RuntimeError> {
+    if !args.is_empty() {
+        return Err(error(
+            location,
+            RuntimeErrorEnum::ArgumentCount {
+                name: "ProjectDescriptor::init".into(),
+                expected: 0,
+                found: args.len(),
+            },
+        ));
+    }
+
+    Ok(Value::Record(BTreeMap::from([
+        ("name".to_string(), Value::String(String::new())),
+        ("bin".to_string(), Value::Unit),
+    ])))
+}
@@ -0,0 +1,1245 @@
+mod builtins;
+#[cfg(test)]
+mod tests;
+mod value;
+
+pub use value::{Function, Value};
+
+use value::type_name;
+
+use crate::{
+    ast::*,
+    error::{RuntimeError, RuntimeErrorEnum},
+    resolver::{impl_method_name, method_name, Callee, Program, TraitRef, TypeRef},
+};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::{BufRead, Write},
+    rc::Rc,
+};
+
+/// Why evaluation stopped before producing a value
+enum Unwind {
+    /// `|.` was reached, the function should return this value
+    Return(Value),
+    Error(RuntimeError),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Self::Error(error)
+    }
+}
+
+/// The type parameters of a generic declaration, with the types they were given
+type Generics<'a> = Rc<HashMap<&'a str, Bound<'a>>>;
+
+/// A type argument, with the module and the type parameters it was written with
+struct Bound<'a> {
+    module: usize,
+    ty: &'a Type,
+    generics: Generics<'a>,
+}
+
+/// The variables visible at a point in a function, innermost scope last
+type Scope = Vec<HashMap<String, Value>>;
+
+/// How many function calls can be nested before evaluation stops with a stack overflow. Loops
+/// are written as recursion so the limit is high, the caller has to run the interpreter on a
+/// thread with a stack large enough for it
+pub const MAX_CALL_DEPTH: usize = 10_000;
+
+fn error(location: Span, error: RuntimeErrorEnum) -> RuntimeError {
+    RuntimeError::new(location.start.row, location.start.column, error)
+}
+
+/// Evaluates a [Program] by walking its AST
+pub struct Interpreter<'a> {
+    program: &'a Program,
+    /// The index of the module whose code is being evaluated
+    module: usize,
+    input: Box<dyn BufRead + 'a>,
+    output: Box<dyn Write + 'a>,
+    /// The command line arguments read by `Std::CLI::parse_args`
+    args: Vec<String>,
+    /// The number of user function calls being evaluated
+    depth: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Creates an interpreter reading from stdin and writing to stdout
+    pub fn new(program: &'a Program) -> Self {
+        Self::with_io(program, std::io::stdin().lock(), std::io::stdout().lock())
+    }
+
+    pub fn with_io(
+        program: &'a Program,
+        input: impl BufRead + 'a,
+        output: impl Write + 'a,
+    ) -> Self {
+        Self {
+            program,
+            module: 0,
+            input: Box::new(input),
+            output: Box::new(output),
+            args: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Passes command line arguments to the program
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Runs the program's `main` function
+    pub fn run(&mut self) -> Result<Value, RuntimeError> {
+        if self.program.find_function("main").is_none() {
+            return Err(RuntimeError::new(1, 1, RuntimeErrorEnum::MissingMain));
+        }
+        self.call_function("main", Vec::new())
+    }
+
+    /// Calls a top level function by name
+    pub fn call_function(&mut self, name: &str, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        let (module, func) = self
+            .program
+            .find_function(name)
+            .ok_or_else(|| RuntimeError::new(1, 1, RuntimeErrorEnum::UnknownName(name.into())))?;
+
+        let result = self.call_func(module, func, args, func.location);
+        self.output
+            .flush()
+            .map_err(|e| error(func.location, RuntimeErrorEnum::Input(e.to_string())))?;
+        result
+    }
+
+    fn call(&mut self, path: &Path, args: Vec<Value>, location: Span) -> Result<Value, Unwind> {
+        let segments = segments(path);
+        let program = self.program;
+        match program.lookup(self.module, &segments) {
+            Some(Callee::Func { module, func }) => Ok(self
+                .call_func(module, func, args, location)
+                .map_err(|error| self.called_from(error, &func.name.name, location))?),
+            Some(Callee::Method {
+                module,
+                object,
+                func,
+            }) => Ok(self
+                .call_func(module, func, args, location)
+                .map_err(|error| self.called_from(error, &method_name(object, func), location))?),
+            Some(Callee::TraitMethod {
+                module,
+                r#trait,
+                method,
+            }) => Ok(self.call_trait_method(module, r#trait, &method.name.name, args, location)?),
+            Some(Callee::Object { module, object }) => match <[Value; 1]>::try_from(args) {
+                Ok([Value::Record(fields) | Value::Data { fields, .. }]) => Ok(Value::Object {
+                    ty: program.item_path(module, &object.name.name),
+                    fields: Rc::new(RefCell::new(fields)),
+                }),
+                Ok([other]) => Err(error(
+                    location,
+                    RuntimeErrorEnum::InvalidType {
+                        expected: "Record",
+                        found: other.type_name(),
+                    },
+                )
+                .into()),
+                Err(args) => Err(error(
+                    location,
+                    RuntimeErrorEnum::ArgumentCount {
+                        name: segments.join("::"),
+                        expected: 1,
+                        found: args.len(),
+                    },
+                )
+                .into()),
+            },
+            Some(Callee::Variant {
+                module,
+                r#enum,
+                variant,
+            }) => {
+                let expected = variant.kind.arity();
+                if args.len() != expected {
+                    return Err(error(
+                        location,
+                        RuntimeErrorEnum::ArgumentCount {
+                            name: segments.join("::"),
+                            expected,
+                            found: args.len(),
+                        },
+                    )
+                    .into());
+                }
+                Ok(Value::Variant {
+                    r#enum: program.item_path(module, &r#enum.name.name),
+                    name: variant.name.name.clone(),
+                    payload: Box::new(input(args)),
+                })
+            }
+            Some(Callee::Builtin(path)) => {
+                let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+                match builtins::builtin(&path) {
+                    Some(builtin) => Ok(builtin(self, args, location)?),
+                    None => {
+                        Err(error(location, RuntimeErrorEnum::UnknownName(path.join("::"))).into())
+                    }
+                }
+            }
+            None => Err(error(location, RuntimeErrorEnum::UnknownName(segments.join("::"))).into()),
+        }
+    }
+
+    /// The function named by `path` as a value, [None] if naming it calls it because it takes no
+    /// parameters
+    fn function_value(&self, path: &Path) -> Option<Value> {
+        match self.program.lookup(self.module, &segments(path))? {
+            Callee::Func { module, func } if !func.params.is_empty() => {
+                Some(Value::Function(Function::Named {
+                    module,
+                    name: func.name.name.clone(),
+                }))
+            }
+            Callee::Method {
+                module,
+                object,
+                func,
+            } if !func.params.is_empty() => Some(Value::Function(Function::Named {
+                module,
+                name: method_name(object, func),
+            })),
+            Callee::TraitMethod {
+                module,
+                r#trait,
+                method,
+            } if !method.params.is_empty() => Some(Value::Function(Function::Trait {
+                module,
+                r#trait: r#trait.name.name.clone(),
+                method: method.name.name.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Adds the call of `function` at `location` in the current module to the trace of an error
+    /// raised inside it
+    fn called_from(&self, error: RuntimeError, function: &str, location: Span) -> RuntimeError {
+        error.called_from(
+            function,
+            &self.program.modules()[self.module].file,
+            location.start.row,
+            location.start.column,
+        )
+    }
+
+    /// Calls a function that was passed around as a value, such as the argument of `map`
+    fn call_value(
+        &mut self,
+        function: &Value,
+        args: Vec<Value>,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        let Value::Function(function) = function else {
+            return Err(error(
+                location,
+                RuntimeErrorEnum::InvalidType {
+                    expected: "Function",
+                    found: function.type_name(),
+                },
+            ));
+        };
+        match function {
+            Function::Named { module, name } => {
+                let program = self.program;
+                let func = program.modules()[*module]
+                    .function(name)
+                    .expect("Function values only name declared functions");
+                self.call_func(*module, func, args, location)
+                    .map_err(|error| self.called_from(error, name, location))
+            }
+            Function::Trait {
+                module,
+                r#trait,
+                method,
+            } => {
+                let program = self.program;
+                let Some(TraitRef::Declared { module, r#trait }) =
+                    program.lookup_trait(*module, std::slice::from_ref(r#trait))
+                else {
+                    unreachable!("Function values only name declared traits")
+                };
+                self.call_trait_method(module, r#trait, method, args, location)
+            }
+            Function::Closure {
+                module,
+                body,
+                captured,
+            } => self
+                .body(*module, body, input(args), vec![captured.clone()])
+                .map_err(|error| self.called_from(error, "func { ... }", location)),
+        }
+    }
+
+    /// Calls a user function, its arguments become the initial pipe value of its body. Objects
+    /// passed by value are copied, `ref` and `mut` parameters share them with the caller
+    fn call_func(
+        &mut self,
+        module: usize,
+        func: &'a Func,
+        mut args: Vec<Value>,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        if args.len() != func.params.len() {
+            return Err(error(
+                location,
+                RuntimeErrorEnum::ArgumentCount {
+                    name: func.name.name.clone(),
+                    expected: func.params.len(),
+                    found: args.len(),
+                },
+            ));
+        }
+
+        for (arg, param) in args.iter_mut().zip(&func.params) {
+            if param.mode == ParamMode::Value {
+                *arg = arg.copied();
+            }
+            *arg = self.tagged(
+                module,
+                &param.ty,
+                std::mem::replace(arg, Value::Unit),
+                location,
+            )?;
+        }
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(error(
+                location,
+                RuntimeErrorEnum::StackOverflow(MAX_CALL_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let result = self.body(module, &func.body, input(args), Vec::new());
+        self.depth -= 1;
+        let result = result?;
+        match func.returns.as_slice() {
+            [returns] => self.tagged(module, returns, result, location),
+            _ => Ok(result),
+        }
+    }
+
+    /// Gives a value the type `ty` it is passed or returned as. A record remembers the name of
+    /// its `data` type so the traits it implements can be found, an integer has to fit in its
+    /// integer type
+    fn tagged(
+        &self,
+        module: usize,
+        ty: &'a Type,
+        value: Value,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        self.tagged_in(module, ty, &Generics::default(), value, location)
+    }
+
+    /// Tags a value and every field, element and payload inside it with the types they are
+    /// declared with, `generics` gives the type parameters of the declaration `ty` was written in
+    fn tagged_in(
+        &self,
+        module: usize,
+        ty: &'a Type,
+        generics: &Generics<'a>,
+        value: Value,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        let path = segments(&ty.path);
+        if let [name] = path.as_slice() {
+            if let Some(bound) = generics.get(name.as_str()) {
+                return self.tagged_in(bound.module, bound.ty, &bound.generics, value, location);
+            }
+        }
+        // The type parameters of the declaration `ty` names, bound to its arguments
+        let bind = |params: &'a [Ident]| -> Generics<'a> {
+            let args = ty.args.iter().map(|ty| Bound {
+                module,
+                ty,
+                generics: generics.clone(),
+            });
+            Rc::new(
+                params
+                    .iter()
+                    .map(|param| param.name.as_str())
+                    .zip(args)
+                    .collect(),
+            )
+        };
+
+        let declared = self.program.lookup_type(module, &path);
+        let integer = match &declared {
+            Some(TypeRef::Builtin(path)) if path.len() == 1 => Value::integer_type(&path[0]),
+            _ => None,
+        };
+        Ok(match (value, declared, integer) {
+            (
+                Value::Record(fields) | Value::Data { fields, .. },
+                Some(TypeRef::Item {
+                    module,
+                    item: Item::Data(data),
+                }),
+                _,
+            ) => Value::Data {
+                ty: self.program.item_path(module, &data.name.name),
+                fields: self.tagged_fields(
+                    module,
+                    &data.fields,
+                    &bind(&data.type_params),
+                    fields,
+                    location,
+                )?,
+            },
+            (
+                Value::Variant {
+                    r#enum,
+                    name,
+                    payload,
+                },
+                Some(TypeRef::Item {
+                    module,
+                    item: Item::Enum(declared),
+                }),
+                _,
+            ) => {
+                let generics = bind(&declared.type_params);
+                let kind = declared
+                    .variants
+                    .iter()
+                    .find(|variant| variant.name.name == name)
+                    .map(|variant| &variant.kind);
+                let payload = match (kind, *payload) {
+                    (Some(VariantKind::Tuple(types)), Value::Tuple(values)) if types.len() > 1 => {
+                        let mut tagged = Vec::with_capacity(values.len());
+                        for (ty, value) in types.iter().zip(values) {
+                            tagged.push(self.tagged_in(module, ty, &generics, value, location)?);
+                        }
+                        Value::Tuple(tagged)
+                    }
+                    (Some(VariantKind::Tuple(types)), value) if types.len() == 1 => {
+                        self.tagged_in(module, &types[0], &generics, value, location)?
+                    }
+                    (Some(VariantKind::Record(fields)), Value::Record(values)) => Value::Record(
+                        self.tagged_fields(module, fields, &generics, values, location)?,
+                    ),
+                    (_, payload) => payload,
+                };
+                Value::Variant {
+                    r#enum,
+                    name,
+                    payload: Box::new(payload),
+                }
+            }
+            (Value::List(values), Some(TypeRef::Builtin(builtin)), _) if builtin == ["List"] => {
+                match ty.args.first() {
+                    Some(element) => {
+                        let mut tagged = Vec::with_capacity(values.len());
+                        for value in values {
+                            tagged
+                                .push(self.tagged_in(module, element, generics, value, location)?);
+                        }
+                        Value::List(tagged)
+                    }
+                    None => Value::List(values),
+                }
+            }
+            (Value::Error(message), ..) => Value::Error(message),
+            (value, Some(TypeRef::Builtin(builtin)), _) if builtin == ["Result"] => {
+                match ty.args.first() {
+                    Some(ok) => self.tagged_in(module, ok, generics, value, location)?,
+                    None => value,
+                }
+            }
+            (Value::Integer(value, _), _, Some(ty)) => Value::integer(value, Some(ty))
+                .ok_or_else(|| error(location, RuntimeErrorEnum::Overflow(ty)))?,
+            (value, ..) => value,
+        })
+    }
+
+    /// Tags the values of the fields of a record with their declared types
+    fn tagged_fields(
+        &self,
+        module: usize,
+        declared: &'a [Field],
+        generics: &Generics<'a>,
+        mut fields: BTreeMap<String, Value>,
+        location: Span,
+    ) -> Result<BTreeMap<String, Value>, RuntimeError> {
+        for field in declared {
+            if let Some(value) = fields.remove(&field.name.name) {
+                let value = self.tagged_in(module, &field.ty, generics, value, location)?;
+                fields.insert(field.name.name.clone(), value);
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Calls the implementation of `method` of a declared trait for the type of its `Self`
+    /// argument
+    fn call_trait_method(
+        &mut self,
+        module: usize,
+        r#trait: &Trait,
+        method: &str,
+        args: Vec<Value>,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        let trait_path = self
+            .program
+            .trait_path(&TraitRef::Declared { module, r#trait });
+        // The checker makes sure one of the parameters is `Self`
+        let index = r#trait
+            .method(method)
+            .and_then(|method| {
+                method
+                    .params
+                    .iter()
+                    .position(|param| segments(&param.ty.path) == ["Self"])
+            })
+            .unwrap_or(0);
+        self.call_implementation(&trait_path, method, args, index, location)
+    }
+
+    /// Calls `method` of the implementation of the trait at `trait_path` for the type of the
+    /// argument at `index`
+    fn call_implementation(
+        &mut self,
+        trait_path: &[String],
+        method: &str,
+        args: Vec<Value>,
+        index: usize,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        let program = self.program;
+        let value = args.get(index).unwrap_or(&Value::Unit);
+        let found = value.declared_type().and_then(|ty| {
+            let (module, implementation) = program.implementation(trait_path, ty)?;
+            Some((module, implementation, implementation.method(method)?))
+        });
+        let Some((module, implementation, func)) = found else {
+            return Err(error(
+                location,
+                RuntimeErrorEnum::NotImplemented {
+                    r#trait: trait_path.last().cloned().unwrap_or_default(),
+                    ty: value
+                        .declared_type()
+                        .map_or(value.type_name(), type_name)
+                        .to_string(),
+                },
+            ));
+        };
+        self.call_func(module, func, args, location)
+            .map_err(|error| {
+                self.called_from(error, &impl_method_name(implementation, func), location)
+            })
+    }
+
+    /// Whether the type of `value` has an implementation of the language's trait `name`
+    fn implements(&self, value: &Value, name: &str) -> bool {
+        value.declared_type().is_some_and(|ty| {
+            self.program
+                .implementation(&[name.to_string()], ty)
+                .is_some()
+        })
+    }
+
+    /// The text of `value` for `print`, `println` and string interpolation, written by its
+    /// `Display` implementation if it has one
+    pub(super) fn display(
+        &mut self,
+        value: &Value,
+        location: Span,
+    ) -> Result<String, RuntimeError> {
+        if !self.implements(value, "Display") {
+            return Ok(value.to_string());
+        }
+        let trait_path = ["Display".to_string()];
+        match self.call_implementation(&trait_path, "display", vec![value.clone()], 0, location)? {
+            Value::String(text) => Ok(text),
+            other => Err(error(
+                location,
+                RuntimeErrorEnum::InvalidType {
+                    expected: "String",
+                    found: other.type_name(),
+                },
+            )),
+        }
+    }
+
+    /// Applies `op` with the `Add` or `Compare` implementation of the type of `lhs`
+    fn binary(
+        &mut self,
+        op: BinaryOp,
+        lhs: Value,
+        rhs: Value,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        let (r#trait, method) = match op {
+            BinaryOp::Plus => ("Add", "add"),
+            BinaryOp::LessThan
+            | BinaryOp::GreaterThan
+            | BinaryOp::LessEqual
+            | BinaryOp::GreaterEqual => ("Compare", "compare"),
+            _ => return binary(op, lhs, rhs, location),
+        };
+        if !self.implements(&lhs, r#trait) {
+            return binary(op, lhs, rhs, location);
+        }
+
+        let trait_path = [r#trait.to_string()];
+        match (
+            op,
+            self.call_implementation(&trait_path, method, vec![lhs, rhs], 0, location)?,
+        ) {
+            (BinaryOp::Plus, sum) => Ok(sum),
+            // `compare` orders its arguments like subtracting them would
+            (op, Value::Integer(ordering, _)) => {
+                Ok(compare(op, &ordering, &0).expect("Only orderings are compared"))
+            }
+            (_, other) => Err(error(
+                location,
+                RuntimeErrorEnum::InvalidType {
+                    expected: "Integer",
+                    found: other.type_name(),
+                },
+            )),
+        }
+    }
+
+    /// Applies an operator section such as `|> + n` or `|? < 1` to the pipe value, with the
+    /// trait implementations of its type
+    fn section(
+        &mut self,
+        op: BinaryOp,
+        value: Value,
+        rhs: Value,
+        location: Span,
+    ) -> Result<Value, RuntimeError> {
+        if op == BinaryOp::Plus && self.implements(&value, "Add")
+            || op.is_boolean() && self.implements(&value, "Compare")
+        {
+            return self.binary(op, value, rhs, location);
+        }
+        section(op, value, rhs, location)
+    }
+
+    /// Evaluates the body of a function declared in `module`, stopping at a `|.`
+    fn body(
+        &mut self,
+        module: usize,
+        body: &Block,
+        input: Value,
+        mut scope: Scope,
+    ) -> Result<Value, RuntimeError> {
+        let caller = std::mem::replace(&mut self.module, module);
+        let result = self.block(body, input, &mut scope);
+        self.module = caller;
+
+        match result {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(error)) => Err(error.in_file(&self.program.modules()[module].file)),
+        }
+    }
+
+    fn block(&mut self, block: &Block, input: Value, scope: &mut Scope) -> Result<Value, Unwind> {
+        scope.push(HashMap::new());
+        let result = self.block_inner(block, input, scope);
+        scope.pop();
+        result
+    }
+
+    fn block_inner(
+        &mut self,
+        block: &Block,
+        input: Value,
+        scope: &mut Scope,
+    ) -> Result<Value, Unwind> {
+        for statement in &block.statements {
+            self.pipeline(statement, input.clone(), scope)?;
+        }
+
+        match &block.tail {
+            Some(tail) => self.pipeline(tail, input, scope),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn pipeline(
+        &mut self,
+        pipeline: &Pipeline,
+        input: Value,
+        scope: &mut Scope,
+    ) -> Result<Value, Unwind> {
+        let mut current = input;
+        // The message of the error a stage failed with, everything up to the next `|!` is skipped
+        let mut failure: Option<String> = None;
+        // The `|=` names skipped since the failure, they are bound to the result of the handler
+        let mut skipped: Vec<&str> = Vec::new();
+
+        for stage in &pipeline.stages {
+            let input = std::mem::replace(&mut current, Value::Unit);
+            current = match (&stage.kind, failure.take()) {
+                (StageKind::Error(handler), Some(message)) => {
+                    let handled = self.apply(handler, Value::String(message), scope)?;
+                    let names = scope
+                        .last_mut()
+                        .expect("Pipelines are always evaluated inside a block");
+                    for name in skipped.drain(..) {
+                        names.insert(name.to_string(), handled.clone());
+                    }
+                    handled
+                }
+                (StageKind::Set(name), Some(message)) => {
+                    skipped.push(&name.name);
+                    failure = Some(message);
+                    continue;
+                }
+                (StageKind::Return, Some(message)) => {
+                    return Err(Unwind::Return(Value::Error(message)))
+                }
+                (_, Some(message)) => {
+                    failure = Some(message);
+                    continue;
+                }
+                (_, None) => self.stage(stage, input, scope)?,
+            };
+            if let Value::Error(message) = current {
+                failure = Some(message);
+                current = Value::Unit;
+            }
+        }
+
+        Ok(match failure {
+            Some(message) => Value::Error(message),
+            None => current,
+        })
+    }
+
+    fn stage(&mut self, stage: &Stage, current: Value, scope: &mut Scope) -> Result<Value, Unwind> {
+        match &stage.kind {
+            StageKind::Value(expr) => self.expr(expr, &current, scope),
+            StageKind::Next(expr) => self.apply(expr, current, scope),
+            StageKind::Set(name) => {
+                scope
+                    .last_mut()
+                    .expect("Pipelines are always evaluated inside a block")
+                    .insert(name.name.clone(), current.clone());
+                Ok(current)
+            }
+            StageKind::Match(arms) => {
+                for arm in arms {
+                    scope.push(HashMap::new());
+                    let result = match self.pattern(&arm.pattern, &current, scope) {
+                        Ok(true) => Some(self.pipeline(&arm.body, current.clone(), scope)),
+                        Ok(false) => None,
+                        Err(unwind) => Some(Err(unwind)),
+                    };
+                    scope.pop();
+
+                    if let Some(result) = result {
+                        return result;
+                    }
+                }
+                Err(error(
+                    stage.location,
+                    RuntimeErrorEnum::NoMatch(current.to_string()),
+                )
+                .into())
+            }
+            StageKind::Error(_) => Ok(current),
+            StageKind::Return => Err(Unwind::Return(current)),
+        }
+    }
+
+    /// Applies a `|>` stage, functions receive the pipe value as their first argument unless it
+    /// is referenced explicitly with `.`
+    fn apply(&mut self, expr: &Expr, current: Value, scope: &mut Scope) -> Result<Value, Unwind> {
+        match &expr.kind {
+            ExprKind::Path(path) if lookup(path, scope).is_none() => {
+                self.call(path, vec![current], expr.location)
+            }
+            ExprKind::Call(path, args) if !args.iter().any(Expr::uses_current) => {
+                let mut values = Vec::with_capacity(args.len() + 1);
+                for arg in args {
+                    values.push(self.expr(arg, &current, scope)?);
+                }
+                values.insert(0, current);
+                self.call(path, values, expr.location)
+            }
+            // A closure or a variable holding a function is called with the pipe value
+            ExprKind::Path(_) | ExprKind::Closure(_) => match self.expr(expr, &current, scope)? {
+                function @ Value::Function(_) => {
+                    let args = match current {
+                        Value::Tuple(values) => values,
+                        value => vec![value],
+                    };
+                    Ok(self.call_value(&function, args, expr.location)?)
+                }
+                value => Ok(value),
+            },
+            _ => self.expr(expr, &current, scope),
+        }
+    }
+
+    /// The value of the literal at `location`, an integer without a suffix has the type the
+    /// checker inferred for it
+    fn literal_value(&self, literal: &Literal, location: Span) -> Value {
+        match literal {
+            Literal::Unit => Value::Unit,
+            Literal::Integer(value, ty) => Value::Integer(
+                *value,
+                ty.or_else(|| self.program.literal_type(self.module, location)),
+            ),
+            Literal::Float(value, _) => Value::Float(*value),
+            Literal::Bool(value) => Value::Bool(*value),
+            Literal::String(value) => Value::String(value.clone()),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, current: &Value, scope: &mut Scope) -> Result<Value, Unwind> {
+        match &expr.kind {
+            ExprKind::Literal(literal) => Ok(self.literal_value(literal, expr.location)),
+            ExprKind::Current => Ok(current.clone()),
+            ExprKind::Path(path) => match lookup(path, scope) {
+                Some(value) => Ok(value.clone()),
+                None => match self.function_value(path) {
+                    Some(function) => Ok(function),
+                    None => self.call(path, Vec::new(), expr.location),
+                },
+            },
+            ExprKind::Call(path, args) => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(self.expr(arg, current, scope)?);
+                }
+                self.call(path, values, expr.location)
+            }
+            // `&&` and `||` only evaluate their right operand when the left one does not decide
+            // the result
+            ExprKind::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs) => {
+                match (op, self.expr(lhs, current, scope)?) {
+                    (BinaryOp::And, Value::Bool(false)) => Ok(Value::Bool(false)),
+                    (BinaryOp::Or, Value::Bool(true)) => Ok(Value::Bool(true)),
+                    (_, lhs) => {
+                        let rhs = self.expr(rhs, current, scope)?;
+                        Ok(self.binary(*op, lhs, rhs, expr.location)?)
+                    }
+                }
+            }
+            ExprKind::Binary(op, lhs, rhs) => {
+                let lhs = self.expr(lhs, current, scope)?;
+                let rhs = self.expr(rhs, current, scope)?;
+                Ok(self.binary(*op, lhs, rhs, expr.location)?)
+            }
+            ExprKind::Section(op, operand) => {
+                let rhs = self.expr(operand, current, scope)?;
+                Ok(self.section(*op, current.clone(), rhs, expr.location)?)
+            }
+            ExprKind::Not(operand) => match self.expr(operand, current, scope)? {
+                Value::Bool(value) => Ok(Value::Bool(!value)),
+                other => Err(error(
+                    operand.location,
+                    RuntimeErrorEnum::InvalidType {
+                        expected: "Bool",
+                        found: other.type_name(),
+                    },
+                )
+                .into()),
+            },
+            ExprKind::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let start = self.expr(start, current, scope)?;
+                let end = self.expr(end, current, scope)?;
+                Ok(range(start, end, *inclusive, expr.location)?)
+            }
+            ExprKind::Record(fields) => {
+                let mut values = BTreeMap::new();
+                for (name, value) in fields {
+                    values.insert(name.name.clone(), self.expr(value, current, scope)?);
+                }
+                Ok(Value::Record(values))
+            }
+            ExprKind::Update(field, value) => {
+                if let Value::Object { fields, .. } = current {
+                    let value = self.expr(value, current, scope)?;
+                    match fields.borrow_mut().get_mut(&field.name) {
+                        Some(slot) => *slot = value,
+                        None => {
+                            return Err(error(
+                                field.location,
+                                RuntimeErrorEnum::UnknownField(field.name.clone()),
+                            )
+                            .into())
+                        }
+                    }
+                    // The object is changed in place, so every variable holding it sees the
+                    // new value
+                    return Ok(current.clone());
+                }
+                let (ty, mut fields) = match current.clone() {
+                    Value::Record(fields) => (None, fields),
+                    Value::Data { ty, fields } => (Some(ty), fields),
+                    _ => {
+                        return Err(error(
+                            expr.location,
+                            RuntimeErrorEnum::InvalidType {
+                                expected: "Record",
+                                found: current.type_name(),
+                            },
+                        )
+                        .into())
+                    }
+                };
+                let value = self.expr(value, current, scope)?;
+                match fields.get_mut(&field.name) {
+                    Some(slot) => *slot = value,
+                    None => {
+                        return Err(error(
+                            field.location,
+                            RuntimeErrorEnum::UnknownField(field.name.clone()),
+                        )
+                        .into())
+                    }
+                }
+                // The updated record is still of the same `data` type
+                Ok(match ty {
+                    Some(ty) => Value::Data { ty, fields },
+                    None => Value::Record(fields),
+                })
+            }
+            ExprKind::Field(record, field) => match self.expr(record, current, scope)? {
+                Value::Record(mut fields) | Value::Data { mut fields, .. } => {
+                    fields.remove(&field.name).ok_or_else(|| {
+                        error(
+                            field.location,
+                            RuntimeErrorEnum::UnknownField(field.name.clone()),
+                        )
+                        .into()
+                    })
+                }
+                Value::Object { fields, .. } => {
+                    fields.borrow().get(&field.name).cloned().ok_or_else(|| {
+                        error(
+                            field.location,
+                            RuntimeErrorEnum::UnknownField(field.name.clone()),
+                        )
+                        .into()
+                    })
+                }
+                other => Err(error(
+                    record.location,
+                    RuntimeErrorEnum::InvalidType {
+                        expected: "Record",
+                        found: other.type_name(),
+                    },
+                )
+                .into()),
+            },
+            ExprKind::Group(pipeline) => self.pipeline(pipeline, current.clone(), scope),
+            ExprKind::Closure(body) => {
+                // Inner scopes come last so they shadow the outer ones
+                let captured = scope
+                    .iter()
+                    .flat_map(|vars| vars.iter())
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                Ok(Value::Function(Function::Closure {
+                    module: self.module,
+                    body: Rc::clone(body),
+                    captured,
+                }))
+            }
+            ExprKind::Interpolation(parts) => {
+                let mut text = String::new();
+                for part in parts {
+                    match part {
+                        InterpolationPart::Text(part) => text.push_str(part),
+                        InterpolationPart::Expr(expr) => {
+                            let value = self.expr(expr, current, scope)?;
+                            text.push_str(&self.display(&value, expr.location)?)
+                        }
+                    }
+                }
+                Ok(Value::String(text))
+            }
+        }
+    }
+
+    /// Tests `value` against `pattern`, adding any bindings to the innermost scope
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Value,
+        scope: &mut Scope,
+    ) -> Result<bool, Unwind> {
+        match &pattern.kind {
+            PatternKind::Wildcard => Ok(true),
+            PatternKind::Binding(name) => {
+                scope
+                    .last_mut()
+                    .expect("Patterns are always matched inside a scope")
+                    .insert(name.name.clone(), value.clone());
+                Ok(true)
+            }
+            PatternKind::Literal(literal) => {
+                Ok(&self.literal_value(literal, pattern.location) == value)
+            }
+            PatternKind::Compare(op, expr) => {
+                let rhs = self.expr(expr, value, scope)?;
+                match self.section(*op, value.clone(), rhs, pattern.location)? {
+                    Value::Bool(matched) => Ok(matched),
+                    other => Err(error(
+                        pattern.location,
+                        RuntimeErrorEnum::InvalidType {
+                            expected: "Bool",
+                            found: other.type_name(),
+                        },
+                    )
+                    .into()),
+                }
+            }
+            PatternKind::Record(fields) => {
+                let values = match value {
+                    Value::Record(values) | Value::Data { fields: values, .. } => {
+                        Cow::Borrowed(values)
+                    }
+                    // Matching can call functions that change the object
+                    Value::Object { fields, .. } => Cow::Owned(fields.borrow().clone()),
+                    _ => return Ok(false),
+                };
+                for (name, pattern) in fields {
+                    match values.get(&name.name) {
+                        Some(value) if self.pattern(pattern, value, scope)? => {}
+                        _ => return Ok(false),
+                    }
+                }
+                Ok(true)
+            }
+            PatternKind::Tuple(patterns) => match value {
+                Value::Tuple(values) if values.len() == patterns.len() => {
+                    for (pattern, value) in patterns.iter().zip(values) {
+                        if !self.pattern(pattern, value, scope)? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+            PatternKind::Variant(path, patterns) => {
+                let Value::Variant {
+                    r#enum,
+                    name,
+                    payload,
+                } = value
+                else {
+                    return Ok(false);
+                };
+                let segments = segments(path);
+                let [.., expected_enum, expected_name] = segments.as_slice() else {
+                    return Ok(false);
+                };
+                if type_name(r#enum) != expected_enum || name != expected_name {
+                    return Ok(false);
+                }
+                match (patterns.as_slice(), payload.as_ref()) {
+                    ([], _) => Ok(true),
+                    ([pattern], payload) => self.pattern(pattern, payload, scope),
+                    (patterns, Value::Tuple(values)) if patterns.len() == values.len() => {
+                        for (pattern, value) in patterns.iter().zip(values) {
+                            if !self.pattern(pattern, value, scope)? {
+                                return Ok(false);
+                            }
+                        }
+                        Ok(true)
+                    }
+                    _ => Ok(false),
+                }
+            }
+        }
+    }
+}
+
+/// The initial pipe value of a function called with `args`
+fn input(mut args: Vec<Value>) -> Value {
+    match args.len() {
+        0 => Value::Unit,
+        1 => args.remove(0),
+        _ => Value::Tuple(args),
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.name.clone())
+        .collect()
+}
+
+fn lookup<'s>(path: &Path, scope: &'s Scope) -> Option<&'s Value> {
+    match path.segments.as_slice() {
+        [name] => scope.iter().rev().find_map(|vars| vars.get(&name.name)),
+        _ => None,
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value, location: Span) -> Result<Value, RuntimeError> {
+    let invalid = |lhs: &Value, rhs: &Value| {
+        error(
+            location,
+            RuntimeErrorEnum::InvalidOperands {
+                op: op.symbol(),
+                lhs: lhs.type_name(),
+                rhs: rhs.type_name(),
+            },
+        )
+    };
+
+    match (lhs, rhs) {
+        // The checker makes sure both operands have the same type if both have one
+        (Value::Integer(lhs, lhs_ty), Value::Integer(rhs, rhs_ty)) => {
+            integer_binary(op, lhs, rhs, lhs_ty.or(rhs_ty), location).unwrap_or_else(|| {
+                Err(invalid(
+                    &Value::Integer(lhs, lhs_ty),
+                    &Value::Integer(rhs, rhs_ty),
+                ))
+            })
+        }
+        (Value::Float(lhs), Value::Float(rhs)) => float_binary(op, lhs, rhs)
+            .ok_or_else(|| invalid(&Value::Float(lhs), &Value::Float(rhs))),
+        (Value::Integer(lhs, ty), Value::Float(rhs)) => float_binary(op, lhs as f64, rhs)
+            .ok_or_else(|| invalid(&Value::Integer(lhs, ty), &Value::Float(rhs))),
+        (Value::Float(lhs), Value::Integer(rhs, ty)) => float_binary(op, lhs, rhs as f64)
+            .ok_or_else(|| invalid(&Value::Float(lhs), &Value::Integer(rhs, ty))),
+        (Value::String(lhs), Value::String(rhs)) => match op {
+            BinaryOp::Plus => Ok(Value::String(lhs + &rhs)),
+            _ => compare(op, &lhs, &rhs)
+                .ok_or_else(|| invalid(&Value::String(lhs), &Value::String(rhs))),
+        },
+        (Value::Bool(lhs), Value::Bool(rhs)) => match op {
+            BinaryOp::Or => Ok(Value::Bool(lhs || rhs)),
+            BinaryOp::And => Ok(Value::Bool(lhs && rhs)),
+            BinaryOp::Equal => Ok(Value::Bool(lhs == rhs)),
+            BinaryOp::NotEqual => Ok(Value::Bool(lhs != rhs)),
+            _ => Err(invalid(&Value::Bool(lhs), &Value::Bool(rhs))),
+        },
+        (lhs, rhs) => match op {
+            BinaryOp::Equal => Ok(Value::Bool(lhs == rhs)),
+            BinaryOp::NotEqual => Ok(Value::Bool(lhs != rhs)),
+            _ => Err(invalid(&lhs, &rhs)),
+        },
+    }
+}
+
+/// Builds a range value, `..=` ranges are stored with their end moved past the last value
+fn range(start: Value, end: Value, inclusive: bool, location: Span) -> Result<Value, RuntimeError> {
+    match (start, end) {
+        (Value::Integer(start, start_ty), Value::Integer(end, end_ty)) => {
+            let end = match inclusive {
+                true => end
+                    .checked_add(1)
+                    .ok_or_else(|| error(location, RuntimeErrorEnum::Overflow("Int128")))?,
+                false => end,
+            };
+            Ok(Value::Range {
+                start,
+                end,
+                ty: start_ty.or(end_ty),
+            })
+        }
+        (start, end) => Err(error(
+            location,
+            RuntimeErrorEnum::InvalidOperands {
+                op: if inclusive { "..=" } else { ".." },
+                lhs: start.type_name(),
+                rhs: end.type_name(),
+            },
+        )),
+    }
+}
+
+/// Applies an operator section such as `|> + n` or `|? < 1` to the pipe value
+fn section(op: BinaryOp, value: Value, rhs: Value, location: Span) -> Result<Value, RuntimeError> {
+    binary(op, value, rhs, location).map_err(|e| match e.error() {
+        RuntimeErrorEnum::InvalidOperands { op, lhs, rhs } => error(
+            location,
+            RuntimeErrorEnum::InvalidSection {
+                op,
+                value: lhs,
+                rhs,
+            },
+        ),
+        _ => e,
+    })
+}
+
+/// Applies an operator to integers of the type `ty`, [None] if it does not apply to integers
+fn integer_binary(
+    op: BinaryOp,
+    lhs: i128,
+    rhs: i128,
+    ty: Option<&'static str>,
+    location: Span,
+) -> Option<Result<Value, RuntimeError>> {
+    let overflow = || error(location, RuntimeErrorEnum::Overflow(ty.unwrap_or("Int128")));
+    let checked = |value: Option<i128>| {
+        Some(
+            value
+                .and_then(|value| Value::integer(value, ty))
+                .ok_or_else(overflow),
+        )
+    };
+
+    match op {
+        BinaryOp::Plus => checked(lhs.checked_add(rhs)),
+        BinaryOp::Minus => checked(lhs.checked_sub(rhs)),
+        BinaryOp::Times => checked(lhs.checked_mul(rhs)),
+        BinaryOp::Divide | BinaryOp::Modulo if rhs == 0 => {
+            Some(Err(error(location, RuntimeErrorEnum::DivisionByZero)))
+        }
+        BinaryOp::Divide => checked(lhs.checked_div(rhs)),
+        BinaryOp::Modulo => checked(lhs.checked_rem(rhs)),
+        BinaryOp::Power => checked(u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_pow(rhs))),
+        BinaryOp::Or | BinaryOp::And => None,
+        _ => compare(op, &lhs, &rhs).map(Ok),
+    }
+}
+
+fn float_binary(op: BinaryOp, lhs: f64, rhs: f64) -> Option<Value> {
+    Some(match op {
+        BinaryOp::Plus => Value::Float(lhs + rhs),
+        BinaryOp::Minus => Value::Float(lhs - rhs),
+        BinaryOp::Times => Value::Float(lhs * rhs),
+        BinaryOp::Divide => Value::Float(lhs / rhs),
+        BinaryOp::Modulo => Value::Float(lhs % rhs),
+        BinaryOp::Power => Value::Float(lhs.powf(rhs)),
+        BinaryOp::Or | BinaryOp::And => return None,
+        _ => return compare(op, &lhs, &rhs),
+    })
+}
+
+/// Applies a comparison operator, [None] if `op` does not compare its operands
+fn compare<T: PartialOrd>(op: BinaryOp, lhs: &T, rhs: &T) -> Option<Value> {
+    Some(Value::Bool(match op {
+        BinaryOp::LessThan => lhs < rhs,
+        BinaryOp::GreaterThan => lhs > rhs,
+        BinaryOp::LessEqual => lhs <= rhs,
+        BinaryOp::GreaterEqual => lhs >= rhs,
+        BinaryOp::Equal => lhs == rhs,
+        BinaryOp::NotEqual => lhs != rhs,
+        _ => return None,
+    }))
+}
@@ -0,0 +1,930 @@
+use super::*;
+use crate::{
+    checker::check,
+    parser::parse,
+    resolver::{resolve, SourceModule},
+    tokenizer::tokenize,
+};
+
+fn run(source: &str, input: &str) -> (Result<Value, RuntimeError>, String) {
+    run_with_args(source, input, &[])
+}
+
+fn run_with_args(
+    source: &str,
+    input: &str,
+    args: &[&str],
+) -> (Result<Value, RuntimeError>, String) {
+    let module = parse(tokenize(source).map(|token| token.unwrap())).unwrap();
+    let mut program = resolve(vec![SourceModule::new(
+        vec!["Test".into()],
+        "test.st".into(),
+        module,
+    )])
+    .unwrap();
+    // Types the integer literals, some tests run programs the checker rejects to see how the
+    // interpreter reports the mistake
+    let _ = check(&mut program);
+    let mut output = Vec::new();
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    let result = Interpreter::with_io(&program, input.as_bytes(), &mut output)
+        .with_args(args)
+        .run();
+    (result, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_hello_world() {
+    let source = std::fs::read_to_string("../../examples/hello_world.st").unwrap();
+    let (result, output) = run(&source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "Hello world\nthis is a multiline str\n");
+}
+
+#[test]
+fn test_fib_example() {
+    let source = std::fs::read_to_string("../../examples/fib.st").unwrap();
+
+    let (result, output) = run_with_args(&source, "4\n", &[]);
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "Enter a number\nThe 4th fibonacci number is 11\n");
+
+    let (result, output) = run_with_args(&source, "3\n", &["--all"]);
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "Enter a number\nThe 0th fibonacci number is 1\nThe 1th fibonacci number is 2\n\
+        The 2th fibonacci number is 4\n"
+    );
+
+    let (result, _) = run_with_args(&source, "", &["--many"]);
+    assert_eq!(
+        result,
+        Err(RuntimeError::new(
+            18,
+            37,
+            RuntimeErrorEnum::Panic("Unexpected argument --many".into())
+        )
+        .in_file("test.st"))
+    );
+}
+
+#[test]
+fn test_fib() {
+    let source = "
+        func fib (Int32) (Int32) {
+            |? < 2 -> 1
+            |? _ -> |= n
+                    n - 1
+                    |> fib
+                    |> (. + (n - 2 |> fib))
+            \\?
+        }
+
+        func main () () {
+            read_int |= n;
+            fib n |> println;
+        }";
+    let (result, output) = run(source, "10\n");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "89\n");
+}
+
+#[test]
+fn test_pipe_stages() {
+    let source = r#"
+        data Args {
+            all: Bool;
+        }
+
+        func describe (Args) (String) {
+            |? { all: true } -> "all"
+            |? _ -> "some"
+            \?
+        }
+
+        func early (Int32) (Int32) {
+            |? > 10 -> |.
+            |? _ -> ()
+            \?;
+            0
+        }
+
+        func main () () {
+            {all: true} |> describe |> println;
+            {all: false} |> describe |> println;
+            20 |> early |> println;
+            5 |> early |> println;
+            fail "oh no" |! println;
+            fail "again" |! func { "handled #{.}" } |> println;
+            2 ^ 10 |= x;
+            x |> println "x is" .;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "all\nsome\n20\n0\noh no\nhandled again\nx is 1024\n"
+    );
+}
+
+#[test]
+fn test_runtime_errors() {
+    assert_eq!(
+        run("func main () () { 1 / 0 }", "").0,
+        Err(RuntimeError::new(1, 19, RuntimeErrorEnum::DivisionByZero).in_file("test.st"))
+    );
+    assert_eq!(
+        run("func main () () { 1 + true }", "").0,
+        Err(RuntimeError::new(
+            1,
+            19,
+            RuntimeErrorEnum::InvalidOperands {
+                op: "+",
+                lhs: "Integer",
+                rhs: "Bool",
+            }
+        )
+        .in_file("test.st"))
+    );
+    assert_eq!(
+        run("func main () () { missing }", "").0,
+        Err(
+            RuntimeError::new(1, 19, RuntimeErrorEnum::UnknownName("missing".into()))
+                .in_file("test.st")
+        )
+    );
+    assert_eq!(
+        run("func main () () { Std::CLI::ProjectDescriptor::init }", "").0,
+        Err(RuntimeError::new(
+            1,
+            19,
+            RuntimeErrorEnum::UnknownName("Std::CLI::ProjectDescriptor::init".into())
+        )
+        .in_file("test.st"))
+    );
+    assert_eq!(
+        run("func other () () { () }", "").0,
+        Err(RuntimeError::new(1, 1, RuntimeErrorEnum::MissingMain))
+    );
+    assert_eq!(
+        run(
+            "trait Shape { func area (Self) (Float64); } func main () () { 1 |> Shape::area }",
+            ""
+        )
+        .0,
+        Err(RuntimeError::new(
+            1,
+            68,
+            RuntimeErrorEnum::NotImplemented {
+                r#trait: "Shape".into(),
+                ty: "Integer".into(),
+            }
+        )
+        .in_file("test.st"))
+    );
+}
+
+#[test]
+fn test_field_update() {
+    let source = r#"
+        func main () () {
+            {name: "", count: 1}
+            |> .{name} "example"
+            |> .{count} 2
+            |> println;
+            {name: ""} |> .{missing} 1
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(output, "{count: 2, name: example}\n");
+    assert_eq!(
+        result,
+        Err(
+            RuntimeError::new(7, 29, RuntimeErrorEnum::UnknownField("missing".into()))
+                .in_file("test.st")
+        )
+    );
+}
+
+#[test]
+fn test_records() {
+    let source = r##"
+        data Point {
+            x: Int32;
+            y: Int32;
+        }
+
+        func swap (Point) (Point) {
+            {x: .y, y: .x}
+        }
+
+        func main () () {
+            3 |= x;
+            {x, y: 4} |= point;
+            point |> swap |> println .x .y "#{.x}";
+            {point, label: "origin"} |= labelled;
+            labelled.point.y |> println;
+            labelled
+            |? {point: {x: 0, y}} -> println "on the y axis at" y
+            |? {point: {x, y: 4}, label} -> println label "at" x
+            |? _ -> ()
+            \?;
+            point.z
+        }"##;
+    let (result, output) = run(source, "");
+
+    assert_eq!(
+        result,
+        Err(
+            RuntimeError::new(22, 19, RuntimeErrorEnum::UnknownField("z".into()))
+                .in_file("test.st")
+        )
+    );
+    assert_eq!(output, "4 3 4\n4\norigin at 3\n");
+}
+
+#[test]
+fn test_enums() {
+    let source = r#"
+        enum Shape {
+            Empty,
+            Circle(Float64),
+            Pair(Int64, Int64),
+            Rect { width: Float64; height: Float64; },
+        }
+
+        func area (Shape) (Float64) {
+            |? Shape::Empty -> 0.0
+            |? Shape::Circle r -> r * r * 3.0
+            |? Shape::Pair a 0 -> 0.0
+            |? Shape::Pair _ _ -> 1.0
+            |? Shape::Rect {width, height} -> width * height
+            \?
+        }
+
+        func main () () {
+            Shape::Circle 1.5 |> area |> println;
+            2.0 |> Shape::Circle |> println;
+            Shape::Pair 1 0 |> area |> println "pair" .;
+            Shape::Empty |> area |> println;
+            Shape::Rect {width: 2.0, height: 3.0} |> area |> println;
+            Shape::Pair 1
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::new(
+            24,
+            13,
+            RuntimeErrorEnum::ArgumentCount {
+                name: "Shape::Pair".into(),
+                expected: 2,
+                found: 1
+            }
+        )
+        .in_file("test.st"))
+    );
+    assert_eq!(output, "6.75\nShape::Circle(2)\npair 0\n0\n6\n");
+}
+
+#[test]
+fn test_objects() {
+    let source = r#"
+        object Counter {
+            count: Int32;
+            step: Int32;
+
+            func increment (mut Counter) () {
+                .{count} (.count + .step);
+            }
+
+            func get (ref Counter) (Int32) { .count }
+
+            func reset (Counter) (Counter) { .{count} 0 }
+        }
+
+        func main () () {
+            Counter {count: 0, step: 2} |= counter;
+            counter |> Counter::increment;
+            counter |> Counter::get |> println;
+            counter |> Counter::reset |> println;
+            counter |= alias;
+            alias |> Counter::increment;
+            counter.count |> println;
+            counter
+            |? {count: 4} -> println "four"
+            |? _ -> println "other"
+            \?;
+            0..1 |> map func { counter } |> map Counter::get |> println;
+            Counter 1
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::new(
+            28,
+            13,
+            RuntimeErrorEnum::InvalidType {
+                expected: "Record",
+                found: "Integer"
+            }
+        )
+        .in_file("test.st"))
+    );
+    assert_eq!(output, "2\nCounter {count: 0, step: 2}\n4\nfour\n[4]\n");
+}
+
+#[test]
+fn test_generics() {
+    let source = r#"
+        data Pair<A, B> {
+            first: A;
+            second: B;
+        }
+
+        enum Option<T> {
+            None,
+            Some(T),
+        }
+
+        func swap<A, B> (Pair<A, B>) (Pair<B, A>) { {first: .second, second: .first} }
+
+        func get<T> (Option<T>) (Result<T>) {
+            |? Option::Some value -> value
+            |? Option::None -> fail "nothing there"
+            \?
+        }
+
+        func main () () {
+            {first: 1, second: "one"} |> swap |> println;
+            Option::Some 2 |> get |> println;
+            Option::None |> get |! println |> println;
+            Option::Some {first: true, second: 1.5} |> println;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "{first: one, second: 1}\n2\nnothing there\n()\nOption::Some({first: true, second: 1.5})\n"
+    );
+}
+
+#[test]
+fn test_traits() {
+    let source = r##"
+        trait Shape {
+            func area (Self) (Float64);
+        }
+
+        data Money { cents: Int64; }
+        data Square { side: Float64; }
+        object Circle { radius: Float64; }
+        enum Blob { Small, Big(Float64) }
+
+        impl Add for Money {
+            func add (Money, Money) (Money) { |? ({cents: a}, {cents: b}) -> {cents: a + b} \? }
+        }
+
+        impl Compare for Money {
+            func compare (Money, Money) (Int32) { |? ({cents: a}, {cents: b}) -> a - b |> sign \? }
+        }
+
+        impl Display for Money {
+            func display (Money) (String) { "$#{.cents}" }
+        }
+
+        impl Display for Square {
+            func display (Square) (String) { "[#{.side}]" }
+        }
+
+        impl Shape for Square {
+            func area (Square) (Float64) { .side * .side }
+        }
+
+        impl Shape for Circle {
+            func area (Circle) (Float64) { .radius * .radius * 3.0 }
+        }
+
+        impl Shape for Blob {
+            func area (Blob) (Float64) {
+                |? Blob::Small -> 1.0
+                |? Blob::Big size -> size
+                \?
+            }
+        }
+
+        func sign (Int64) (Int32) {
+            |? < 0 -> 0 - 1
+            |? 0 -> 0
+            |? _ -> 1
+            \?
+        }
+
+        func cents (Int64) (Money) { {cents: .} }
+        func square (Float64) (Square) { {side: .} }
+        func twice<T: Add> (T) (T) { . + . }
+        func describe<T: Shape + Display> (T) (String) { "#{.} has area #{Shape::area .}" }
+
+        func main () () {
+            5 |> cents |= money;
+            7 |> cents |= more;
+            money |> println;
+            "#{money} and #{money + more}" |> println;
+            money < more |> println;
+            more < money |> println;
+            money <= money |> println;
+            money |> + more |> twice |> println;
+            3 |> twice |> println;
+            3.0 |> square |> describe |> println;
+            Circle {radius: 1.0} |> Shape::area |> println;
+            Blob::Big 7.5 |> Shape::area |> println;
+            0..2 |> map func { Circle {radius: 2.0} } |> map Shape::area |> println;
+        }"##;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "$5\n$5 and $12\ntrue\nfalse\ntrue\n$24\n6\n[3] has area 9\n3\n7.5\n[12, 12]\n"
+    );
+}
+
+#[test]
+fn test_nested_trait_values() {
+    let source = r##"
+        data Money { cents: Int64; }
+        data Wallet { m: Money; }
+        data Holder<T> { held: T; }
+        data Outer<T> { inner: Holder<T>; }
+        enum Coin { Single(Money), Pair(Money, Money), Named { value: Money; } }
+
+        impl Add for Money {
+            func add (Money, Money) (Money) { |? ({cents: a}, {cents: b}) -> {cents: a + b} \? }
+        }
+
+        impl Display for Money {
+            func display (Money) (String) { "$#{.cents}" }
+        }
+
+        func wallet (Int64) (Wallet) { {m: {cents: .}} }
+        func purse () (List<Money>) { 2..4 |> map func { {cents: .} } }
+        func held (Int64) (Holder<Money>) { {held: {cents: .}} }
+        func outer (Int64) (Outer<Money>) { {inner: {held: {cents: .}}} }
+        func single (Int64) (Coin) { Coin::Single {cents: .} }
+        func pair (Int64) (Coin) { Coin::Pair {cents: .} {cents: 1} }
+        func named (Int64) (Coin) { Coin::Named {value: {cents: .}} }
+
+        func show (Coin) () {
+            |? Coin::Single m -> m + m |> println
+            |? Coin::Pair (a, b) -> a + b |> println
+            |? Coin::Named {value} -> value |> println
+            \?
+        }
+
+        func main () () {
+            5 |> wallet |= w;
+            w.m |> println;
+            w.m |> + w.m |> println;
+            purse |> for_each func { . + . |> println };
+            3 |> held |> .held |> println;
+            9 |> outer |> .inner.held |> println;
+            4 |> single |> show;
+            4 |> pair |> show;
+            4 |> named |> show;
+        }"##;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "$5\n$10\n$4\n$6\n$3\n$9\n$8\n$5\n$4\n");
+}
+
+#[test]
+fn test_traits_across_modules() {
+    let module = |name: &str, source: &str| {
+        let ast = parse(tokenize(source).map(|token| token.unwrap())).unwrap();
+        SourceModule::new(
+            name.split("::").map(String::from).collect(),
+            format!("{name}.st").into(),
+            ast,
+        )
+    };
+    let currency = |symbol: &str| {
+        format!(
+            r##"pub data Money {{ cents: Int64; }}
+            impl Display for Money {{
+                func display (Money) (String) {{ "{symbol}#{{.cents}}" }}
+            }}
+            pub func money (Int64) (Money) {{ {{cents: .}} }}"##
+        )
+    };
+    let program = resolve(vec![
+        module(
+            "Shop::Main",
+            "using Shop::Euro;
+            using Shop::Dollar;
+            func main () () {
+                5 |> Dollar::money |> println;
+                7 |> Euro::money |> println;
+            }",
+        ),
+        module("Shop::Euro", &currency("€")),
+        module("Shop::Dollar", &currency("$")),
+    ])
+    .unwrap();
+    let mut output = Vec::new();
+    let result = Interpreter::with_io(&program, "".as_bytes(), &mut output).run();
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(String::from_utf8(output).unwrap(), "$5\n€7\n");
+}
+
+#[test]
+fn test_interpolation() {
+    let source = r##"
+        func main () () {
+            read_int |= n;
+            n * 2 |> println "#{n} doubled is #{.}";
+            {x: n, y: 1} |> println "point #{.} #{"#{n}" |> println "nested #{.}"}!";
+        }"##;
+    let (result, output) = run(source, "21\n");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "21 doubled is 42\nnested 21\npoint {x: 21, y: 1} ()!\n"
+    );
+}
+
+#[test]
+fn test_ranges() {
+    let source = r#"
+        func double (Int32) (Int32) { . * 2 }
+        func even (Int32) (Bool) { . % 2 |? 0 -> true |? _ -> false \? }
+        func sum (Int32, Int32) (Int32) { |? (total, n) -> total + n \? }
+        func show (Int32) () { println . }
+
+        func main () () {
+            read_int |= n;
+            0..n |> map double |> println;
+            1..=n |> filter even |> println;
+            1..=n |> fold 0 sum |> println;
+            0..=n |> fold 0 func { |? (total, n) -> total + n * n \? } |> println;
+            0..n |> map double |> filter even |> for_each show;
+            n..0 |> map double |> println;
+        }"#;
+    let (result, output) = run(source, "3\n");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "[0, 2, 4]\n[2]\n6\n14\n0\n2\n4\n[]\n");
+}
+
+#[test]
+fn test_closures() {
+    let source = r##"
+        func main () () {
+            read_int |= n;
+            "total" |= label;
+            0..3 |> map func { . * n } |> println;
+            1..=6 |> filter func { |? < n -> true |. |? _ -> () \?; false } |> println;
+            0..2 |> for_each func { |= i |> println "#{label} #{i * n}" };
+            1..=3 |> map func { |= i 1..=i |> map func { . + i } } |> println;
+            n |> func { . + 1 } |> println;
+            func { . * n } |= scale;
+            3 |> scale |> func { . - 1 } |> scale |> println;
+        }"##;
+    let (result, output) = run(source, "4\n");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "[0, 4, 8]\n[1, 2, 3]\n0 total 0\n1 total 4\n[[2], [3, 4], [4, 5, 6]]\n5\n44\n"
+    );
+}
+
+#[test]
+fn test_comparisons() {
+    let source = r#"
+        func describe (Int32) (String) {
+            |? <= 0 -> "small"
+            |? == 5 -> "five"
+            |? != 7 -> "other"
+            |? >= 7 -> "seven"
+            \?
+        }
+
+        func main () () {
+            0..=7 |> filter func { . != 3 && !(. > 5) || . == 7 } |> println;
+            "a" <= "b" |> println;
+            1.5 >= 2 |> println;
+            {x: 1} == {x: 1} |> println;
+            0 |> describe |> println;
+            5 |> describe |> println;
+            6 |> describe |> println;
+            7 |> describe |> println;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(
+        output,
+        "[0, 1, 2, 4, 5, 7]\ntrue\nfalse\ntrue\nsmall\nfive\nother\nseven\n"
+    );
+}
+
+#[test]
+fn test_short_circuit() {
+    let source = r#"
+        func main () () {
+            0 |= x;
+            x != 0 && 10 / x > 1 |> println;
+            x == 0 || 10 / x > 1 |> println;
+            2 |= y;
+            y != 0 && 10 / y > 1 |> println;
+            y == 0 || 10 / y > 9 |> println;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "false\ntrue\ntrue\nfalse\n");
+
+    assert_eq!(
+        run("func main () () { 0 |= x; x == 0 && 10 / x > 1 }", "").0,
+        Err(RuntimeError::new(1, 37, RuntimeErrorEnum::DivisionByZero).in_file("test.st"))
+    );
+}
+
+#[test]
+fn test_integer_types() {
+    let source = r#"
+        func inc (Int32) (Int32) { . + 1 }
+        func wide (Int64) (Int64) { . + 1 }
+
+        func main () () {
+            2147483646 |> inc |> println;
+            2147483647 |> wide |> println;
+            254u8 + 1u8 |> println;
+            2147483647 |> inc;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(output, "2147483647\n2147483648\n255\n");
+    assert_eq!(
+        result,
+        Err(
+            RuntimeError::new(2, 36, RuntimeErrorEnum::Overflow("Int32"))
+                .in_file("test.st")
+                .called_from("inc", "test.st", 9, 27)
+        )
+    );
+    assert_eq!(
+        run("func main () () { 255u8 + 1u8 }", "").0,
+        Err(RuntimeError::new(1, 19, RuntimeErrorEnum::Overflow("UInt8")).in_file("test.st"))
+    );
+    assert_eq!(
+        run(
+            "func small (Int8) () { () } func main () () { 128 |> small }",
+            ""
+        )
+        .0,
+        Err(RuntimeError::new(1, 54, RuntimeErrorEnum::Overflow("Int8"))
+            .in_file("test.st")
+            .called_from("small", "test.st", 1, 54))
+    );
+
+    // Literals nothing gives a type to are `Int32`
+    assert_eq!(
+        run("func main () () { 2147483647 |= x; x + 1 |> println; }", "").0,
+        Err(RuntimeError::new(1, 36, RuntimeErrorEnum::Overflow("Int32")).in_file("test.st"))
+    );
+
+    // Literals in the result of a function have its return type before they are returned
+    assert_eq!(
+        run(
+            "func big () (Int32) { 2147483647 + 1 - 1 } func main () () { big }",
+            ""
+        )
+        .0,
+        Err(
+            RuntimeError::new(1, 23, RuntimeErrorEnum::Overflow("Int32"))
+                .in_file("test.st")
+                .called_from("big", "test.st", 1, 62)
+        )
+    );
+    assert_eq!(
+        run(
+            "func small () (Int8) { 64 * 2 |. } func main () () { small }",
+            ""
+        )
+        .0,
+        Err(RuntimeError::new(1, 24, RuntimeErrorEnum::Overflow("Int8"))
+            .in_file("test.st")
+            .called_from("small", "test.st", 1, 54))
+    );
+
+    // The elements of a range have the type of its bounds
+    let source = r#"
+        func doubled (Int32) () {
+            |= n;
+            n - 2..n |> map func { . * 2 } |> println
+        }
+
+        func main () () {
+            5 |> doubled;
+            2147483647 |> doubled;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(output, "[6, 8]\n");
+    assert_eq!(
+        result,
+        Err(
+            RuntimeError::new(4, 36, RuntimeErrorEnum::Overflow("Int32"))
+                .in_file("test.st")
+                .called_from("func { ... }", "test.st", 4, 25)
+                .called_from("doubled", "test.st", 9, 27)
+        )
+    );
+}
+
+#[test]
+fn test_sections() {
+    let source = r#"
+        func main () () {
+            read_int |= n;
+            n |> + 1 |> * n |> - 2 |> println;
+            n |> ^ 2 |> % 7 |> println;
+            n |> >= 3 |> && true |> println;
+            "a" |> + "b" |> println;
+            n |> + "b";
+        }"#;
+    let (result, output) = run(source, "3\n");
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::new(
+            8,
+            18,
+            RuntimeErrorEnum::InvalidSection {
+                op: "+",
+                value: "Integer",
+                rhs: "String"
+            }
+        )
+        .in_file("test.st"))
+    );
+    assert_eq!(output, "10\n2\ntrue\nab\n");
+}
+
+#[test]
+fn test_early_return() {
+    let source = r#"
+        func classify (Int32) (String) {
+            |? < 10 -> (|? 0 -> "zero" |. |? _ -> "small" |. \?)
+            |? _ -> ()
+            \?;
+            "large"
+        }
+
+        func main () () {
+            0 |> classify |> println;
+            5 |> classify |> println;
+            50 |> classify |> println;
+            0..4 |> map func {
+                |? 2 -> "two" |.
+                |? _ -> ()
+                \?;
+                "other"
+            } |> println;
+            () |.;
+            println "unreachable";
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "zero\nsmall\nlarge\n[other, other, two, other]\n");
+}
+
+#[test]
+fn test_results() {
+    let source = r#"
+        func parse (Int32) (Result<Int32>) {
+            |? < 0 -> fail "negative:" .
+            |? _ -> . * 2
+            \?
+        }
+
+        func double (Int32) (Result<Int32>) {
+            |? < 10 -> |> parse |> + 1 |.
+            |? _ -> ()
+            \?;
+            0
+        }
+
+        func show (Int32) () {
+            |> double |! println "failed:" . |> println
+        }
+
+        func check (Int32) () {
+            |? > 2 -> panic "too big"
+            |? _ -> ()
+            \?
+        }
+
+        func main () () {
+            2 |> show;
+            0 - 3 |> show;
+            20 |> double |> println;
+            0..5 |> for_each func { |> check };
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(
+        result,
+        Err(
+            RuntimeError::new(20, 23, RuntimeErrorEnum::Panic("too big".into()))
+                .in_file("test.st")
+                .called_from("check", "test.st", 29, 40)
+                .called_from("func { ... }", "test.st", 29, 21)
+        )
+    );
+    assert_eq!(output, "5\nfailed: negative: -3\n()\n0\n");
+}
+
+#[test]
+fn test_binding_before_handler() {
+    let source = r#"
+        func check (Int32) (Result<Int32>) {
+            |? < 0 -> fail "negative"
+            |? _ -> .
+            \?
+        }
+
+        func zero (String) (Int32) { 0 }
+
+        func main () () {
+            0 - 3 |> check |= x |! zero;
+            x |> println;
+            4 |> check |= y |> + 1 |= z |! zero;
+            y |> println;
+            z |> println;
+            0 - 1 |> check |= y |> + 1 |= z |! zero;
+            y + z |> println;
+        }"#;
+    let (result, output) = run(source, "");
+
+    assert_eq!(result, Ok(Value::Unit));
+    assert_eq!(output, "0\n4\n5\n0\n");
+}
+
+#[test]
+fn test_stack_overflow() {
+    let source = "
+        func down (Int32) (Int32) {
+            |? <= 0 -> 0
+            |? _ -> . - 1 |> down
+            \\?
+        }
+
+        func main () () {
+            read_int |> down |> println;
+        }";
+    // Recursion this deep needs more stack than test threads get
+    let count_down = |input: &'static str| {
+        std::thread::Builder::new()
+            .stack_size(1 << 30)
+            .spawn(move || {
+                let (result, output) = run(source, input);
+                (result.map(|_| ()), output)
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    };
+
+    assert_eq!(count_down("5000\n"), (Ok(()), "0\n".into()));
+
+    let (result, output) = count_down("20000\n");
+    let error = result.unwrap_err();
+    assert_eq!(output, "");
+    assert_eq!(
+        error.error(),
+        &RuntimeErrorEnum::StackOverflow(MAX_CALL_DEPTH)
+    );
+    assert_eq!(error.position(), (4, 30));
+    assert_eq!(error.trace().len(), MAX_CALL_DEPTH);
+    assert!(error.trace().iter().all(|frame| frame.function == "down"));
+    assert_eq!(
+        (
+            error.trace().last().unwrap().row,
+            error.trace().last().unwrap().column
+        ),
+        (9, 25)
+    );
+}
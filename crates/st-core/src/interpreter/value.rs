@@ -0,0 +1,281 @@
+use crate::ast::Block;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    rc::Rc,
+};
+
+/// The integer types with the smallest and largest values they can hold, integers are stored
+/// as `i128` so there is no `UInt128`
+const INTEGER_BOUNDS: &[(&str, i128, i128)] = &[
+    ("Int8", i8::MIN as i128, i8::MAX as i128),
+    ("Int16", i16::MIN as i128, i16::MAX as i128),
+    ("Int32", i32::MIN as i128, i32::MAX as i128),
+    ("Int64", i64::MIN as i128, i64::MAX as i128),
+    ("Int128", i128::MIN, i128::MAX),
+    ("UInt8", 0, u8::MAX as i128),
+    ("UInt16", 0, u16::MAX as i128),
+    ("UInt32", 0, u32::MAX as i128),
+    ("UInt64", 0, u64::MAX as i128),
+];
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Unit,
+    /// An integer with the type it was given by a literal suffix, the type the checker inferred
+    /// for the literal, a parameter or return type or the other operand of an operator, [None]
+    /// if it was not given one. Arithmetic on an integer with a type fails when the result does
+    /// not fit in the type
+    Integer(i128, Option<&'static str>),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Tuple(Vec<Value>),
+    Record(BTreeMap<String, Value>),
+    /// A record that was passed to or returned from a function as a `data` type, it remembers
+    /// the full path of the type so the traits it implements can be found
+    Data {
+        ty: Vec<String>,
+        fields: BTreeMap<String, Value>,
+    },
+    /// The integers from `start` up to but not including `end`, `..=` ranges are stored with
+    /// their end moved past the last value. The elements have the integer type of the bounds
+    Range {
+        start: i128,
+        end: i128,
+        ty: Option<&'static str>,
+    },
+    List(Vec<Value>),
+    Function(Function),
+    /// A value of an `enum` at the given full path, the payload is `()` for unit variants, a
+    /// tuple for variants with several fields and the only value otherwise
+    Variant {
+        r#enum: Vec<String>,
+        name: String,
+        payload: Box<Value>,
+    },
+    /// A value of the `object` type at the given full path, its fields are shared by every
+    /// variable holding it so changes made through one are seen through all of them
+    Object {
+        ty: Vec<String>,
+        fields: Rc<RefCell<BTreeMap<String, Value>>>,
+    },
+    /// The failed side of a `Result`, created by `fail` with a message, successful results are
+    /// plain values
+    Error(String),
+}
+
+/// A function that can be passed around as a value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    /// A function declared with `func name`, in the module with the given index
+    Named { module: usize, name: String },
+    /// A method of the trait named `trait` in the module with the given index, it calls the
+    /// implementation for the type of its `Self` argument
+    Trait {
+        module: usize,
+        r#trait: String,
+        method: String,
+    },
+    /// A `func { ... }` expression with the variables that were visible where it was evaluated
+    Closure {
+        module: usize,
+        body: Rc<Block>,
+        captured: HashMap<String, Value>,
+    },
+}
+
+impl Value {
+    /// An integer of the type `ty`, [None] if `value` does not fit in it
+    pub fn integer(value: i128, ty: Option<&'static str>) -> Option<Value> {
+        let fits = ty.is_none_or(|ty| {
+            INTEGER_BOUNDS
+                .iter()
+                .find(|(name, ..)| *name == ty)
+                .is_none_or(|(_, min, max)| (*min..=*max).contains(&value))
+        });
+        fits.then_some(Value::Integer(value, ty))
+    }
+
+    /// The name of the integer type called `name`, [None] if it is not one
+    pub fn integer_type(name: &str) -> Option<&'static str> {
+        INTEGER_BOUNDS
+            .iter()
+            .find(|(ty, ..)| *ty == name)
+            .map(|(ty, ..)| *ty)
+    }
+
+    /// The name of the value's type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Unit => "()",
+            Value::Integer(..) => "Integer",
+            Value::Float(_) => "Float",
+            Value::Bool(_) => "Bool",
+            Value::String(_) => "String",
+            Value::Tuple(_) => "Tuple",
+            Value::Record(_) | Value::Data { .. } => "Record",
+            Value::Range { .. } => "Range",
+            Value::List(_) => "List",
+            Value::Function(_) => "Function",
+            Value::Variant { .. } => "Variant",
+            Value::Object { .. } => "Object",
+            Value::Error(_) => "Error",
+        }
+    }
+
+    /// The full path of the declared type the value belongs to, which its trait implementations
+    /// are found by, [None] for values of types provided by the language
+    pub fn declared_type(&self) -> Option<&[String]> {
+        match self {
+            Value::Data { ty, .. } | Value::Object { ty, .. } => Some(ty),
+            Value::Variant { r#enum, .. } => Some(r#enum),
+            _ => None,
+        }
+    }
+
+    /// A copy of the value that shares no objects with the original, used when an object is
+    /// passed by value
+    pub fn copied(&self) -> Value {
+        match self {
+            Value::Tuple(values) => Value::Tuple(values.iter().map(Value::copied).collect()),
+            Value::List(values) => Value::List(values.iter().map(Value::copied).collect()),
+            Value::Record(fields) => Value::Record(copy_fields(fields)),
+            Value::Data { ty, fields } => Value::Data {
+                ty: ty.clone(),
+                fields: copy_fields(fields),
+            },
+            Value::Variant {
+                r#enum,
+                name,
+                payload,
+            } => Value::Variant {
+                r#enum: r#enum.clone(),
+                name: name.clone(),
+                payload: Box::new(payload.copied()),
+            },
+            Value::Object { ty, fields } => Value::Object {
+                ty: ty.clone(),
+                fields: Rc::new(RefCell::new(copy_fields(&fields.borrow()))),
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+fn copy_fields(fields: &BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+    fields
+        .iter()
+        .map(|(name, value)| (name.clone(), value.copied()))
+        .collect()
+}
+
+impl PartialEq for Value {
+    /// The type a `data` value remembers is not part of its value, it equals a record with the
+    /// same fields
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            // The type is not part of the value, `1u8` equals `1`
+            (Value::Integer(lhs, _), Value::Integer(rhs, _)) => lhs == rhs,
+            (Value::Float(lhs), Value::Float(rhs)) => lhs == rhs,
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) | (Value::Error(lhs), Value::Error(rhs)) => {
+                lhs == rhs
+            }
+            (Value::Tuple(lhs), Value::Tuple(rhs)) | (Value::List(lhs), Value::List(rhs)) => {
+                lhs == rhs
+            }
+            (
+                Value::Record(lhs) | Value::Data { fields: lhs, .. },
+                Value::Record(rhs) | Value::Data { fields: rhs, .. },
+            ) => lhs == rhs,
+            (
+                Value::Range { start, end, .. },
+                Value::Range {
+                    start: other_start,
+                    end: other_end,
+                    ..
+                },
+            ) => start == other_start && end == other_end,
+            (Value::Function(lhs), Value::Function(rhs)) => lhs == rhs,
+            (
+                Value::Variant {
+                    r#enum,
+                    name,
+                    payload,
+                },
+                Value::Variant {
+                    r#enum: other_enum,
+                    name: other_name,
+                    payload: other_payload,
+                },
+            ) => r#enum == other_enum && name == other_name && payload == other_payload,
+            (
+                Value::Object { ty, fields },
+                Value::Object {
+                    ty: other_ty,
+                    fields: other_fields,
+                },
+            ) => ty == other_ty && fields == other_fields,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Integer(value, _) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::String(value) => write!(f, "{value}"),
+            Value::Tuple(values) => {
+                let values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+                write!(f, "({})", values.join(", "))
+            }
+            Value::Record(fields) | Value::Data { fields, .. } => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            Value::Range { start, end, .. } => write!(f, "{start}..{end}"),
+            Value::List(values) => {
+                let values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Value::Function(Function::Named { name, .. }) => write!(f, "func {name}"),
+            Value::Function(Function::Trait {
+                r#trait, method, ..
+            }) => write!(f, "func {trait}::{method}"),
+            Value::Function(Function::Closure { .. }) => write!(f, "func {{ ... }}"),
+            Value::Variant {
+                r#enum,
+                name,
+                payload,
+            } => {
+                let r#enum = type_name(r#enum);
+                match payload.as_ref() {
+                    Value::Unit => write!(f, "{enum}::{name}"),
+                    Value::Tuple(_) => write!(f, "{enum}::{name}{payload}"),
+                    _ => write!(f, "{enum}::{name}({payload})"),
+                }
+            }
+            Value::Object { ty, fields } => {
+                let name = type_name(ty);
+                write!(f, "{name} {}", Value::Record(fields.borrow().clone()))
+            }
+            Value::Error(message) => write!(f, "error: {message}"),
+        }
+    }
+}
+
+/// The name of the declared type at the full path `ty`, as it is written in error messages and
+/// printed values
+pub(super) fn type_name(ty: &[String]) -> &str {
+    ty.last().map_or("", String::as_str)
+}
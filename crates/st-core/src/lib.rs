@@ -1,3 +1,9 @@
+pub mod ast;
+pub mod checker;
+pub mod diagnostic;
 pub mod error;
+pub mod interpreter;
 pub mod parser;
+pub mod project;
+pub mod resolver;
 pub mod tokenizer;
@@ -0,0 +1,1632 @@
+use super::{parser_macro, Parser, ParserBase, ParserStep, Stream};
+use crate::{
+    ast::*,
+    error::{CompileError, CompileErrorEnum},
+    parser,
+    tokenizer::{Position, StringPart, Token, TokenEnum},
+};
+use std::{
+    cell::{Cell, RefCell},
+    iter::{self, Peekable},
+    rc::Rc,
+    vec,
+};
+
+const ITEM_START: &[TokenEnum] = &[
+    TokenEnum::KWUsing,
+    TokenEnum::KWPub,
+    TokenEnum::KWFunc,
+    TokenEnum::KWData,
+    TokenEnum::KWObject,
+    TokenEnum::KWEnum,
+    TokenEnum::KWTrait,
+    TokenEnum::KWImpl,
+];
+
+const DECLARATION_START: &[TokenEnum] = &[
+    TokenEnum::KWFunc,
+    TokenEnum::KWData,
+    TokenEnum::KWObject,
+    TokenEnum::KWEnum,
+    TokenEnum::KWTrait,
+];
+
+pub(super) const EXPRESSION_START: &[TokenEnum] = &[
+    TokenEnum::Identifier(String::new()),
+    TokenEnum::Integer(0, None),
+    TokenEnum::Float(0.0, None),
+    TokenEnum::Bool(false),
+    TokenEnum::String(String::new()),
+    TokenEnum::Period,
+    TokenEnum::Field(String::new()),
+    TokenEnum::OpenBrace,
+    TokenEnum::OpenCurlyBrace,
+    TokenEnum::KWFunc,
+    TokenEnum::Not,
+];
+
+const STAGE_START: &[TokenEnum] = &[
+    TokenEnum::PipeNext,
+    TokenEnum::PipeSet,
+    TokenEnum::PipeMatch,
+    TokenEnum::PipeError,
+    TokenEnum::PipeReturn,
+    TokenEnum::Identifier(String::new()),
+    TokenEnum::Integer(0, None),
+    TokenEnum::Float(0.0, None),
+    TokenEnum::Bool(false),
+    TokenEnum::String(String::new()),
+    TokenEnum::Period,
+    TokenEnum::Field(String::new()),
+    TokenEnum::OpenBrace,
+    TokenEnum::OpenCurlyBrace,
+    TokenEnum::KWFunc,
+    TokenEnum::Not,
+];
+
+const STATEMENT_END: &[TokenEnum] = &[TokenEnum::SemiColon, TokenEnum::CloseCurlyBrace];
+
+const PATTERN_START: &[TokenEnum] = &[
+    TokenEnum::Identifier(String::new()),
+    TokenEnum::Integer(0, None),
+    TokenEnum::Float(0.0, None),
+    TokenEnum::Bool(false),
+    TokenEnum::String(String::new()),
+    TokenEnum::OpenBrace,
+    TokenEnum::OpenCurlyBrace,
+    TokenEnum::LessThan,
+    TokenEnum::GreaterThan,
+    TokenEnum::LessEqual,
+    TokenEnum::GreaterEqual,
+    TokenEnum::Equal,
+    TokenEnum::NotEqual,
+];
+
+const PARAM_MODES: &[TokenEnum] = &[TokenEnum::KWRef, TokenEnum::KWMut];
+
+const RANGES: &[TokenEnum] = &[TokenEnum::Range, TokenEnum::RangeInclusive];
+
+/// The operators of each precedence level of binary expressions, from the loosest
+const OR: &[TokenEnum] = &[TokenEnum::Or];
+const AND: &[TokenEnum] = &[TokenEnum::And];
+const COMPARISON: &[TokenEnum] = &[
+    TokenEnum::LessThan,
+    TokenEnum::GreaterThan,
+    TokenEnum::LessEqual,
+    TokenEnum::GreaterEqual,
+    TokenEnum::Equal,
+    TokenEnum::NotEqual,
+];
+const ADDITIVE: &[TokenEnum] = &[TokenEnum::Plus, TokenEnum::Minus];
+const MULTIPLICATIVE: &[TokenEnum] = &[TokenEnum::Times, TokenEnum::Divide, TokenEnum::Modulo];
+const POWER: &[TokenEnum] = &[TokenEnum::Power];
+
+const BINARY_OPERATORS: &[TokenEnum] = &[
+    TokenEnum::Or,
+    TokenEnum::And,
+    TokenEnum::LessThan,
+    TokenEnum::GreaterThan,
+    TokenEnum::LessEqual,
+    TokenEnum::GreaterEqual,
+    TokenEnum::Equal,
+    TokenEnum::NotEqual,
+    TokenEnum::Plus,
+    TokenEnum::Minus,
+    TokenEnum::Times,
+    TokenEnum::Divide,
+    TokenEnum::Modulo,
+    TokenEnum::Power,
+];
+
+/// Every operator that takes two operands
+fn binary_op(token: &TokenEnum) -> Option<BinaryOp> {
+    match token {
+        TokenEnum::Or => Some(BinaryOp::Or),
+        TokenEnum::And => Some(BinaryOp::And),
+        TokenEnum::Plus => Some(BinaryOp::Plus),
+        TokenEnum::Minus => Some(BinaryOp::Minus),
+        TokenEnum::Times => Some(BinaryOp::Times),
+        TokenEnum::Divide => Some(BinaryOp::Divide),
+        TokenEnum::Modulo => Some(BinaryOp::Modulo),
+        TokenEnum::Power => Some(BinaryOp::Power),
+        _ => comparison_op(token),
+    }
+}
+
+/// The operators that compare two values, which can also start a `|?` pattern
+fn comparison_op(token: &TokenEnum) -> Option<BinaryOp> {
+    match token {
+        TokenEnum::LessThan => Some(BinaryOp::LessThan),
+        TokenEnum::GreaterThan => Some(BinaryOp::GreaterThan),
+        TokenEnum::LessEqual => Some(BinaryOp::LessEqual),
+        TokenEnum::GreaterEqual => Some(BinaryOp::GreaterEqual),
+        TokenEnum::Equal => Some(BinaryOp::Equal),
+        TokenEnum::NotEqual => Some(BinaryOp::NotEqual),
+        _ => None,
+    }
+}
+
+fn is(expected: TokenEnum) -> impl Fn(&Token) -> bool + Clone {
+    move |token| token.token == expected
+}
+
+fn is_not(expected: TokenEnum) -> impl Fn(&Token) -> bool + Clone {
+    move |token| token.token != expected
+}
+
+fn is_ident(token: &Token) -> bool {
+    matches!(token.token, TokenEnum::Identifier(_))
+}
+
+fn starts_expression(token: &Token) -> bool {
+    matches!(
+        token.token,
+        TokenEnum::Identifier(_)
+            | TokenEnum::Integer(..)
+            | TokenEnum::Float(..)
+            | TokenEnum::Bool(_)
+            | TokenEnum::String(_)
+            | TokenEnum::InterpolatedString(_)
+            | TokenEnum::Period
+            | TokenEnum::Field(_)
+            | TokenEnum::OpenBrace
+            | TokenEnum::OpenCurlyBrace
+            | TokenEnum::KWFunc
+            | TokenEnum::Not
+    )
+}
+
+/// Whether a token starts a payload pattern of a variant
+fn starts_payload(token: &Token) -> bool {
+    matches!(
+        token.token,
+        TokenEnum::Identifier(_)
+            | TokenEnum::Integer(..)
+            | TokenEnum::Float(..)
+            | TokenEnum::Bool(_)
+            | TokenEnum::String(_)
+            | TokenEnum::OpenBrace
+            | TokenEnum::OpenCurlyBrace
+    )
+}
+
+/// The span of the last token of the grammar, where running out of input is reported
+fn last_span(tokens: &[Token], empty: Span) -> Span {
+    tokens
+        .iter()
+        .rev()
+        .find(|token| !matches!(token.token, TokenEnum::DocComment(_)))
+        .map_or(empty, |token| token.span)
+}
+
+type Tokens<'t> = dyn Stream<Item = Token> + 't;
+
+fn peek<'t>(tokens: &'t mut Tokens) -> Option<&'t TokenEnum> {
+    tokens.peek().map(|token| &token.token)
+}
+
+/// A step of the grammar, producing `O` from tokens
+trait Step<O>: ParserStep<Item = Token, Error = CompileError, Output = O> {}
+
+impl<O, S> Step<O> for S where S: ParserStep<Item = Token, Error = CompileError, Output = O> {}
+
+/// The tokens being parsed, remembering the last one taken in [ModuleParser::last] so that the
+/// span of a production can end at it, and counting the braces taken in
+/// [ModuleParser::open_braces]
+///
+/// Doc comments are not part of the grammar, they are set aside in [ModuleParser::docs] until
+/// the next token is taken so a declaration can pick up the ones written before it
+struct TokenStream<'p> {
+    iter: Peekable<vec::IntoIter<Token>>,
+    parser: &'p ModuleParser,
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.peek();
+        let token = self.iter.next()?;
+        self.parser.last.set(token.span);
+        self.parser.docs.borrow_mut().clear();
+        let open_braces = &self.parser.open_braces;
+        match token.token {
+            TokenEnum::OpenCurlyBrace => open_braces.set(open_braces.get() + 1),
+            TokenEnum::CloseCurlyBrace => open_braces.set(open_braces.get().saturating_sub(1)),
+            _ => {}
+        }
+        Some(token)
+    }
+}
+
+impl Stream for TokenStream<'_> {
+    fn peek(&mut self) -> Option<&Token> {
+        while let Some(Token {
+            token: TokenEnum::DocComment(doc),
+            ..
+        }) = self.iter.peek()
+        {
+            self.parser.docs.borrow_mut().push(doc.clone());
+            self.iter.next();
+        }
+        self.iter.peek()
+    }
+}
+
+/// Parser producing a [Module] from a stream of tokens
+///
+/// Each production of the grammar is a chain of [ParserStep]s, productions refer to each other
+/// through [ModuleParser::rule]. Errors in a statement, match arm or item are collected and
+/// parsing resumes after them, so that every syntax error in a file is reported at once
+pub(super) struct ModuleParser {
+    tokens: Vec<Token>,
+    last: Cell<Span>,
+    end: Cell<Span>,
+    /// The number of `{` taken and not closed yet, so that recovering from an error inside the
+    /// braces of an expression skips past the `}` closing them
+    open_braces: Cell<usize>,
+    docs: RefCell<Vec<String>>,
+    errors: RefCell<Vec<CompileError>>,
+}
+
+impl ModuleParser {
+    pub(super) fn new(iter: impl Iterator<Item = Token>) -> Self {
+        let tokens = iter.collect::<Vec<_>>();
+        let start = Span::new(Position::new(0, 1, 1), Position::new(0, 1, 1));
+        Self {
+            end: Cell::new(last_span(&tokens, start)),
+            tokens,
+            last: Cell::new(start),
+            open_braces: Cell::new(0),
+            docs: RefCell::new(Vec::new()),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn stream(&self, tokens: Vec<Token>) -> TokenStream<'_> {
+        TokenStream {
+            iter: tokens.into_iter().peekable(),
+            parser: self,
+        }
+    }
+
+    /// A production of the grammar as a step of another one
+    fn rule<'p, O>(
+        &'p self,
+        production: impl Fn(&'p Self, &mut Tokens) -> Result<O, CompileError> + 'p,
+    ) -> impl Step<O> + 'p {
+        parser_macro::rule(move |tokens| production(self, tokens))
+    }
+
+    /// The span of the next token, or of the last token at the end of the input
+    fn location(&self, tokens: &mut Tokens) -> Span {
+        tokens.peek().map_or(self.end.get(), |token| token.span)
+    }
+
+    /// The span from `start` to the end of the last token taken
+    fn since(&self, start: Span) -> Span {
+        start.to(self.last.get())
+    }
+
+    fn end_of_input(&self, expected: TokenEnum) -> CompileError {
+        CompileError::new(
+            self.end.get(),
+            CompileErrorEnum::UnexpectedEndOfInput {
+                expected: expected.into(),
+            },
+        )
+    }
+
+    /// Builds the error for the next token not being any of `expected`, the token is left to
+    /// recover from
+    fn unexpected(&self, tokens: &mut Tokens, expected: &'static [TokenEnum]) -> CompileError {
+        match tokens.peek() {
+            Some(Token { span, token }) => CompileError::new(
+                *span,
+                CompileErrorEnum::ExpectedOneOf {
+                    expected: expected.into(),
+                    found: token.clone(),
+                },
+            ),
+            None => self.end_of_input(expected[0].clone()),
+        }
+    }
+
+    /// Takes the token `expected`, giving its span
+    fn token(&self, expected: TokenEnum) -> impl Step<Span> {
+        let missing = self.end_of_input(expected.clone());
+
+        Parser::new().take(
+            move |Token { span, token }| {
+                if token == expected {
+                    Ok(span)
+                } else {
+                    Err(CompileError::new(
+                        span,
+                        CompileErrorEnum::ExpectedToken {
+                            expected: expected.clone().into(),
+                            found: token,
+                        },
+                    ))
+                }
+            },
+            missing,
+        )
+    }
+
+    /// Matches the token `expected`, for punctuation whose span is not needed
+    fn punct(&self, expected: TokenEnum) -> impl Step<()> {
+        let missing = self.end_of_input(expected.clone());
+
+        Parser::new().r#match(
+            move |Token { span, token }| {
+                if token == expected {
+                    Ok(())
+                } else {
+                    Err(CompileError::new(
+                        span,
+                        CompileErrorEnum::ExpectedToken {
+                            expected: expected.clone().into(),
+                            found: token,
+                        },
+                    ))
+                }
+            },
+            missing,
+        )
+    }
+
+    /// Takes a token that is any of `expected`
+    fn one_of(&self, expected: &'static [TokenEnum]) -> impl Step<Token> {
+        Parser::new().take(
+            move |token: Token| {
+                if expected.contains(&token.token) {
+                    Ok(token)
+                } else {
+                    Err(CompileError::new(
+                        token.span,
+                        CompileErrorEnum::ExpectedOneOf {
+                            expected: expected.into(),
+                            found: token.token,
+                        },
+                    ))
+                }
+            },
+            self.end_of_input(expected[0].clone()),
+        )
+    }
+
+    /// Takes one of the binary `operators`
+    fn operator(&self, operators: &'static [TokenEnum]) -> impl Step<BinaryOp> {
+        self.one_of(operators).transform(|token| {
+            binary_op(&token.token).expect("the operators are all binary operators")
+        })
+    }
+
+    fn ident(&self) -> impl Step<Ident> {
+        let missing = self.end_of_input(TokenEnum::Identifier(String::new()));
+
+        parser!(
+            take Token { token: TokenEnum::Identifier(name), span } => Ident {
+                name,
+                location: span,
+            }, else token => CompileError::new(
+                token.span,
+                CompileErrorEnum::ExpectedToken {
+                    expected: TokenEnum::Identifier(String::new()).into(),
+                    found: token.token,
+                },
+            ), missing;
+        )
+    }
+
+    /// The name of a `.name` token, which starts after the `.`
+    fn field(&self) -> impl Step<Ident> {
+        let missing = self.end_of_input(TokenEnum::Field(String::new()));
+
+        parser!(
+            take Token { token: TokenEnum::Field(name), span } => Ident {
+                name,
+                location: Span::new(
+                    Position::new(span.start.offset + 1, span.start.row, span.start.column + 1),
+                    span.end,
+                ),
+            }, else token => CompileError::new(
+                token.span,
+                CompileErrorEnum::ExpectedToken {
+                    expected: TokenEnum::Field(String::new()).into(),
+                    found: token.token,
+                },
+            ), missing;
+        )
+    }
+
+    fn literal(&self) -> impl Step<Literal> {
+        Parser::new().take(
+            |Token { span, token }| match token {
+                TokenEnum::Integer(value, ty) => Ok(Literal::Integer(value, ty)),
+                TokenEnum::Float(value, ty) => Ok(Literal::Float(value, ty)),
+                TokenEnum::Bool(value) => Ok(Literal::Bool(value)),
+                TokenEnum::String(value) => Ok(Literal::String(value)),
+                token => Err(CompileError::new(
+                    span,
+                    CompileErrorEnum::UnexpectedToken(token),
+                )),
+            },
+            self.end_of_input(TokenEnum::Integer(0, None)),
+        )
+    }
+
+    /// `item sep item sep item`, one or more items
+    fn separated<S>(&self, item: impl Fn() -> S, separator: TokenEnum) -> impl Step<Vec<S::Output>>
+    where
+        S: ParserStep<Item = Token, Error = CompileError>,
+    {
+        item()
+            .repeat(
+                is(separator.clone()),
+                self.punct(separator)
+                    .then(item())
+                    .transform(|(_, item)| item),
+            )
+            .transform(|(first, rest)| iter::once(first).chain(rest).collect())
+    }
+
+    /// `item sep item sep`, any number of items for as long as the next token `starts` one. The
+    /// separator after the last item is optional, an item without one ends the list
+    fn list<S>(
+        &self,
+        item: impl Fn() -> S,
+        separator: TokenEnum,
+        starts: impl Fn(&Token) -> bool + Clone,
+    ) -> impl Step<Vec<S::Output>>
+    where
+        S: ParserStep<Item = Token, Error = CompileError>,
+    {
+        Parser::new()
+            .optional(
+                starts.clone(),
+                item().repeat(
+                    is(separator.clone()),
+                    self.punct(separator)
+                        .optional(starts, item())
+                        .transform(|(_, item)| item),
+                ),
+            )
+            .transform(|items| match items {
+                Some((first, rest)) => iter::once(first)
+                    .chain(rest.into_iter().flatten())
+                    .collect(),
+                None => Vec::new(),
+            })
+    }
+
+    /// `open item sep item sep close`, see [ModuleParser::list]
+    fn delimited<S>(
+        &self,
+        open: TokenEnum,
+        item: impl Fn() -> S,
+        separator: TokenEnum,
+        close: TokenEnum,
+    ) -> impl Step<Vec<S::Output>>
+    where
+        S: ParserStep<Item = Token, Error = CompileError>,
+    {
+        self.punct(open)
+            .then(self.list(item, separator, is_not(close.clone())))
+            .then(self.punct(close))
+            .transform(|((_, items), _)| items)
+    }
+
+    /// `<item, item>`, nothing if the next token is not a `<`
+    fn angled<S>(&self, item: impl Fn() -> S) -> impl Step<Vec<S::Output>>
+    where
+        S: ParserStep<Item = Token, Error = CompileError>,
+    {
+        Parser::new()
+            .optional(
+                is(TokenEnum::LessThan),
+                self.punct(TokenEnum::LessThan)
+                    .then(self.separated(item, TokenEnum::Comma))
+                    .then(self.punct(TokenEnum::GreaterThan))
+                    .transform(|((_, items), _)| items),
+            )
+            .transform(Option::unwrap_or_default)
+    }
+
+    pub(super) fn module(mut self) -> Result<Module, Vec<CompileError>> {
+        let tokens = std::mem::take(&mut self.tokens);
+        let module = Parser::new()
+            .repeat(|_| true, self.rule(Self::item_or_skip))
+            .transform(|items| Module {
+                items: items.into_iter().flatten().collect(),
+            })
+            .parse(&mut self.stream(tokens));
+
+        let mut errors = self.errors.into_inner();
+        match module {
+            Ok(module) if errors.is_empty() => Ok(module),
+            module => {
+                errors.extend(module.err());
+                errors.sort_by_key(|error| error.span().start.offset);
+                Err(errors)
+            }
+        }
+    }
+
+    /// Records an error to carry on parsing after it, an error where one was already found is
+    /// a consequence of the first and is dropped
+    fn report(&self, error: CompileError) {
+        let mut errors = self.errors.borrow_mut();
+        if !errors
+            .iter()
+            .any(|other| other.span().start == error.span().start)
+        {
+            errors.push(error);
+        }
+    }
+
+    /// Reports the error of a production that failed and skips past it with `skip`, so that what
+    /// follows it is still parsed
+    fn recover<O>(
+        &self,
+        tokens: &mut Tokens,
+        result: Result<O, CompileError>,
+        skip: impl Fn(&Self, &mut Tokens),
+    ) -> Option<O> {
+        match result {
+            Ok(output) => Some(output),
+            Err(error) => {
+                self.report(error);
+                skip(self, tokens);
+                None
+            }
+        }
+    }
+
+    /// Skips tokens until the start of the next item outside of any braces
+    fn skip_to_item(&self, tokens: &mut Tokens) {
+        let mut depth = 0usize;
+        while let Some(token) = peek(tokens) {
+            match token {
+                token if depth == 0 && ITEM_START.contains(token) => return,
+                TokenEnum::OpenCurlyBrace => depth += 1,
+                TokenEnum::CloseCurlyBrace => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            tokens.next();
+        }
+    }
+
+    /// Skips tokens until the end of the current statement or match arm, a `;` is consumed while
+    /// a `}` and in a match `|?` and `\\?` are left to continue the enclosing block or match.
+    /// `open_braces` is [ModuleParser::open_braces] at the start of the statement, the braces
+    /// opened since then are closed first
+    fn skip_statement(&self, tokens: &mut Tokens, open_braces: usize, in_match: bool) {
+        let mut depth = self.open_braces.get().saturating_sub(open_braces);
+        while let Some(token) = peek(tokens) {
+            match token {
+                TokenEnum::OpenCurlyBrace => depth += 1,
+                TokenEnum::CloseCurlyBrace if depth == 0 => return,
+                TokenEnum::CloseCurlyBrace => depth -= 1,
+                TokenEnum::PipeMatch | TokenEnum::PipeMatchEnd if depth == 0 && in_match => return,
+                TokenEnum::SemiColon if depth == 0 && in_match => return,
+                TokenEnum::SemiColon | TokenEnum::PipeMatchEnd if depth == 0 => {
+                    tokens.next();
+                    return;
+                }
+                _ => {}
+            }
+            tokens.next();
+        }
+    }
+
+    /// An item, or `None` once an error in it has been reported and skipped
+    fn item_or_skip(&self, tokens: &mut Tokens) -> Result<Option<Item>, CompileError> {
+        let item = self.item(tokens);
+        Ok(self.recover(tokens, item, Self::skip_to_item))
+    }
+
+    fn item(&self, tokens: &mut Tokens) -> Result<Item, CompileError> {
+        let location = self.location(tokens);
+        let doc = self.doc(tokens);
+        match peek(tokens) {
+            Some(TokenEnum::KWUsing) => self.using(tokens).map(Item::Using),
+            Some(TokenEnum::KWImpl) => self.r#impl(tokens).map(Item::Impl),
+            Some(
+                TokenEnum::KWPub
+                | TokenEnum::KWFunc
+                | TokenEnum::KWData
+                | TokenEnum::KWObject
+                | TokenEnum::KWEnum
+                | TokenEnum::KWTrait,
+            ) => Parser::new()
+                .optional(is(TokenEnum::KWPub), self.punct(TokenEnum::KWPub))
+                .then(self.rule(Self::declaration))
+                .transform(|(public, item)| {
+                    self.declared(item, public.is_some(), doc.clone(), location)
+                })
+                .parse(tokens),
+            _ => Err(self.unexpected(tokens, ITEM_START)),
+        }
+    }
+
+    /// The doc comments written just before the next token, one line per comment
+    fn doc(&self, tokens: &mut Tokens) -> Option<String> {
+        tokens.peek();
+        let docs = self.docs.borrow();
+        (!docs.is_empty()).then(|| docs.join("\n"))
+    }
+
+    /// Fills in what is written before the keyword of a declaration, which starts at `location`
+    fn declared(&self, item: Item, public: bool, doc: Option<String>, location: Span) -> Item {
+        let location = self.since(location);
+        match item {
+            Item::Func(func) => Item::Func(Func {
+                public,
+                doc,
+                location,
+                ..func
+            }),
+            Item::Data(data) => Item::Data(Data {
+                public,
+                doc,
+                location,
+                ..data
+            }),
+            Item::Object(object) => Item::Object(Object {
+                public,
+                doc,
+                location,
+                ..object
+            }),
+            Item::Enum(r#enum) => Item::Enum(Enum {
+                public,
+                doc,
+                location,
+                ..r#enum
+            }),
+            Item::Trait(r#trait) => Item::Trait(Trait {
+                public,
+                doc,
+                location,
+                ..r#trait
+            }),
+            Item::Using(_) | Item::Impl(_) => item,
+        }
+    }
+
+    fn declaration(&self, tokens: &mut Tokens) -> Result<Item, CompileError> {
+        match peek(tokens) {
+            Some(TokenEnum::KWFunc) => self.func(tokens).map(Item::Func),
+            Some(TokenEnum::KWData) => self.data(tokens).map(Item::Data),
+            Some(TokenEnum::KWObject) => self.object(tokens).map(Item::Object),
+            Some(TokenEnum::KWEnum) => self.r#enum(tokens).map(Item::Enum),
+            Some(TokenEnum::KWTrait) => self.r#trait(tokens).map(Item::Trait),
+            _ => Err(self.unexpected(tokens, DECLARATION_START)),
+        }
+    }
+
+    /// `using Path;`
+    fn using(&self, tokens: &mut Tokens) -> Result<Using, CompileError> {
+        self.token(TokenEnum::KWUsing)
+            .then(self.rule(Self::path))
+            .then(self.punct(TokenEnum::SemiColon))
+            .transform(|((location, path), _)| Using {
+                path,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `Ident(::Ident)*`
+    fn path(&self, tokens: &mut Tokens) -> Result<Path, CompileError> {
+        self.separated(|| self.ident(), TokenEnum::DoubleColon)
+            .transform(|segments| Path {
+                location: self.since(segments[0].location),
+                segments,
+            })
+            .parse(tokens)
+    }
+
+    /// `Path<Type, Type>`
+    fn r#type(&self, tokens: &mut Tokens) -> Result<Type, CompileError> {
+        self.rule(Self::path)
+            .then(self.angled(|| self.rule(Self::r#type)))
+            .transform(|(path, args)| Type {
+                location: self.since(path.location),
+                path,
+                args,
+            })
+            .parse(tokens)
+    }
+
+    /// `<T: Add + Display, U>` after the name of a function, if it is generic
+    fn type_param(&self, tokens: &mut Tokens) -> Result<TypeParam, CompileError> {
+        self.ident()
+            .optional(
+                is(TokenEnum::Colon),
+                self.punct(TokenEnum::Colon)
+                    .then(self.separated(|| self.rule(Self::path), TokenEnum::Plus))
+                    .transform(|(_, bounds)| bounds),
+            )
+            .transform(|(name, bounds)| TypeParam {
+                name,
+                bounds: bounds.unwrap_or_default(),
+            })
+            .parse(tokens)
+    }
+
+    /// `(Type, Type)`
+    fn type_list(&self, tokens: &mut Tokens) -> Result<Vec<Type>, CompileError> {
+        self.delimited(
+            TokenEnum::OpenBrace,
+            || self.rule(Self::r#type),
+            TokenEnum::Comma,
+            TokenEnum::CloseBrace,
+        )
+        .parse(tokens)
+    }
+
+    /// `data Name<T> { field: Type; }`
+    fn data(&self, tokens: &mut Tokens) -> Result<Data, CompileError> {
+        self.token(TokenEnum::KWData)
+            .then(self.ident())
+            .then(self.angled(|| self.ident()))
+            .then(self.rule(Self::field_list))
+            .transform(|(((location, name), type_params), fields)| Data {
+                public: false,
+                doc: None,
+                name,
+                type_params,
+                fields,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `object Name { field: Type; func method (ref Name) () { body } }`, the methods follow the
+    /// fields
+    fn object(&self, tokens: &mut Tokens) -> Result<Object, CompileError> {
+        self.token(TokenEnum::KWObject)
+            .then(self.ident())
+            .then(self.punct(TokenEnum::OpenCurlyBrace))
+            .then(self.list(
+                || self.rule(Self::field_declaration),
+                TokenEnum::SemiColon,
+                is_ident,
+            ))
+            .repeat(
+                is_not(TokenEnum::CloseCurlyBrace),
+                self.rule(|this, tokens| this.method(tokens, true)),
+            )
+            .then(self.punct(TokenEnum::CloseCurlyBrace))
+            .transform(|(((((location, name), _), fields), methods), _)| Object {
+                public: false,
+                doc: None,
+                name,
+                fields,
+                methods,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `name: Type`
+    fn field_declaration(&self, tokens: &mut Tokens) -> Result<Field, CompileError> {
+        self.ident()
+            .then(self.punct(TokenEnum::Colon))
+            .then(self.rule(Self::r#type))
+            .transform(|((name, _), ty)| Field {
+                location: name.location.to(ty.location),
+                name,
+                ty,
+            })
+            .parse(tokens)
+    }
+
+    /// `{ field: Type; field: Type }`
+    fn field_list(&self, tokens: &mut Tokens) -> Result<Vec<Field>, CompileError> {
+        self.delimited(
+            TokenEnum::OpenCurlyBrace,
+            || self.rule(Self::field_declaration),
+            TokenEnum::SemiColon,
+            TokenEnum::CloseCurlyBrace,
+        )
+        .parse(tokens)
+    }
+
+    /// `enum Name<T> { Variant, Variant(Type), Variant { field: Type; } }`
+    fn r#enum(&self, tokens: &mut Tokens) -> Result<Enum, CompileError> {
+        self.token(TokenEnum::KWEnum)
+            .then(self.ident())
+            .then(self.angled(|| self.ident()))
+            .then(self.delimited(
+                TokenEnum::OpenCurlyBrace,
+                || self.rule(Self::variant),
+                TokenEnum::Comma,
+                TokenEnum::CloseCurlyBrace,
+            ))
+            .transform(|(((location, name), type_params), variants)| Enum {
+                public: false,
+                doc: None,
+                name,
+                type_params,
+                variants,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    fn variant(&self, tokens: &mut Tokens) -> Result<Variant, CompileError> {
+        self.ident()
+            .then(self.rule(Self::variant_kind))
+            .transform(|(name, kind)| Variant {
+                location: self.since(name.location),
+                name,
+                kind,
+            })
+            .parse(tokens)
+    }
+
+    fn variant_kind(&self, tokens: &mut Tokens) -> Result<VariantKind, CompileError> {
+        match peek(tokens) {
+            Some(TokenEnum::OpenBrace) => self.type_list(tokens).map(VariantKind::Tuple),
+            Some(TokenEnum::OpenCurlyBrace) => self.field_list(tokens).map(VariantKind::Record),
+            _ => Ok(VariantKind::Unit),
+        }
+    }
+
+    /// `trait Name { func method (Self) (Returns); }`
+    fn r#trait(&self, tokens: &mut Tokens) -> Result<Trait, CompileError> {
+        self.token(TokenEnum::KWTrait)
+            .then(self.ident())
+            .then(self.punct(TokenEnum::OpenCurlyBrace))
+            .repeat(
+                is_not(TokenEnum::CloseCurlyBrace),
+                self.rule(Self::trait_method),
+            )
+            .then(self.punct(TokenEnum::CloseCurlyBrace))
+            .transform(|((((location, name), _), methods), _)| Trait {
+                public: false,
+                doc: None,
+                name,
+                methods,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `func method (Self) (Returns);`
+    fn trait_method(&self, tokens: &mut Tokens) -> Result<TraitMethod, CompileError> {
+        let doc = self.doc(tokens);
+        self.token(TokenEnum::KWFunc)
+            .then(self.ident())
+            .then(self.rule(Self::param_list))
+            .then(self.rule(Self::type_list))
+            .then(self.punct(TokenEnum::SemiColon))
+            .transform(|((((location, name), params), returns), _)| TraitMethod {
+                doc: doc.clone(),
+                name,
+                params,
+                returns,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `impl Trait for Type { func method (Type) (Returns) { body } }`
+    fn r#impl(&self, tokens: &mut Tokens) -> Result<Impl, CompileError> {
+        self.token(TokenEnum::KWImpl)
+            .then(self.rule(Self::path))
+            .then(self.punct(TokenEnum::KWFor))
+            .then(self.rule(Self::r#type))
+            .then(self.punct(TokenEnum::OpenCurlyBrace))
+            .repeat(
+                is_not(TokenEnum::CloseCurlyBrace),
+                self.rule(|this, tokens| this.method(tokens, false)),
+            )
+            .then(self.punct(TokenEnum::CloseCurlyBrace))
+            .transform(|((((((location, r#trait), _), ty), _), methods), _)| Impl {
+                r#trait,
+                ty,
+                methods,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// A method of an object or an impl with its doc comments, only the methods of an object can
+    /// be `pub`
+    fn method(&self, tokens: &mut Tokens, public: bool) -> Result<Func, CompileError> {
+        let location = self.location(tokens);
+        let doc = self.doc(tokens);
+        Parser::new()
+            .optional(
+                move |token: &Token| public && token.token == TokenEnum::KWPub,
+                self.punct(TokenEnum::KWPub),
+            )
+            .then(self.rule(Self::func))
+            .transform(|(public, func)| Func {
+                public: public.is_some(),
+                doc: doc.clone(),
+                location: self.since(location),
+                ..func
+            })
+            .parse(tokens)
+    }
+
+    /// `(Type, ref Type, mut Type)`
+    fn param_list(&self, tokens: &mut Tokens) -> Result<Vec<Param>, CompileError> {
+        self.delimited(
+            TokenEnum::OpenBrace,
+            || self.rule(Self::param),
+            TokenEnum::Comma,
+            TokenEnum::CloseBrace,
+        )
+        .parse(tokens)
+    }
+
+    fn param(&self, tokens: &mut Tokens) -> Result<Param, CompileError> {
+        let location = self.location(tokens);
+        Parser::new()
+            .optional(
+                |token: &Token| PARAM_MODES.contains(&token.token),
+                self.one_of(PARAM_MODES),
+            )
+            .then(self.rule(Self::r#type))
+            .transform(|(mode, ty)| Param {
+                mode: match mode.map(|mode| mode.token) {
+                    Some(TokenEnum::KWRef) => ParamMode::Ref,
+                    Some(TokenEnum::KWMut) => ParamMode::Mut,
+                    _ => ParamMode::Value,
+                },
+                ty,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// `func name<T> (Params) (Returns) { body }`
+    fn func(&self, tokens: &mut Tokens) -> Result<Func, CompileError> {
+        self.token(TokenEnum::KWFunc)
+            .then(self.ident())
+            .then(self.angled(|| self.rule(Self::type_param)))
+            .then(self.rule(Self::param_list))
+            .then(self.rule(Self::type_list))
+            .then(self.rule(Self::block))
+            .transform(
+                |(((((location, name), type_params), params), returns), body)| Func {
+                    public: false,
+                    doc: None,
+                    name,
+                    type_params,
+                    params,
+                    returns,
+                    body,
+                    location: self.since(location),
+                },
+            )
+            .parse(tokens)
+    }
+
+    /// `{ pipeline; pipeline; tail }`
+    fn block(&self, tokens: &mut Tokens) -> Result<Block, CompileError> {
+        self.token(TokenEnum::OpenCurlyBrace)
+            .repeat(
+                is_not(TokenEnum::CloseCurlyBrace),
+                self.rule(Self::statement),
+            )
+            .then(self.punct(TokenEnum::CloseCurlyBrace))
+            .transform(|((location, statements), _)| {
+                let mut block = Block {
+                    statements: Vec::new(),
+                    tail: None,
+                    location: self.since(location),
+                };
+                for (pipeline, tail) in statements.into_iter().flatten() {
+                    if tail {
+                        block.tail = Some(Box::new(pipeline));
+                    } else {
+                        block.statements.push(pipeline);
+                    }
+                }
+                block
+            })
+            .parse(tokens)
+    }
+
+    /// A pipeline in a block and whether it is the tail the block evaluates to, `None` for an
+    /// empty statement or one whose error has been reported and skipped
+    fn statement(&self, tokens: &mut Tokens) -> Result<Option<(Pipeline, bool)>, CompileError> {
+        if peek(tokens) == Some(&TokenEnum::SemiColon) {
+            return self.punct(TokenEnum::SemiColon).parse(tokens).map(|_| None);
+        }
+
+        let open_braces = self.open_braces.get();
+        let statement = self
+            .rule(|this, tokens| this.pipeline(tokens, false))
+            .then(self.rule(Self::statement_end))
+            .parse(tokens);
+        Ok(self.recover(tokens, statement, |this, tokens| {
+            this.skip_statement(tokens, open_braces, false)
+        }))
+    }
+
+    /// Takes the `;` after a statement, a pipeline followed by the end of the block is its tail
+    fn statement_end(&self, tokens: &mut Tokens) -> Result<bool, CompileError> {
+        match peek(tokens) {
+            Some(TokenEnum::SemiColon) => self
+                .punct(TokenEnum::SemiColon)
+                .parse(tokens)
+                .map(|_| false),
+            Some(TokenEnum::CloseCurlyBrace) | None => Ok(true),
+            _ => Err(self.unexpected(tokens, STATEMENT_END)),
+        }
+    }
+
+    /// A chain of stages, when `in_arm` is set a `|?` ends the pipeline instead of starting a new
+    /// match, so that it can be read as the next arm of the enclosing match
+    fn pipeline(&self, tokens: &mut Tokens, in_arm: bool) -> Result<Pipeline, CompileError> {
+        let location = self.location(tokens);
+        let starts_stage = move |token: &Token| match token.token {
+            TokenEnum::PipeNext
+            | TokenEnum::PipeSet
+            | TokenEnum::PipeError
+            | TokenEnum::PipeReturn => true,
+            TokenEnum::PipeMatch => !in_arm,
+            _ => false,
+        };
+        if !tokens
+            .peek()
+            .is_some_and(|token| starts_stage(token) || starts_expression(token))
+        {
+            return Err(self.unexpected(tokens, STAGE_START));
+        }
+
+        Parser::new()
+            .optional(starts_expression, self.rule(Self::value))
+            .repeat(starts_stage, self.rule(Self::stage))
+            .transform(|(value, stages)| Pipeline {
+                stages: value
+                    .into_iter()
+                    .chain(stages.into_iter().flatten())
+                    .collect(),
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// A stage that is just an expression, which starts a pipeline or follows a `|=`
+    fn value(&self, tokens: &mut Tokens) -> Result<Stage, CompileError> {
+        let location = self.location(tokens);
+        self.rule(Self::expr)
+            .transform(|expr| Stage {
+                kind: StageKind::Value(expr),
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    /// The stage after a pipe, a `|= name` can be followed by a value to carry on with
+    fn stage(&self, tokens: &mut Tokens) -> Result<Vec<Stage>, CompileError> {
+        let location = self.location(tokens);
+        let stage = |kind: StageKind| Stage {
+            kind,
+            location: self.since(location),
+        };
+        match peek(tokens) {
+            Some(TokenEnum::PipeNext) => self
+                .punct(TokenEnum::PipeNext)
+                .then(self.rule(Self::section))
+                .transform(|(_, expr)| vec![stage(StageKind::Next(expr))])
+                .parse(tokens),
+            Some(TokenEnum::PipeSet) => self
+                .punct(TokenEnum::PipeSet)
+                .then(self.ident())
+                .transform(|(_, name)| stage(StageKind::Set(name)))
+                .optional(starts_expression, self.rule(Self::value))
+                .transform(|(set, value)| iter::once(set).chain(value).collect())
+                .parse(tokens),
+            Some(TokenEnum::PipeMatch) => self
+                .rule(Self::match_arms)
+                .transform(|arms| vec![stage(StageKind::Match(arms))])
+                .parse(tokens),
+            Some(TokenEnum::PipeError) => self
+                .punct(TokenEnum::PipeError)
+                .then(self.rule(Self::expr))
+                .transform(|(_, expr)| vec![stage(StageKind::Error(expr))])
+                .parse(tokens),
+            Some(TokenEnum::PipeReturn) => self
+                .punct(TokenEnum::PipeReturn)
+                .transform(|_| vec![stage(StageKind::Return)])
+                .parse(tokens),
+            _ => Err(self.unexpected(tokens, STAGE_START)),
+        }
+    }
+
+    /// `|? pattern -> pipeline |? pattern -> pipeline \?`
+    fn match_arms(&self, tokens: &mut Tokens) -> Result<Vec<MatchArm>, CompileError> {
+        Parser::new()
+            .repeat(is(TokenEnum::PipeMatch), self.rule(Self::match_arm_or_skip))
+            .then(self.punct(TokenEnum::PipeMatchEnd))
+            .transform(|(arms, _)| arms.into_iter().flatten().collect())
+            .parse(tokens)
+    }
+
+    /// A match arm, or `None` once an error in it has been reported and skipped
+    fn match_arm_or_skip(&self, tokens: &mut Tokens) -> Result<Option<MatchArm>, CompileError> {
+        let open_braces = self.open_braces.get();
+        let arm = self.match_arm(tokens);
+        Ok(self.recover(tokens, arm, |this, tokens| {
+            this.skip_statement(tokens, open_braces, true)
+        }))
+    }
+
+    /// `|? pattern -> pipeline`
+    fn match_arm(&self, tokens: &mut Tokens) -> Result<MatchArm, CompileError> {
+        self.token(TokenEnum::PipeMatch)
+            .then(self.rule(Self::pattern))
+            .then(self.punct(TokenEnum::Arrow))
+            .then(self.rule(|this, tokens| this.pipeline(tokens, true)))
+            .transform(|(((location, pattern), _), body)| MatchArm {
+                pattern,
+                body,
+                location: self.since(location),
+            })
+            .parse(tokens)
+    }
+
+    fn pattern(&self, tokens: &mut Tokens) -> Result<Pattern, CompileError> {
+        self.pattern_with(tokens, true)
+    }
+
+    /// A pattern, the patterns after a variant are read as its payload when `payload` is set.
+    /// Payload patterns are read without it, so a variant with a payload inside another one is
+    /// written in parentheses, `Shape::Scaled (Size::Fixed n) factor`
+    fn pattern_with(&self, tokens: &mut Tokens, payload: bool) -> Result<Pattern, CompileError> {
+        let location = self.location(tokens);
+        let pattern = |kind: PatternKind| Pattern {
+            kind,
+            location: self.since(location),
+        };
+        match peek(tokens) {
+            Some(TokenEnum::Identifier(name)) if name == "_" => self
+                .punct(TokenEnum::Identifier("_".into()))
+                .transform(|_| pattern(PatternKind::Wildcard))
+                .parse(tokens),
+            Some(TokenEnum::Identifier(_)) => {
+                let mut path = self.path(tokens)?;
+                if path.segments.len() == 1 {
+                    return Ok(pattern(PatternKind::Binding(path.segments.remove(0))));
+                }
+                Parser::new()
+                    .repeat(
+                        move |token: &Token| payload && starts_payload(token),
+                        self.rule(|this, tokens| this.pattern_with(tokens, false)),
+                    )
+                    .parse(tokens)
+                    .map(|patterns| pattern(PatternKind::Variant(path, patterns)))
+            }
+            Some(TokenEnum::OpenBrace) => self
+                .punct(TokenEnum::OpenBrace)
+                .optional(
+                    is_not(TokenEnum::CloseBrace),
+                    self.separated(|| self.rule(Self::pattern), TokenEnum::Comma),
+                )
+                .then(self.punct(TokenEnum::CloseBrace))
+                .transform(|((_, patterns), _)| {
+                    pattern(match patterns {
+                        None => PatternKind::Literal(Literal::Unit),
+                        Some(mut patterns) if patterns.len() == 1 => patterns.remove(0).kind,
+                        Some(patterns) => PatternKind::Tuple(patterns),
+                    })
+                })
+                .parse(tokens),
+            Some(token) => match comparison_op(token) {
+                Some(op) => self
+                    .operator(COMPARISON)
+                    .then(self.rule(move |this, tokens| this.operand(tokens, op)))
+                    .transform(|(op, operand)| pattern(PatternKind::Compare(op, operand)))
+                    .parse(tokens),
+                None => self.pattern_literal(tokens, pattern),
+            },
+            None => Err(self.unexpected(tokens, PATTERN_START)),
+        }
+    }
+
+    /// The patterns that are not a path, group or comparison
+    fn pattern_literal(
+        &self,
+        tokens: &mut Tokens,
+        pattern: impl Fn(PatternKind) -> Pattern,
+    ) -> Result<Pattern, CompileError> {
+        match peek(tokens) {
+            Some(TokenEnum::OpenCurlyBrace) => self
+                .delimited(
+                    TokenEnum::OpenCurlyBrace,
+                    || self.rule(Self::field_pattern),
+                    TokenEnum::Comma,
+                    TokenEnum::CloseCurlyBrace,
+                )
+                .transform(|fields| pattern(PatternKind::Record(fields)))
+                .parse(tokens),
+            Some(
+                TokenEnum::Integer(..)
+                | TokenEnum::Float(..)
+                | TokenEnum::Bool(_)
+                | TokenEnum::String(_),
+            ) => self
+                .literal()
+                .transform(|literal| pattern(PatternKind::Literal(literal)))
+                .parse(tokens),
+            _ => Err(self.unexpected(tokens, PATTERN_START)),
+        }
+    }
+
+    /// `name: pattern`, or `name` which binds the field to its own name
+    fn field_pattern(&self, tokens: &mut Tokens) -> Result<(Ident, Pattern), CompileError> {
+        self.ident()
+            .optional(
+                is(TokenEnum::Colon),
+                self.punct(TokenEnum::Colon)
+                    .then(self.rule(Self::pattern))
+                    .transform(|(_, pattern)| pattern),
+            )
+            .transform(|(name, pattern)| {
+                let pattern = pattern.unwrap_or_else(|| Pattern {
+                    location: name.location,
+                    kind: PatternKind::Binding(name.clone()),
+                });
+                (name, pattern)
+            })
+            .parse(tokens)
+    }
+
+    fn expr(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.range(tokens)
+    }
+
+    /// The expression of a `|>` stage, which may be an operator section such as `+ n`
+    fn section(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        let Some(op) = peek(tokens).and_then(binary_op) else {
+            return self.expr(tokens);
+        };
+        let location = self.location(tokens);
+        self.one_of(BINARY_OPERATORS)
+            .then(self.rule(move |this, tokens| this.operand(tokens, op)))
+            .transform(|(_, operand)| Expr {
+                location: location.to(operand.location),
+                kind: ExprKind::Section(op, Box::new(operand)),
+            })
+            .parse(tokens)
+    }
+
+    /// The right operand of `op`, which binds everything tighter than `op` itself
+    fn operand(&self, tokens: &mut Tokens, op: BinaryOp) -> Result<Expr, CompileError> {
+        match op {
+            BinaryOp::Or => self.and(tokens),
+            BinaryOp::And => self.comparison(tokens),
+            BinaryOp::LessThan
+            | BinaryOp::GreaterThan
+            | BinaryOp::LessEqual
+            | BinaryOp::GreaterEqual
+            | BinaryOp::Equal
+            | BinaryOp::NotEqual => self.additive(tokens),
+            BinaryOp::Plus | BinaryOp::Minus => self.multiplicative(tokens),
+            BinaryOp::Times | BinaryOp::Divide | BinaryOp::Modulo | BinaryOp::Power => {
+                self.power(tokens)
+            }
+        }
+    }
+
+    /// `start..end` or `start..=end`, ranges do not chain so there is at most one operator
+    fn range(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.rule(Self::or)
+            .optional(
+                |token: &Token| RANGES.contains(&token.token),
+                self.one_of(RANGES).then(self.rule(Self::or)),
+            )
+            .transform(|(start, end)| match end {
+                None => start,
+                Some((operator, end)) => Expr {
+                    location: start.location.to(end.location),
+                    kind: ExprKind::Range {
+                        start: Box::new(start),
+                        end: Box::new(end),
+                        inclusive: operator.token == TokenEnum::RangeInclusive,
+                    },
+                },
+            })
+            .parse(tokens)
+    }
+
+    /// `operand op operand op operand`, left associative
+    fn binary(
+        &self,
+        tokens: &mut Tokens,
+        operand: fn(&Self, &mut Tokens) -> Result<Expr, CompileError>,
+        operators: &'static [TokenEnum],
+    ) -> Result<Expr, CompileError> {
+        self.rule(operand)
+            .repeat(
+                |token: &Token| operators.contains(&token.token),
+                self.operator(operators).then(self.rule(operand)),
+            )
+            .transform(|(first, rest)| {
+                rest.into_iter().fold(first, |lhs, (op, rhs)| Expr {
+                    location: lhs.location.to(rhs.location),
+                    kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                })
+            })
+            .parse(tokens)
+    }
+
+    fn or(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.binary(tokens, Self::and, OR)
+    }
+
+    fn and(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.binary(tokens, Self::comparison, AND)
+    }
+
+    fn comparison(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.binary(tokens, Self::additive, COMPARISON)
+    }
+
+    fn additive(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.binary(tokens, Self::multiplicative, ADDITIVE)
+    }
+
+    fn multiplicative(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.binary(tokens, Self::power, MULTIPLICATIVE)
+    }
+
+    /// `^` is right associative
+    fn power(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        self.rule(Self::not)
+            .optional(
+                is(TokenEnum::Power),
+                self.operator(POWER).then(self.rule(Self::power)),
+            )
+            .transform(|(lhs, rhs)| match rhs {
+                None => lhs,
+                Some((op, rhs)) => Expr {
+                    location: lhs.location.to(rhs.location),
+                    kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                },
+            })
+            .parse(tokens)
+    }
+
+    /// `!expr` negates a whole call, `!even n` is `!(even n)`
+    fn not(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        if peek(tokens) != Some(&TokenEnum::Not) {
+            return self.application(tokens);
+        }
+        self.token(TokenEnum::Not)
+            .then(self.rule(Self::not))
+            .transform(|(location, expr)| Expr {
+                location: location.to(expr.location),
+                kind: ExprKind::Not(Box::new(expr)),
+            })
+            .parse(tokens)
+    }
+
+    /// `path arg arg`, a path followed by atoms is a call
+    fn application(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        if !matches!(peek(tokens), Some(TokenEnum::Identifier(_))) {
+            return self.atom(tokens);
+        }
+
+        let path = self.path(tokens)?;
+        let location = path.location;
+        if tokens.peek().is_some_and(|token| self.follows_field(token)) {
+            let expr = Expr {
+                kind: ExprKind::Path(path),
+                location,
+            };
+            return self.fields(tokens, expr);
+        }
+
+        Parser::new()
+            .repeat(starts_expression, self.rule(Self::atom))
+            .parse(tokens)
+            .map(|args| Expr {
+                location: self.since(location),
+                kind: if args.is_empty() {
+                    ExprKind::Path(path)
+                } else {
+                    ExprKind::Call(path, args)
+                },
+            })
+    }
+
+    fn atom(&self, tokens: &mut Tokens) -> Result<Expr, CompileError> {
+        let location = self.location(tokens);
+        let kind = match peek(tokens) {
+            Some(TokenEnum::Identifier(_)) => self
+                .rule(Self::path)
+                .transform(ExprKind::Path)
+                .parse(tokens)?,
+            Some(TokenEnum::Field(_)) => self
+                .field()
+                .transform(|field| {
+                    let current = Expr {
+                        kind: ExprKind::Current,
+                        location,
+                    };
+                    ExprKind::Field(Box::new(current), field)
+                })
+                .parse(tokens)?,
+            Some(TokenEnum::Period) => self
+                .punct(TokenEnum::Period)
+                .optional(
+                    is(TokenEnum::OpenCurlyBrace),
+                    self.punct(TokenEnum::OpenCurlyBrace)
+                        .then(self.ident())
+                        .then(self.punct(TokenEnum::CloseCurlyBrace))
+                        .then(self.rule(Self::expr)),
+                )
+                .transform(|(_, update)| match update {
+                    Some((((_, field), _), value)) => ExprKind::Update(field, Box::new(value)),
+                    None => ExprKind::Current,
+                })
+                .parse(tokens)?,
+            Some(TokenEnum::OpenBrace) => self
+                .punct(TokenEnum::OpenBrace)
+                .optional(
+                    is_not(TokenEnum::CloseBrace),
+                    self.rule(|this, tokens| this.pipeline(tokens, false)),
+                )
+                .then(self.punct(TokenEnum::CloseBrace))
+                .transform(|((_, pipeline), _)| match pipeline {
+                    Some(pipeline) => ExprKind::Group(Box::new(pipeline)),
+                    None => ExprKind::Literal(Literal::Unit),
+                })
+                .parse(tokens)?,
+            Some(TokenEnum::OpenCurlyBrace) => self
+                .delimited(
+                    TokenEnum::OpenCurlyBrace,
+                    || self.rule(Self::record_field),
+                    TokenEnum::Comma,
+                    TokenEnum::CloseCurlyBrace,
+                )
+                .transform(ExprKind::Record)
+                .parse(tokens)?,
+            Some(
+                TokenEnum::Integer(..)
+                | TokenEnum::Float(..)
+                | TokenEnum::Bool(_)
+                | TokenEnum::String(_),
+            ) => self.literal().transform(ExprKind::Literal).parse(tokens)?,
+            Some(TokenEnum::KWFunc) => self
+                .punct(TokenEnum::KWFunc)
+                .then(self.rule(Self::block))
+                .transform(|(_, block)| ExprKind::Closure(Rc::new(block)))
+                .parse(tokens)?,
+            // As an argument `!` only negates the next atom, `f !x` passes `!x` to `f`
+            Some(TokenEnum::Not) => self
+                .punct(TokenEnum::Not)
+                .then(self.rule(Self::atom))
+                .transform(|(_, atom)| ExprKind::Not(Box::new(atom)))
+                .parse(tokens)?,
+            Some(TokenEnum::InterpolatedString(_)) => self
+                .rule(Self::interpolation)
+                .transform(ExprKind::Interpolation)
+                .parse(tokens)?,
+            _ => return Err(self.unexpected(tokens, EXPRESSION_START)),
+        };
+        let expr = Expr {
+            kind,
+            location: self.since(location),
+        };
+        self.fields(tokens, expr)
+    }
+
+    /// `name: expr` in a record, or `name` which is short for `name: name`
+    fn record_field(&self, tokens: &mut Tokens) -> Result<(Ident, Expr), CompileError> {
+        self.ident()
+            .optional(
+                is(TokenEnum::Colon),
+                self.punct(TokenEnum::Colon)
+                    .then(self.rule(Self::expr))
+                    .transform(|(_, value)| value),
+            )
+            .transform(|(name, value)| {
+                let value = value.unwrap_or_else(|| Expr {
+                    location: name.location,
+                    kind: ExprKind::Path(Path {
+                        location: name.location,
+                        segments: vec![name.clone()],
+                    }),
+                });
+                (name, value)
+            })
+            .parse(tokens)
+    }
+
+    /// Whether a token is a `.name` written directly after the last token, which makes it a
+    /// field of the previous expression rather than a field of the pipe value
+    fn follows_field(&self, token: &Token) -> bool {
+        matches!(
+            token,
+            Token { token: TokenEnum::Field(_), span } if span.start == self.last.get().end
+        )
+    }
+
+    /// `expr.field.field`
+    fn fields(&self, tokens: &mut Tokens, expr: Expr) -> Result<Expr, CompileError> {
+        Parser::new()
+            .repeat(|token: &Token| self.follows_field(token), self.field())
+            .parse(tokens)
+            .map(|fields| {
+                fields.into_iter().fold(expr, |expr, field| Expr {
+                    location: expr.location.to(field.location),
+                    kind: ExprKind::Field(Box::new(expr), field),
+                })
+            })
+    }
+
+    /// An interpolated string, parsing the pipeline in each of its `#{...}`
+    fn interpolation(&self, tokens: &mut Tokens) -> Result<Vec<InterpolationPart>, CompileError> {
+        let missing = self.end_of_input(TokenEnum::InterpolatedString(Vec::new()));
+        let parts = parser!(
+            take Token { token: TokenEnum::InterpolatedString(parts), .. } => parts,
+            else token => CompileError::new(
+                token.span,
+                CompileErrorEnum::UnexpectedToken(token.token),
+            ), missing;
+        )
+        .parse(tokens)?;
+
+        // The code is parsed as its own stream of tokens, running out of it is reported at its
+        // last token or at the `#{` if it is empty
+        let (last, end, open_braces) = (self.last.get(), self.end.get(), self.open_braces.get());
+        let mut result = Vec::with_capacity(parts.len());
+        let mut errors = Vec::new();
+        for part in parts {
+            match part {
+                StringPart::Text(text) => result.push(InterpolationPart::Text(text)),
+                StringPart::Code(code, span) => {
+                    self.last.set(span);
+                    self.end.set(last_span(&code, span));
+                    let mut code = self.stream(code);
+                    match self.pipeline(&mut code, false) {
+                        Ok(_) if code.peek().is_some() => {
+                            errors.push(self.unexpected(&mut code, &[TokenEnum::CloseCurlyBrace]))
+                        }
+                        Ok(pipeline) => {
+                            // A lone value is kept as an expression, anything else is a group
+                            let expr = match pipeline.stages.as_slice() {
+                                [Stage {
+                                    kind: StageKind::Value(expr),
+                                    ..
+                                }] => expr.clone(),
+                                _ => Expr {
+                                    location: pipeline.location,
+                                    kind: ExprKind::Group(Box::new(pipeline)),
+                                },
+                            };
+                            result.push(InterpolationPart::Expr(expr));
+                        }
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+        }
+        self.last.set(last);
+        self.end.set(end);
+        self.open_braces.set(open_braces);
+
+        // The first error is returned to the caller to recover from, the rest are reported here
+        let mut errors = errors.into_iter();
+        match errors.next() {
+            Some(first) => {
+                errors.for_each(|error| self.report(error));
+                Err(first)
+            }
+            None => Ok(result),
+        }
+    }
+}
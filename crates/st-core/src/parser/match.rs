@@ -1,4 +1,4 @@
-use super::{Parser, ParserBase, ParserStep};
+use super::{Parser, ParserBase, ParserStep, Stream};
 
 pub struct Match<F, P: ParserBase>
 where
@@ -12,10 +12,14 @@ where
 impl<F, P: ParserBase> Match<F, P>
 where
     F: Fn(P::Item) -> Result<(), P::Error>,
+    P::Item: Clone,
 {
-    fn r#do(&self, item: Option<P::Item>) -> Result<(), P::Error> {
-        item.ok_or(self.missing.clone())
-            .and_then(|item| (self.matcher)(item))
+    /// The item is only taken from the stream if it matches
+    fn r#do(&self, stream: &mut dyn Stream<Item = P::Item>) -> Result<(), P::Error> {
+        let item = stream.peek().cloned().ok_or(self.missing.clone())?;
+        (self.matcher)(item).map(|_| {
+            stream.next();
+        })
     }
 }
 
@@ -28,28 +32,29 @@ where
     type Error = P::Error;
 }
 
-impl<F, I, E: Clone> ParserStep for Match<F, Parser<I, E>>
+impl<F, I: Clone, E: Clone> ParserStep for Match<F, Parser<I, E>>
 where
     F: Fn(I) -> Result<(), E>,
 {
-    fn execute(
+    fn parse(
         &self,
-        mut iter: impl Iterator<Item = Self::Item>,
+        stream: &mut dyn Stream<Item = Self::Item>,
     ) -> Result<Self::Output, Self::Error> {
-        self.r#do(iter.next())
+        self.r#do(stream)
     }
 }
 
 impl<F, P: ParserStep> ParserStep for Match<F, P>
 where
     F: Fn(P::Item) -> Result<(), P::Error>,
+    P::Item: Clone,
 {
-    fn execute(
+    fn parse(
         &self,
-        mut iter: impl Iterator<Item = Self::Item>,
+        stream: &mut dyn Stream<Item = Self::Item>,
     ) -> Result<Self::Output, Self::Error> {
         self.parent
-            .execute(iter.by_ref())
-            .and_then(|result| self.r#do(iter.next()).map(|_| result))
+            .parse(stream)
+            .and_then(|result| self.r#do(stream).map(|_| result))
     }
 }
@@ -1,17 +1,36 @@
+mod grammar;
 pub mod r#match;
 pub mod optional;
 pub mod parser_macro;
+pub mod repeat;
+pub mod rule;
 pub mod take;
 pub mod then;
 pub mod transform;
 
-use crate::{error::CompileError, tokenizer::TokenEnum};
-use std::marker::PhantomData;
+use crate::{ast::Module, error::CompileError, tokenizer::Token};
+use std::{iter::Peekable, marker::PhantomData};
 
-use self::{optional::Optional, r#match::Match, take::Take, then::Then, transform::Transform};
+use self::{
+    grammar::ModuleParser, optional::Optional, r#match::Match, repeat::Repeat, rule::Rule,
+    take::Take, then::Then, transform::Transform,
+};
 
-pub fn parse(mut _iter: impl Iterator<Item = TokenEnum>) -> Result<(), CompileError> {
-    todo!()
+/// Parses a whole source file into a [Module], returning every syntax error found
+pub fn parse(iter: impl Iterator<Item = Token>) -> Result<Module, Vec<CompileError>> {
+    ModuleParser::new(iter).module()
+}
+
+/// The items a parser runs over, the next item can be looked at without taking it so that a
+/// step which does not apply leaves it for the steps after it
+pub trait Stream: Iterator {
+    fn peek(&mut self) -> Option<&Self::Item>;
+}
+
+impl<I: Iterator> Stream for Peekable<I> {
+    fn peek(&mut self) -> Option<&I::Item> {
+        Peekable::peek(self)
+    }
 }
 
 pub trait ParserBase: Sized
@@ -115,10 +134,29 @@ where
             parent: self,
         }
     }
+
+    fn repeat<F, P>(self, test: F, parser: P) -> Repeat<F, P, Self>
+    where
+        F: Fn(&Self::Item) -> bool,
+        P: ParserStep<Item = Self::Item, Error = Self::Error>,
+    {
+        Repeat {
+            test,
+            parser,
+            parent: self,
+        }
+    }
 }
 
 pub trait ParserStep: ParserBase {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error>;
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error>;
+
+    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
+        self.parse(&mut iter.peekable())
+    }
 }
 
 pub struct Parser<I, E: Clone> {
@@ -134,11 +172,20 @@ where
     }
 }
 
+impl<I, E> Default for Parser<I, E>
+where
+    E: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<I, E: Clone> ParserBase for Parser<I, E> {
     type Output = ();
     type Item = I;
     type Error = E;
 }
 
-// #[cfg(test)]
+#[cfg(test)]
 mod tests;
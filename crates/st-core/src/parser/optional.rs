@@ -1,6 +1,6 @@
 mod with_parser;
 
-use super::{Parser, ParserBase, ParserStep};
+use super::{Parser, ParserBase, ParserStep, Stream};
 
 pub struct Optional<I, F, P, T, S>
 where
@@ -31,13 +31,15 @@ where
     F: Fn(&S::Item) -> bool,
     P: ParserStep<Item = S::Item, Error = S::Error>,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        self.parent.execute(iter.by_ref()).and_then(|res| {
-            let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent.parse(stream).and_then(|res| {
+            let peeked = stream.peek();
             if let Some(value) = peeked {
                 if (self.test)(value) {
-                    return self.parser.execute(iter).map(|res2| (res, Some(res2)));
+                    return self.parser.parse(stream).map(|res2| (res, Some(res2)));
                 }
             }
             Ok((res, None))
@@ -64,15 +66,17 @@ where
     P: ParserStep<Item = S::Item, Error = S::Error>,
     T: Fn(P::Output) -> TO,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        self.parent.execute(iter.by_ref()).and_then(|res| {
-            let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent.parse(stream).and_then(|res| {
+            let peeked = stream.peek();
             if let Some(value) = peeked {
                 if (self.test)(value) {
                     return self
                         .parser
-                        .execute(iter)
+                        .parse(stream)
                         .map(|res2| (res, Some((self.transform)(res2))));
                 }
             }
@@ -98,15 +102,17 @@ where
     F: Fn(&S::Item) -> Option<I>,
     P: ParserStep<Item = S::Item, Error = S::Error>,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        self.parent.execute(iter.by_ref()).and_then(|res| {
-            let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent.parse(stream).and_then(|res| {
+            let peeked = stream.peek();
             if let Some(value) = peeked {
                 if let Some(tested) = (self.test)(value) {
                     return self
                         .parser
-                        .execute(iter)
+                        .parse(stream)
                         .map(|out| (res, Some((tested, out))));
                 }
             }
@@ -134,15 +140,17 @@ where
     P: ParserStep<Item = S::Item, Error = S::Error>,
     T: Fn(I, P::Output) -> TO,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        self.parent.execute(iter.by_ref()).and_then(|res| {
-            let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent.parse(stream).and_then(|res| {
+            let peeked = stream.peek();
             if let Some(value) = peeked {
                 if let Some(tested) = (self.test)(value) {
                     return self
                         .parser
-                        .execute(iter)
+                        .parse(stream)
                         .map(|out| (res, Some((self.transform)(tested, out))));
                 }
             }
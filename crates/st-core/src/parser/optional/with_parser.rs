@@ -17,12 +17,14 @@ where
     P: ParserStep<Item = I, Error = E>,
     E: Clone,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        let peeked = stream.peek();
         if let Some(value) = peeked {
             if (self.test)(value) {
-                return self.parser.execute(iter).map(|res2| Some(res2));
+                return self.parser.parse(stream).map(Some);
             }
         }
         Ok(None)
@@ -48,14 +50,16 @@ where
     T: Fn(P::Output) -> TO,
     E: Clone,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        let peeked = stream.peek();
         if let Some(value) = peeked {
             if (self.test)(value) {
                 return self
                     .parser
-                    .execute(iter)
+                    .parse(stream)
                     .map(|res2| Some((self.transform)(res2)));
             }
         }
@@ -80,12 +84,14 @@ where
     P: ParserStep<Item = Item, Error = E>,
     E: Clone,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        let peeked = stream.peek();
         if let Some(value) = peeked {
             if let Some(tested) = (self.test)(value) {
-                return self.parser.execute(iter).map(|out| Some((tested, out)));
+                return self.parser.parse(stream).map(|out| Some((tested, out)));
             }
         }
         Ok(None)
@@ -111,14 +117,16 @@ where
     T: Fn(I, P::Output) -> TO,
     E: Clone,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        let mut iter = iter.peekable();
-        let peeked = iter.peek();
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        let peeked = stream.peek();
         if let Some(value) = peeked {
             if let Some(tested) = (self.test)(value) {
                 return self
                     .parser
-                    .execute(iter)
+                    .parse(stream)
                     .map(|out| Some((self.transform)(tested, out)));
             }
         }
@@ -27,7 +27,7 @@ macro_rules! parser {
                 )
             }}
             $($rest)*
-        );
+        )
     };
 
     (match $case:pat, else $err:ident => $err_case:expr, $missing:expr; $($rest:tt)*) => {
@@ -67,7 +67,7 @@ macro_rules! parser {
     };
     ({{ $parent:expr }} then $parser_name:ident; $($rest:tt)*) => {
         parser!({{
-                $crate::parser::parser_macro::r#match($parent, $parser_name)
+                $crate::parser::parser_macro::then($parent, $parser_name)
             }}
             $($rest)*
         )
@@ -86,7 +86,7 @@ macro_rules! parser {
     };
     ({{ $parent:expr }} then { $($sub_parser:tt)* }; $($rest:tt)*) => {
         parser!({{
-                $crate::parser::parser_macro::r#then($parent, parser!($($sub_parser)*))
+                $crate::parser::parser_macro::then($parent, parser!($($sub_parser)*))
             }}
             $($rest)*
         )
@@ -124,7 +124,7 @@ where
     Match {
         matcher,
         missing,
-        parent: parent,
+        parent,
     }
 }
 
@@ -140,7 +140,7 @@ where
     Take {
         matcher,
         missing,
-        parent: parent,
+        parent,
     }
 }
 
@@ -152,7 +152,7 @@ where
 {
     Transform {
         transformer,
-        parent: parent,
+        parent,
     }
 }
 
@@ -161,10 +161,7 @@ where
     Parent: ParserBase,
     T: ParserStep<Item = Parent::Item, Error = Parent::Error>,
 {
-    Then {
-        parser,
-        parent: parent,
-    }
+    Then { parser, parent }
 }
 
 pub const fn optional<Parent, I, F, P>(
@@ -181,7 +178,7 @@ where
         test,
         parser,
         transform: (),
-        parent: parent,
+        parent,
     }
 }
 
@@ -201,7 +198,7 @@ where
         test,
         parser,
         transform,
-        parent: parent,
+        parent,
     }
 }
 
@@ -221,6 +218,26 @@ where
         test,
         parser,
         transform,
-        parent: parent,
+        parent,
     }
 }
+
+pub const fn repeat<Parent, F, P>(parent: Parent, test: F, parser: P) -> Repeat<F, P, Parent>
+where
+    Parent: ParserBase,
+    F: Fn(&Parent::Item) -> bool,
+    P: ParserStep<Item = Parent::Item, Error = Parent::Error>,
+{
+    Repeat {
+        test,
+        parser,
+        parent,
+    }
+}
+
+pub const fn rule<F, O, I, E>(rule: F) -> Rule<F, I, E>
+where
+    F: Fn(&mut dyn Stream<Item = I>) -> Result<O, E>,
+{
+    Rule::new(rule)
+}
@@ -0,0 +1,80 @@
+use super::{Parser, ParserBase, ParserStep, Stream};
+
+pub struct Repeat<F, P, S>
+where
+    S: ParserBase,
+    F: Fn(&S::Item) -> bool,
+    P: ParserStep<Item = S::Item, Error = S::Error>,
+{
+    pub(super) test: F,
+    pub(super) parser: P,
+    pub(super) parent: S,
+}
+
+impl<F, P, S> Repeat<F, P, S>
+where
+    S: ParserBase,
+    F: Fn(&S::Item) -> bool,
+    P: ParserStep<Item = S::Item, Error = S::Error>,
+{
+    /// Runs the parser for as long as the next item passes the test
+    fn r#do(&self, stream: &mut dyn Stream<Item = S::Item>) -> Result<Vec<P::Output>, S::Error> {
+        let mut outputs = Vec::new();
+        while stream.peek().is_some_and(&self.test) {
+            outputs.push(self.parser.parse(stream)?);
+        }
+        Ok(outputs)
+    }
+}
+
+impl<F, P, I, E> ParserBase for Repeat<F, P, Parser<I, E>>
+where
+    F: Fn(&I) -> bool,
+    P: ParserStep<Item = I, Error = E>,
+    E: Clone,
+{
+    type Output = Vec<P::Output>;
+    type Item = I;
+    type Error = E;
+}
+
+impl<F, P, I, E> ParserStep for Repeat<F, P, Parser<I, E>>
+where
+    F: Fn(&I) -> bool,
+    P: ParserStep<Item = I, Error = E>,
+    E: Clone,
+{
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.r#do(stream)
+    }
+}
+
+impl<F, P, S> ParserBase for Repeat<F, P, S>
+where
+    S: ParserStep,
+    F: Fn(&S::Item) -> bool,
+    P: ParserStep<Item = S::Item, Error = S::Error>,
+{
+    type Output = (S::Output, Vec<P::Output>);
+    type Item = S::Item;
+    type Error = S::Error;
+}
+
+impl<F, P, S> ParserStep for Repeat<F, P, S>
+where
+    S: ParserStep,
+    F: Fn(&S::Item) -> bool,
+    P: ParserStep<Item = S::Item, Error = S::Error>,
+{
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent
+            .parse(stream)
+            .and_then(|res| self.r#do(stream).map(|res2| (res, res2)))
+    }
+}
@@ -0,0 +1,41 @@
+use super::{ParserBase, ParserStep, Stream};
+use std::marker::PhantomData;
+
+/// A step that runs a function on the stream, so that a production can refer to other
+/// productions, or to itself, by name
+pub struct Rule<F, I, E> {
+    pub(super) rule: F,
+    pub(super) _type: PhantomData<(I, E)>,
+}
+
+impl<F, I, E> Rule<F, I, E> {
+    pub const fn new(rule: F) -> Self {
+        Self {
+            rule,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<F, O, I, E> ParserBase for Rule<F, I, E>
+where
+    F: Fn(&mut dyn Stream<Item = I>) -> Result<O, E>,
+    E: Clone,
+{
+    type Output = O;
+    type Item = I;
+    type Error = E;
+}
+
+impl<F, O, I, E> ParserStep for Rule<F, I, E>
+where
+    F: Fn(&mut dyn Stream<Item = I>) -> Result<O, E>,
+    E: Clone,
+{
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        (self.rule)(stream)
+    }
+}
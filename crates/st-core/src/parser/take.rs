@@ -1,4 +1,4 @@
-use super::{Parser, ParserBase, ParserStep};
+use super::{Parser, ParserBase, ParserStep, Stream};
 
 pub struct Take<I, F, P>
 where
@@ -14,9 +14,14 @@ impl<I, F, P> Take<I, F, P>
 where
     P: ParserBase,
     F: Fn(P::Item) -> Result<I, P::Error>,
+    P::Item: Clone,
 {
-    fn r#do(&self, item: Option<P::Item>) -> Result<I, P::Error> {
-        item.ok_or(self.missing.clone()).and_then(&self.matcher)
+    /// The item is only taken from the stream if it matches
+    fn r#do(&self, stream: &mut dyn Stream<Item = P::Item>) -> Result<I, P::Error> {
+        let item = stream.peek().cloned().ok_or(self.missing.clone())?;
+        let output = (self.matcher)(item)?;
+        stream.next();
+        Ok(output)
     }
 }
 
@@ -33,13 +38,14 @@ where
 impl<I, F, Item, E> ParserStep for Take<I, F, Parser<Item, E>>
 where
     F: Fn(Item) -> Result<I, E>,
+    Item: Clone,
     E: Clone,
 {
-    fn execute(
+    fn parse(
         &self,
-        mut iter: impl Iterator<Item = Self::Item>,
+        stream: &mut dyn Stream<Item = Self::Item>,
     ) -> Result<Self::Output, Self::Error> {
-        self.r#do(iter.next())
+        self.r#do(stream)
     }
 }
 
@@ -56,14 +62,15 @@ where
 impl<I, F, P> ParserStep for Take<I, F, P>
 where
     P: ParserStep,
+    P::Item: Clone,
     F: Fn(P::Item) -> Result<I, P::Error>,
 {
-    fn execute(
+    fn parse(
         &self,
-        mut iter: impl Iterator<Item = Self::Item>,
+        stream: &mut dyn Stream<Item = Self::Item>,
     ) -> Result<Self::Output, Self::Error> {
         self.parent
-            .execute(iter.by_ref())
-            .and_then(|res| self.r#do(iter.next()).map(|res2| (res, res2)))
+            .parse(stream)
+            .and_then(|res| self.r#do(stream).map(|res2| (res, res2)))
     }
 }
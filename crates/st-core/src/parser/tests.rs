@@ -1,7 +1,7 @@
 use super::*;
 use crate::parser;
 
-// #[test]
+#[test]
 fn test_match() {
     // let parser = Parser::new()
     //     .r#match(
@@ -27,7 +27,7 @@ fn test_match() {
     assert_eq!(parser.execute(vec![1].into_iter()), Err(None));
 }
 
-// #[test]
+#[test]
 fn test_take() {
     // let parser = Parser::new()
     //     .r#match(
@@ -56,13 +56,13 @@ fn test_take() {
 
     assert_eq!(
         parser.execute(vec![1, 2, 3].into_iter()),
-        Ok((((()), "Two"), 3.0))
+        Ok((((), "Two"), 3.0))
     );
     assert_eq!(parser.execute(vec![1, 3, 3].into_iter()), Err(Some(3)));
     assert_eq!(parser.execute(vec![1].into_iter()), Err(None));
 }
 
-// #[test]
+#[test]
 fn test_transform() {
     // let parser = Parser::new()
     //     .r#match(
@@ -96,7 +96,7 @@ fn test_transform() {
     assert_eq!(parser.execute(vec![1].into_iter()), Err(None));
 }
 
-// #[test]
+#[test]
 fn test_then() {
     // let parser = Parser::new()
     //     .r#match(
@@ -137,3 +137,709 @@ fn test_then() {
     assert_eq!(parser.execute(vec![1, 3, 3].into_iter()), Err(Some(3)));
     assert_eq!(parser.execute(vec![1].into_iter()), Err(None));
 }
+
+#[test]
+fn test_optional() {
+    let parser = parser!(
+        match 1, else item => Some(item), None;
+    )
+    .optional(
+        |item| *item == 2,
+        parser!(take 2 => "Two", else item => Some(item), None;),
+    )
+    .then(parser!(take 3 => 3.0, else item => Some(item), None;));
+
+    assert_eq!(
+        parser.execute(vec![1, 2, 3].into_iter()),
+        Ok((((), Some("Two")), 3.0))
+    );
+    // The item the test looked at is left for the next step
+    assert_eq!(
+        parser.execute(vec![1, 3].into_iter()),
+        Ok((((), None), 3.0))
+    );
+    assert_eq!(parser.execute(vec![1, 4].into_iter()), Err(Some(4)));
+}
+
+#[test]
+fn test_repeat() {
+    let parser = parser!(
+        match 1, else item => Some(item), None;
+    )
+    .repeat(
+        |item| *item == 2,
+        parser!(take 2 => "Two", else item => Some(item), None;),
+    )
+    .then(parser!(take 3 => 3.0, else item => Some(item), None;));
+
+    assert_eq!(
+        parser.execute(vec![1, 2, 2, 3].into_iter()),
+        Ok((((), vec!["Two", "Two"]), 3.0))
+    );
+    assert_eq!(
+        parser.execute(vec![1, 3].into_iter()),
+        Ok((((), vec![]), 3.0))
+    );
+    assert_eq!(parser.execute(vec![1, 2, 4].into_iter()), Err(Some(4)));
+}
+
+#[test]
+fn test_rule() {
+    // `2* 3`, counting the `2`s by recursing into the rule for each of them
+    fn twos(stream: &mut dyn Stream<Item = i32>) -> Result<usize, Option<i32>> {
+        match stream.peek() {
+            Some(2) => parser!(
+                match 2, else item => Some(item), None;
+                then {{ parser_macro::rule(twos) }};
+                transform (_, count) => count + 1;
+            )
+            .parse(stream),
+            _ => parser!(match 3, else item => Some(item), None;)
+                .parse(stream)
+                .map(|_| 0),
+        }
+    }
+
+    let parser = parser_macro::rule(twos);
+    assert_eq!(parser.execute(vec![2, 2, 2, 3].into_iter()), Ok(3));
+    assert_eq!(parser.execute(vec![3].into_iter()), Ok(0));
+    assert_eq!(parser.execute(vec![2, 4].into_iter()), Err(Some(4)));
+}
+
+use crate::tokenizer::{Position, Span};
+
+fn parse_source(source: &str) -> Result<crate::ast::Module, Vec<CompileError>> {
+    parse(crate::tokenizer::tokenize(source).map(|token| token.unwrap()))
+}
+
+#[test]
+fn test_parse_func() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "pub func fib (Int32) (Int32) {
+            |? < 1 -> 1
+            |? _ -> |= n
+                    n - 1
+                    |> fib
+            \\?
+        }",
+    )
+    .unwrap();
+
+    let Item::Func(func) = &module.items[0] else {
+        panic!("Expected a func, found {:?}", module.items[0]);
+    };
+    assert!(func.public);
+    assert_eq!(func.name.name, "fib");
+    assert_eq!(
+        func.name.location,
+        Span::new(Position::new(9, 1, 10), Position::new(12, 1, 13))
+    );
+    assert_eq!(func.params.len(), 1);
+    assert_eq!(func.returns[0].path.name().name, "Int32");
+
+    let tail = func.body.tail.as_ref().unwrap();
+    let StageKind::Match(arms) = &tail.stages[0].kind else {
+        panic!("Expected a match, found {:?}", tail.stages[0]);
+    };
+    assert_eq!(arms.len(), 2);
+    assert!(matches!(
+        arms[0].pattern.kind,
+        PatternKind::Compare(BinaryOp::LessThan, _)
+    ));
+    assert_eq!(arms[1].pattern.kind, PatternKind::Wildcard);
+    assert_eq!(arms[1].body.stages.len(), 3);
+    assert_eq!(
+        arms[1].location,
+        Span::new(Position::new(67, 3, 13), Position::new(132, 5, 27))
+    );
+}
+
+#[test]
+fn test_parse_operators() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "func main () () {
+            a || b && c == d + 1;
+            !even n && x >= 2;
+            |? <= 1 -> 1 |? != n -> 2 \\?
+        }",
+    )
+    .unwrap();
+    let Item::Func(func) = &module.items[0] else {
+        panic!("Expected a func, found {:?}", module.items[0]);
+    };
+    let expr = |pipeline: &Pipeline| match &pipeline.stages[0].kind {
+        StageKind::Value(expr) => expr.kind.clone(),
+        kind => panic!("Expected a value, found {kind:?}"),
+    };
+    let op = |expr: &Expr| match &expr.kind {
+        ExprKind::Binary(op, ..) => Some(*op),
+        _ => None,
+    };
+
+    let ExprKind::Binary(BinaryOp::Or, _, rhs) = expr(&func.body.statements[0]) else {
+        panic!("Expected || to bind loosest");
+    };
+    let ExprKind::Binary(BinaryOp::And, _, rhs) = &rhs.kind else {
+        panic!("Expected && inside ||, found {rhs:?}");
+    };
+    let ExprKind::Binary(BinaryOp::Equal, _, rhs) = &rhs.kind else {
+        panic!("Expected == inside &&, found {rhs:?}");
+    };
+    assert_eq!(op(rhs), Some(BinaryOp::Plus));
+
+    let ExprKind::Binary(BinaryOp::And, lhs, rhs) = expr(&func.body.statements[1]) else {
+        panic!("Expected &&");
+    };
+    assert!(matches!(&lhs.kind, ExprKind::Not(call) if matches!(call.kind, ExprKind::Call(..))));
+    assert_eq!(op(&rhs), Some(BinaryOp::GreaterEqual));
+
+    let tail = func.body.tail.as_ref().unwrap();
+    let StageKind::Match(arms) = &tail.stages[0].kind else {
+        panic!("Expected a match, found {:?}", tail.stages[0]);
+    };
+    assert!(matches!(
+        arms[0].pattern.kind,
+        PatternKind::Compare(BinaryOp::LessEqual, _)
+    ));
+    assert!(matches!(
+        arms[1].pattern.kind,
+        PatternKind::Compare(BinaryOp::NotEqual, _)
+    ));
+}
+
+#[test]
+fn test_parse_sections() {
+    use crate::ast::*;
+
+    let module = parse_source("func main () () { n |> + n * 2 |> ^ 2 |> || false }").unwrap();
+    let Item::Func(func) = &module.items[0] else {
+        panic!("Expected a func, found {:?}", module.items[0]);
+    };
+    let stages = &func.body.tail.as_ref().unwrap().stages;
+    let sections = stages[1..]
+        .iter()
+        .map(|stage| match &stage.kind {
+            StageKind::Next(Expr {
+                kind: ExprKind::Section(op, operand),
+                ..
+            }) => (*op, operand.kind.clone()),
+            kind => panic!("Expected a section, found {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(sections.len(), 3);
+    assert!(matches!(
+        sections[0],
+        (BinaryOp::Plus, ExprKind::Binary(BinaryOp::Times, ..))
+    ));
+    assert!(matches!(
+        sections[1],
+        (
+            BinaryOp::Power,
+            ExprKind::Literal(Literal::Integer(2, None))
+        )
+    ));
+    assert!(matches!(
+        sections[2],
+        (BinaryOp::Or, ExprKind::Literal(Literal::Bool(false)))
+    ));
+}
+
+#[test]
+fn test_parse_updates() {
+    use crate::ast::*;
+
+    let module =
+        parse_source("func main () () { counter |> .{count} .count + 1 |> .{total} 2 * .count }")
+            .unwrap();
+    let Item::Func(func) = &module.items[0] else {
+        panic!("Expected a func, found {:?}", module.items[0]);
+    };
+    let stages = &func.body.tail.as_ref().unwrap().stages;
+    let updates = stages[1..]
+        .iter()
+        .map(|stage| match &stage.kind {
+            StageKind::Next(Expr {
+                kind: ExprKind::Update(field, value),
+                ..
+            }) => (field.name.as_str(), value.kind.clone()),
+            kind => panic!("Expected an update, found {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    // The value of an update is the whole expression after it, not just the next operand
+    assert_eq!(updates.len(), 2);
+    let ("count", ExprKind::Binary(BinaryOp::Plus, lhs, _)) = &updates[0] else {
+        panic!("Expected count to be set to a sum, found {:?}", updates[0]);
+    };
+    assert!(matches!(&lhs.kind, ExprKind::Field(current, field)
+        if matches!(current.kind, ExprKind::Current) && field.name == "count"));
+    assert!(matches!(
+        updates[1],
+        ("total", ExprKind::Binary(BinaryOp::Times, ..))
+    ));
+}
+
+#[test]
+fn test_parse_declarations() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "using Std::CLI;
+        data Args { all: Bool; }
+        object Counter { count: Int32 }
+        enum Colour { Red, Green, Blue }",
+    )
+    .unwrap();
+
+    assert_eq!(module.items.len(), 4);
+    let Item::Using(using) = &module.items[0] else {
+        panic!("Expected a using, found {:?}", module.items[0]);
+    };
+    assert_eq!(using.path.segments.len(), 2);
+    let Item::Data(data) = &module.items[1] else {
+        panic!("Expected data, found {:?}", module.items[1]);
+    };
+    assert_eq!(data.fields[0].name.name, "all");
+    assert_eq!(
+        data.location,
+        Span::new(Position::new(24, 2, 9), Position::new(48, 2, 33))
+    );
+    assert!(matches!(&module.items[2], Item::Object(object) if object.fields.len() == 1));
+    assert!(matches!(&module.items[3], Item::Enum(r#enum) if r#enum.variants.len() == 3));
+}
+
+#[test]
+fn test_parse_enums() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "enum Shape {
+            Empty,
+            Circle(Float64),
+            Rect { width: Float64; height: Float64; },
+        }
+
+        func main () () {
+            Shape::Empty
+            |? Shape::Circle r -> r
+            |? Shape::Rect {width} -> width
+            |? (Shape::Empty) -> 0.0
+            \\?;
+        }",
+    )
+    .unwrap();
+
+    let Item::Enum(r#enum) = &module.items[0] else {
+        panic!("Expected an enum, found {:?}", module.items[0]);
+    };
+    let arities = r#enum
+        .variants
+        .iter()
+        .map(|variant| (variant.name.name.as_str(), variant.kind.arity()))
+        .collect::<Vec<_>>();
+    assert_eq!(arities, vec![("Empty", 0), ("Circle", 1), ("Rect", 1)]);
+    assert!(matches!(&r#enum.variants[2].kind, VariantKind::Record(fields) if fields.len() == 2));
+
+    let Item::Func(func) = &module.items[1] else {
+        panic!("Expected a function, found {:?}", module.items[1]);
+    };
+    let StageKind::Match(arms) = &func.body.statements[0].stages[1].kind else {
+        panic!(
+            "Expected a match, found {:?}",
+            func.body.statements[0].stages[1]
+        );
+    };
+    let payloads = arms
+        .iter()
+        .map(|arm| match &arm.pattern.kind {
+            PatternKind::Variant(path, patterns) => (path.segments.len(), patterns.len()),
+            other => panic!("Expected a variant pattern, found {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(payloads, vec![(2, 1), (2, 1), (2, 0)]);
+}
+
+#[test]
+fn test_parse_tuple_patterns() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "func sum (Int32, Int32) (Int32) {
+            |? (total, (n)) -> total + n
+            |? (_, 0, _) -> 0
+        \\?
+        }",
+    )
+    .unwrap();
+
+    let Item::Func(func) = &module.items[0] else {
+        panic!("Expected a func, found {:?}", module.items[0]);
+    };
+    let tail = func.body.tail.as_ref().unwrap();
+    let StageKind::Match(arms) = &tail.stages[0].kind else {
+        panic!("Expected a match, found {:?}", tail.stages[0]);
+    };
+    let lengths = arms
+        .iter()
+        .map(|arm| match &arm.pattern.kind {
+            PatternKind::Tuple(patterns) => patterns.len(),
+            other => panic!("Expected a tuple pattern, found {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lengths, vec![2, 3]);
+    let PatternKind::Tuple(patterns) = &arms[0].pattern.kind else {
+        unreachable!();
+    };
+    assert!(matches!(&patterns[1].kind, PatternKind::Binding(name) if name.name == "n"));
+}
+
+#[test]
+fn test_parse_objects() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "object Counter {
+            count: Int32;
+            step: Int32;
+
+            /// Adds the step to the count
+            pub func increment (mut Counter) () { () }
+            func add (ref Counter, Counter) (Int32) { 0 }
+        }",
+    )
+    .unwrap();
+
+    let Item::Object(object) = &module.items[0] else {
+        panic!("Expected an object, found {:?}", module.items[0]);
+    };
+    assert_eq!(object.fields.len(), 2);
+    let methods = object
+        .methods
+        .iter()
+        .map(|method| {
+            let modes = method.params.iter().map(|param| param.mode).collect();
+            (method.name.name.as_str(), method.public, modes)
+        })
+        .collect::<Vec<(_, _, Vec<_>)>>();
+    assert_eq!(
+        methods,
+        vec![
+            ("increment", true, vec![ParamMode::Mut]),
+            ("add", false, vec![ParamMode::Ref, ParamMode::Value]),
+        ]
+    );
+    assert_eq!(
+        object.methods[0].doc.as_deref(),
+        Some("Adds the step to the count")
+    );
+    assert_eq!(
+        object.methods[1].params[0].location,
+        Span::new(Position::new(189, 7, 23), Position::new(200, 7, 34))
+    );
+}
+
+#[test]
+fn test_parse_generics() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "data Pair<A, B> { first: A; second: B; }
+        enum Option<T> { None, Some(T) }
+        func first<A, B> (Pair<A, B>) (A) { .first }
+        func main () () { () }",
+    )
+    .unwrap();
+
+    let names = |params: &[Ident]| {
+        params
+            .iter()
+            .map(|param| param.name.clone())
+            .collect::<Vec<_>>()
+    };
+    let Item::Data(data) = &module.items[0] else {
+        panic!("Expected data, found {:?}", module.items[0]);
+    };
+    assert_eq!(names(&data.type_params), vec!["A", "B"]);
+    let Item::Enum(r#enum) = &module.items[1] else {
+        panic!("Expected an enum, found {:?}", module.items[1]);
+    };
+    assert_eq!(names(&r#enum.type_params), vec!["T"]);
+    let Item::Func(func) = &module.items[2] else {
+        panic!("Expected a function, found {:?}", module.items[2]);
+    };
+    let params = func
+        .type_params
+        .iter()
+        .map(|param| param.name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names(&params), vec!["A", "B"]);
+    assert_eq!(func.params[0].ty.args.len(), 2);
+    let Item::Func(main) = &module.items[3] else {
+        panic!("Expected a function, found {:?}", module.items[3]);
+    };
+    assert!(main.type_params.is_empty());
+}
+
+#[test]
+fn test_parse_traits() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "/// Anything with an area
+        pub trait Shape {
+            /// The area in square units
+            func area (Self) (Float64);
+            func scale (mut Self, Float64) ();
+        }
+        impl Shape for Square {
+            func area (Square) (Float64) { .side * .side }
+            func scale (mut Square, Float64) () { () }
+        }
+        func describe<T: Shape + Display, U> (T) (String) { \"shape\" }",
+    )
+    .unwrap();
+
+    let names = |idents: &[Ident]| {
+        idents
+            .iter()
+            .map(|ident| ident.name.clone())
+            .collect::<Vec<_>>()
+    };
+    let Item::Trait(r#trait) = &module.items[0] else {
+        panic!("Expected a trait, found {:?}", module.items[0]);
+    };
+    assert!(r#trait.public);
+    assert_eq!(r#trait.doc.as_deref(), Some("Anything with an area"));
+    assert_eq!(r#trait.name.name, "Shape");
+    assert_eq!(r#trait.methods.len(), 2);
+    let area = r#trait.method("area").unwrap();
+    assert_eq!(area.doc.as_deref(), Some("The area in square units"));
+    assert_eq!(area.params.len(), 1);
+    assert_eq!(area.returns.len(), 1);
+    let scale = r#trait.method("scale").unwrap();
+    assert_eq!(scale.params[0].mode, ParamMode::Mut);
+    assert!(scale.returns.is_empty());
+
+    let Item::Impl(implementation) = &module.items[1] else {
+        panic!("Expected an impl, found {:?}", module.items[1]);
+    };
+    assert_eq!(names(&implementation.r#trait.segments), vec!["Shape"]);
+    assert_eq!(names(&implementation.ty.path.segments), vec!["Square"]);
+    assert_eq!(implementation.methods.len(), 2);
+    assert!(implementation.method("scale").is_some());
+
+    let Item::Func(func) = &module.items[2] else {
+        panic!("Expected a function, found {:?}", module.items[2]);
+    };
+    let bounds = func
+        .type_params
+        .iter()
+        .map(|param| {
+            let bounds = param
+                .bounds
+                .iter()
+                .map(|bound| names(&bound.segments).join("::"))
+                .collect::<Vec<_>>();
+            (param.name.name.as_str(), bounds)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bounds,
+        vec![
+            ("T", vec!["Shape".to_string(), "Display".to_string()]),
+            ("U", vec![])
+        ]
+    );
+}
+
+#[test]
+fn test_parse_doc_comments() {
+    use crate::ast::*;
+
+    let module = parse_source(
+        "/// Arguments from the command line
+        ///
+        /// Parsed by `Std::CLI`
+        pub data Args { all: Bool; }
+
+        /// Not attached, a `using` has no docs
+        using Std::CLI;
+        /* A block comment is not a doc comment */
+        enum Colour { Red }
+
+        /// Runs the program
+        func main () () {
+            /// Ignored inside a body
+            1
+        }",
+    )
+    .unwrap();
+
+    let docs = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Func(func) => Some(func.doc.as_deref()),
+            Item::Data(data) => Some(data.doc.as_deref()),
+            Item::Object(object) => Some(object.doc.as_deref()),
+            Item::Enum(r#enum) => Some(r#enum.doc.as_deref()),
+            Item::Trait(r#trait) => Some(r#trait.doc.as_deref()),
+            Item::Using(_) | Item::Impl(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        docs,
+        vec![
+            Some("Arguments from the command line\n\nParsed by `Std::CLI`"),
+            None,
+            Some("Runs the program"),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    use crate::{error::CompileErrorEnum, tokenizer::TokenEnum};
+
+    assert_eq!(
+        parse_source("func main () () { 1 |> }"),
+        Err(vec![CompileError::new(
+            Span::new(Position::new(23, 1, 24), Position::new(24, 1, 25)),
+            CompileErrorEnum::ExpectedOneOf {
+                expected: super::grammar::EXPRESSION_START.into(),
+                found: TokenEnum::CloseCurlyBrace,
+            }
+        )])
+    );
+    assert_eq!(
+        parse_source("func main ()"),
+        Err(vec![CompileError::new(
+            Span::new(Position::new(11, 1, 12), Position::new(12, 1, 13)),
+            CompileErrorEnum::UnexpectedEndOfInput {
+                expected: TokenEnum::OpenBrace.into(),
+            }
+        )])
+    );
+    assert_eq!(
+        parse_source(r#"func main () () { "a #{} b #{c d)}" }"#),
+        Err(vec![
+            CompileError::new(
+                Span::new(Position::new(21, 1, 22), Position::new(24, 1, 25)),
+                CompileErrorEnum::UnexpectedEndOfInput {
+                    expected: TokenEnum::PipeNext.into(),
+                }
+            ),
+            CompileError::new(
+                Span::new(Position::new(32, 1, 33), Position::new(33, 1, 34)),
+                CompileErrorEnum::ExpectedOneOf {
+                    expected: (&[TokenEnum::CloseCurlyBrace][..]).into(),
+                    found: TokenEnum::CloseBrace,
+                }
+            ),
+        ])
+    );
+}
+
+#[test]
+fn test_error_recovery() {
+    use crate::{error::CompileErrorEnum, tokenizer::TokenEnum};
+
+    let errors = parse_source(
+        r#"func main () () {
+            1 |> ;
+            read_int |? 1 -> |> |? _ -> 2 \?;
+            "ok" |> println
+        }
+
+        data Broken { x Int32 }
+
+        func other () () { |= }"#,
+    )
+    .unwrap_err()
+    .into_iter()
+    .map(|error| {
+        (
+            error.span().start.row,
+            error.span().start.column,
+            error.error().clone(),
+        )
+    })
+    .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                2,
+                18,
+                CompileErrorEnum::ExpectedOneOf {
+                    expected: super::grammar::EXPRESSION_START.into(),
+                    found: TokenEnum::SemiColon,
+                }
+            ),
+            (
+                3,
+                33,
+                CompileErrorEnum::ExpectedOneOf {
+                    expected: super::grammar::EXPRESSION_START.into(),
+                    found: TokenEnum::PipeMatch,
+                }
+            ),
+            (
+                7,
+                25,
+                CompileErrorEnum::ExpectedToken {
+                    expected: TokenEnum::Colon.into(),
+                    found: TokenEnum::Identifier("Int32".into()),
+                }
+            ),
+            (
+                9,
+                31,
+                CompileErrorEnum::ExpectedToken {
+                    expected: TokenEnum::Identifier(String::new()).into(),
+                    found: TokenEnum::CloseCurlyBrace,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_brace_error_recovery() {
+    // Recovery skips to the `}` closing the record, not the one of `main`, and goes on with the
+    // statements after it
+    let errors = parse_source(
+        "func main () () {
+            {x: 1,, y: 2} |> println;
+            read_int |? 1 -> {a: ,} |? _ -> 2 \\?;
+            \"ok\" |> println;
+        }",
+    )
+    .unwrap_err()
+    .into_iter()
+    .map(|error| (error.span().start.row, error.span().start.column))
+    .collect::<Vec<_>>();
+
+    assert_eq!(errors, vec![(2, 19), (3, 34)]);
+}
+
+#[test]
+fn test_parse_examples() {
+    for path in [
+        "../../examples/hello_world.st",
+        "../../examples/fib.st",
+        "../../examples/example_project/src/main.st",
+        "../../examples/example_project/src/hello_world.st",
+    ] {
+        let source = std::fs::read_to_string(path).unwrap();
+        if let Err(e) = parse_source(&source) {
+            panic!("{path}:{e:?}");
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Parser, ParserBase, ParserStep};
+use super::{Parser, ParserBase, ParserStep, Stream};
 
 pub struct Then<T, P>
 where
@@ -24,8 +24,11 @@ where
     T: ParserStep<Item = I, Error = E>,
     E: Clone,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        self.parser.execute(iter)
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parser.parse(stream)
     }
 }
 
@@ -44,12 +47,12 @@ where
     T: ParserStep<Item = P::Item, Error = P::Error>,
     P: ParserStep,
 {
-    fn execute(
+    fn parse(
         &self,
-        mut iter: impl Iterator<Item = Self::Item>,
+        stream: &mut dyn Stream<Item = Self::Item>,
     ) -> Result<Self::Output, Self::Error> {
         self.parent
-            .execute(iter.by_ref())
-            .and_then(|first| self.parser.execute(iter).map(|second| (first, second)))
+            .parse(stream)
+            .and_then(|first| self.parser.parse(stream).map(|second| (first, second)))
     }
 }
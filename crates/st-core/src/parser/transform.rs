@@ -1,4 +1,4 @@
-use super::{ParserBase, ParserStep};
+use super::{ParserBase, ParserStep, Stream};
 
 pub struct Transform<I, F, P>
 where
@@ -27,7 +27,10 @@ where
     P::Error: Clone,
     P: ParserStep,
 {
-    fn execute(&self, iter: impl Iterator<Item = Self::Item>) -> Result<Self::Output, Self::Error> {
-        self.parent.execute(iter).map(&self.transformer)
+    fn parse(
+        &self,
+        stream: &mut dyn Stream<Item = Self::Item>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.parent.parse(stream).map(&self.transformer)
     }
 }
@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::Module,
+    checker::check,
+    error::{ProjectError, ProjectWarning},
+    interpreter::{Interpreter, Value},
+    parser::parse,
+    resolver::{module_name, resolve, Program, SourceModule},
+    tokenizer::tokenize,
+};
+use std::path::{Component, Path, PathBuf};
+
+/// A binary described by the `bin` entry of a project manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binary {
+    pub name: String,
+    /// The directory containing the binary's source, relative paths are resolved against the
+    /// directory containing the manifest
+    pub src: PathBuf,
+}
+
+/// A project loaded from a `project.st` manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub name: String,
+    pub bin: Binary,
+    /// Every `.st` file under [Binary::src]
+    pub sources: Vec<PathBuf>,
+    /// Warnings found while checking the manifest
+    pub warnings: Vec<ProjectWarning>,
+}
+
+/// Reads, tokenizes and parses a single source file
+pub fn parse_file(path: &Path) -> Result<Module, ProjectError> {
+    let source = std::fs::read_to_string(path).map_err(|error| ProjectError::Io {
+        path: path.into(),
+        error,
+    })?;
+
+    // Syntax errors are still looked for after a lexical error, the bad token is just left out
+    let mut errors = Vec::new();
+    let tokens = tokenize(&source)
+        .filter_map(|token| token.map_err(|error| errors.push(error)).ok())
+        .collect::<Vec<_>>();
+
+    match parse(tokens.into_iter()) {
+        Ok(module) if errors.is_empty() => return Ok(module),
+        Ok(_) => {}
+        Err(syntax_errors) => errors.extend(syntax_errors),
+    }
+    errors.sort_by_key(|error| error.span().start.offset);
+
+    let errors = errors
+        .into_iter()
+        .map(|error| ProjectError::Compile {
+            path: path.into(),
+            error,
+        })
+        .collect();
+    Err(ProjectError::combine(errors).expect("at least one error was found"))
+}
+
+/// `path` without its `.` components, `glob` leaves them out of the paths it finds so the
+/// source directory has to be written the same way for the sources to be found under it
+fn normalise(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+impl Project {
+    /// Evaluates the `project` function of the manifest at `path` and finds the project's sources
+    pub fn load(path: &Path) -> Result<Self, ProjectError> {
+        let manifest = SourceModule::from_file(path.into(), parse_file(path)?);
+        let mut program = resolve(vec![manifest])?;
+        let warnings = check(&mut program)?;
+        if program.find_function("project").is_none() {
+            return Err(ProjectError::MissingProjectFunc { path: path.into() });
+        }
+
+        let descriptor = Interpreter::new(&program)
+            .call_function("project", Vec::new())
+            .map_err(|error| ProjectError::Runtime {
+                path: path.into(),
+                error,
+            })?;
+
+        let invalid = |reason: &str| ProjectError::InvalidDescriptor {
+            path: path.into(),
+            reason: reason.into(),
+        };
+
+        let Value::Record(fields) = descriptor else {
+            return Err(invalid("project must return a ProjectDescriptor"));
+        };
+        let Some(Value::String(name)) = fields.get("name") else {
+            return Err(invalid("name must be a String"));
+        };
+        let Some(Value::Record(bin)) = fields.get("bin") else {
+            return Err(invalid("bin must be set to {name: String, src: String}"));
+        };
+        let (Some(Value::String(bin_name)), Some(Value::String(src))) =
+            (bin.get("name"), bin.get("src"))
+        else {
+            return Err(invalid("bin must be set to {name: String, src: String}"));
+        };
+
+        let root = path.parent().unwrap_or(Path::new("."));
+        let src = normalise(&root.join(src));
+        let pattern = src.join("**").join("*.st").to_string_lossy().into_owned();
+        let mut sources = glob::glob(&pattern)
+            .map_err(|error| ProjectError::Glob {
+                pattern: pattern.clone(),
+                error,
+            })?
+            .map(|entry| {
+                entry
+                    .map(|path| normalise(&path))
+                    .map_err(|error| ProjectError::Io {
+                        path: error.path().into(),
+                        error: error.into(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        sources.sort();
+
+        Ok(Self {
+            name: name.clone(),
+            bin: Binary {
+                name: bin_name.clone(),
+                src,
+            },
+            sources,
+            warnings,
+        })
+    }
+
+    /// Parses, resolves and type checks every source file of the project, each file is a module
+    /// named after its path relative to the binary's source directory
+    pub fn program(&self) -> Result<(Program, Vec<ProjectWarning>), ProjectError> {
+        let modules = self
+            .sources
+            .iter()
+            .map(|path| {
+                let name = module_name(&self.name, &self.bin.src, path)?;
+                parse_file(path).map(|ast| SourceModule::new(name, path.clone(), ast))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut program = resolve(modules)?;
+        let warnings = check(&mut program)?;
+        Ok((program, warnings))
+    }
+
+    /// Runs the `main` function of a program built by [Project::program] with the given command
+    /// line arguments, errors are reported in the source file they happened in
+    pub fn run(&self, program: &Program, args: Vec<String>) -> Result<Value, ProjectError> {
+        let result = Interpreter::new(program).with_args(args).run();
+        result.map_err(|error| ProjectError::Runtime {
+            path: error.file().unwrap_or(&self.bin.src).into(),
+            error,
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+#[test]
+fn test_load_example_project() {
+    let project = Project::load(Path::new("../../examples/example_project/project.st")).unwrap();
+
+    assert_eq!(project.name, "ExampleProject");
+    assert_eq!(project.bin.name, "example");
+    assert_eq!(
+        project.bin.src,
+        Path::new("../../examples/example_project/src")
+    );
+    assert_eq!(
+        project.sources,
+        vec![
+            PathBuf::from("../../examples/example_project/src/hello_world.st"),
+            PathBuf::from("../../examples/example_project/src/main.st"),
+        ]
+    );
+}
+
+#[test]
+fn test_load_relative_project() {
+    // `./` is kept at the start of the source directory but left out of the paths glob finds
+    let project = Project::load(Path::new("./../../examples/example_project/project.st")).unwrap();
+    let (program, _) = project.program().unwrap();
+
+    let names = program
+        .modules()
+        .iter()
+        .map(|module| module.name.join("::"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["ExampleProject::HelloWorld", "ExampleProject::Main"]
+    );
+}
+
+#[test]
+fn test_missing_project_func() {
+    assert!(matches!(
+        Project::load(Path::new("../../examples/hello_world.st")),
+        Err(ProjectError::MissingProjectFunc { .. })
+    ));
+}
+
+#[test]
+fn test_parse_file_reports_every_error() {
+    let path = std::env::temp_dir().join("st_parse_file_errors.st");
+    std::fs::write(
+        &path,
+        "func main () () {\n    println \"a \\q\";\n    |> ;\n}\n",
+    )
+    .unwrap();
+    let result = parse_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let Err(ProjectError::Errors(errors)) = result else {
+        panic!("Expected several errors, found {result:?}");
+    };
+    let positions = errors
+        .iter()
+        .map(|error| match error {
+            ProjectError::Compile { error, .. } => {
+                (error.span().start.row, error.span().start.column)
+            }
+            error => panic!("Expected a compile error, found {error}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![(2, 16), (3, 8)]);
+}
+
+#[test]
+fn test_runtime_error_file() {
+    let root = std::env::temp_dir().join("st_runtime_error_project");
+    let src = root.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(
+        root.join("project.st"),
+        "using Std::Build::ProjectDescriptor;
+
+        func project () (ProjectDescriptor) {
+            ProjectDescriptor::init
+            |> .{name} \"Broken\"
+            |> .{bin} {name: \"broken\", src: \"src\"}
+        }",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("main.st"),
+        "using Broken::Check::check;\n\nfunc main () () {\n    check;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("check.st"),
+        "pub func check () () {\n    panic \"oh no\";\n}\n",
+    )
+    .unwrap();
+
+    let project = Project::load(&root.join("project.st")).unwrap();
+    let (program, _) = project.program().unwrap();
+    let result = project.run(&program, Vec::new());
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let Err(ProjectError::Runtime { path, error }) = result else {
+        panic!("Expected a runtime error, found {result:?}");
+    };
+    assert_eq!(path, src.join("check.st"));
+    assert_eq!(error.position(), (2, 5));
+    assert_eq!(error.trace()[0].file, src.join("main.st"));
+    assert_eq!(error.trace()[0].row, 4);
+}
@@ -0,0 +1,723 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::*,
+    error::{CompileError, CompileErrorEnum, ProjectError},
+};
+use std::{
+    collections::HashMap,
+    path::{Component, Path as FilePath, PathBuf},
+};
+
+/// Paths provided by the standard library that can be named in `using` declarations
+const STD_ITEMS: &[&str] = &[
+    "Std",
+    "Std::CLI",
+    "Std::CLI::parse_args",
+    "Std::Build",
+    "Std::Build::ProjectDescriptor",
+];
+
+/// Traits provided by the language, the operators and string interpolation use them for types
+/// declared in the program
+pub const BUILTIN_TRAITS: &[&str] = &["Add", "Compare", "Display"];
+
+/// A parsed source file and the module path it is known by
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceModule {
+    pub name: Vec<String>,
+    pub file: PathBuf,
+    pub ast: Module,
+}
+
+impl SourceModule {
+    pub fn new(name: Vec<String>, file: PathBuf, ast: Module) -> Self {
+        Self { name, file, ast }
+    }
+
+    /// A module outside of any project, named after its file
+    pub fn from_file(file: PathBuf, ast: Module) -> Self {
+        let name = file
+            .file_stem()
+            .map(|stem| pascal_case(&stem.to_string_lossy()))
+            .unwrap_or_default();
+        Self::new(vec![name], file, ast)
+    }
+
+    fn item(&self, name: &str) -> Option<(bool, &Item)> {
+        self.ast.items.iter().find_map(|item| {
+            let (public, ident) = match item {
+                Item::Using(_) | Item::Impl(_) => return None,
+                Item::Data(data) => (data.public, &data.name),
+                Item::Object(object) => (object.public, &object.name),
+                Item::Enum(r#enum) => (r#enum.public, &r#enum.name),
+                Item::Func(func) => (func.public, &func.name),
+                Item::Trait(r#trait) => (r#trait.public, &r#trait.name),
+            };
+            (ident.name == name).then_some((public, item))
+        })
+    }
+
+    /// The function called `name`, or the method of an object for names like `Counter::get`
+    pub(crate) fn function(&self, name: &str) -> Option<&Func> {
+        if let Some((object, method)) = name.split_once("::") {
+            return match self.item(object) {
+                Some((_, Item::Object(object))) => object.method(method),
+                _ => None,
+            };
+        }
+        match self.item(name) {
+            Some((_, Item::Func(func))) => Some(func),
+            _ => None,
+        }
+    }
+}
+
+/// The name a method is known by inside its module, `Counter::get`
+pub(crate) fn method_name(object: &Object, method: &Func) -> String {
+    format!("{}::{}", object.name.name, method.name.name)
+}
+
+/// The name a method of an `impl` is known by in stack traces, `Add::add for Point`
+pub(crate) fn impl_method_name(implementation: &Impl, method: &Func) -> String {
+    format!(
+        "{}::{} for {}",
+        implementation.r#trait.name().name,
+        method.name.name,
+        implementation.ty.path.name().name
+    )
+}
+
+/// Converts a snake_case file name into a PascalCase module name
+pub fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// The module path of `file`, a source file under `src` in the project `root`.
+///
+/// `src/hello_world.st` in the project `ExampleProject` is `ExampleProject::HelloWorld`, both
+/// paths must be written the same way, `./src` is not a prefix of `src/hello_world.st`
+pub fn module_name(
+    root: &str,
+    src: &FilePath,
+    file: &FilePath,
+) -> Result<Vec<String>, ProjectError> {
+    let relative = file
+        .strip_prefix(src)
+        .map_err(|_| ProjectError::OutsideSource {
+            path: file.into(),
+            src: src.into(),
+        })?
+        .with_extension("");
+    Ok(std::iter::once(root.to_string())
+        .chain(
+            relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(name) => Some(pascal_case(&name.to_string_lossy())),
+                    _ => None,
+                }),
+        )
+        .collect())
+}
+
+/// What a path used in an expression refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Callee<'a> {
+    /// A function declared in the module at the given index
+    Func { module: usize, func: &'a Func },
+    /// A function that is not declared in the program, such as `println` or
+    /// `Std::CLI::parse_args`, identified by its full path
+    Builtin(Vec<String>),
+    /// A variant of an `enum` declared in the module at the given index, which constructs a
+    /// value of the enum when called, `Shape::Circle 1.0`
+    Variant {
+        module: usize,
+        r#enum: &'a Enum,
+        variant: &'a Variant,
+    },
+    /// An `object` declared in the module at the given index, which creates a new object from
+    /// the record it is called with, `Counter {count: 0}`
+    Object { module: usize, object: &'a Object },
+    /// A method of an `object` declared in the module at the given index, `Counter::get`
+    Method {
+        module: usize,
+        object: &'a Object,
+        func: &'a Func,
+    },
+    /// A method of a `trait` declared in the module at the given index, `Shape::area`, which
+    /// calls the implementation for the type of its first argument
+    TraitMethod {
+        module: usize,
+        r#trait: &'a Trait,
+        method: &'a TraitMethod,
+    },
+}
+
+/// What a path used as a type refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef<'a> {
+    /// A `data`, `object` or `enum` declared in the module at the given index
+    Item { module: usize, item: &'a Item },
+    /// A type that is not declared in the program, such as `Int32` or
+    /// `Std::Build::ProjectDescriptor`, identified by its full path
+    Builtin(Vec<String>),
+}
+
+/// What a path used as a trait refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraitRef<'a> {
+    /// A `trait` declared in the module at the given index
+    Declared { module: usize, r#trait: &'a Trait },
+    /// One of [BUILTIN_TRAITS]
+    Builtin(String),
+}
+
+/// What a `using` declaration or qualified path refers to
+enum Target {
+    Std,
+    Module(usize),
+    Item(usize),
+}
+
+/// A set of modules whose `using` declarations have been resolved
+#[derive(Debug)]
+pub struct Program {
+    modules: Vec<SourceModule>,
+    index: HashMap<Vec<String>, usize>,
+    /// For each module the names brought into scope by `using`, mapped to their full path
+    imports: Vec<HashMap<String, Vec<String>>>,
+    /// The integer type the checker inferred for each integer literal without a suffix, by
+    /// module index and location
+    literal_types: HashMap<(usize, Span), &'static str>,
+}
+
+impl Program {
+    pub fn modules(&self) -> &[SourceModule] {
+        &self.modules
+    }
+
+    /// The integer type of the integer literal without a suffix at `location` in `module`,
+    /// [None] if the program was not checked or the literal is used as a float
+    pub fn literal_type(&self, module: usize, location: Span) -> Option<&'static str> {
+        self.literal_types.get(&(module, location)).copied()
+    }
+
+    pub(crate) fn set_literal_types(&mut self, types: HashMap<(usize, Span), &'static str>) {
+        self.literal_types = types;
+    }
+
+    /// Finds the first module that declares a function called `name`
+    pub fn find_function(&self, name: &str) -> Option<(usize, &Func)> {
+        self.modules
+            .iter()
+            .enumerate()
+            .find_map(|(index, module)| module.function(name).map(|func| (index, func)))
+    }
+
+    /// Resolves a path used inside `module` to the function it calls
+    pub fn lookup(&self, module: usize, path: &[String]) -> Option<Callee<'_>> {
+        if let [name] = path {
+            if let Some(func) = self.modules[module].function(name) {
+                return Some(Callee::Func { module, func });
+            }
+        }
+
+        if let Some((name, type_path)) = path.split_last().filter(|(_, path)| !path.is_empty()) {
+            if let Some(TraitRef::Declared { module, r#trait }) =
+                self.lookup_trait(module, type_path)
+            {
+                let method = r#trait.method(name)?;
+                return Some(Callee::TraitMethod {
+                    module,
+                    r#trait,
+                    method,
+                });
+            }
+            match self.lookup_type(module, type_path) {
+                Some(TypeRef::Item {
+                    module,
+                    item: Item::Enum(r#enum),
+                }) => {
+                    let variant = r#enum
+                        .variants
+                        .iter()
+                        .find(|variant| variant.name.name == *name)?;
+                    return Some(Callee::Variant {
+                        module,
+                        r#enum,
+                        variant,
+                    });
+                }
+                Some(TypeRef::Item {
+                    module: index,
+                    item: Item::Object(object),
+                }) => {
+                    let func = object.method(name)?;
+                    return (func.public || index == module).then_some(Callee::Method {
+                        module: index,
+                        object,
+                        func,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(TypeRef::Item {
+            module,
+            item: Item::Object(object),
+        }) = self.lookup_type(module, path)
+        {
+            return Some(Callee::Object { module, object });
+        }
+
+        let absolute = self.absolute(module, path);
+        let (name, module_name) = absolute.split_last()?;
+        if module_name.is_empty() || absolute[0] == "Std" {
+            return Some(Callee::Builtin(absolute));
+        }
+
+        let index = *self.index.get(module_name)?;
+        let func = self.modules[index].function(name)?;
+        (func.public || index == module).then_some(Callee::Func {
+            module: index,
+            func,
+        })
+    }
+
+    /// Resolves a path used as a type inside `module`
+    pub fn lookup_type(&self, module: usize, path: &[String]) -> Option<TypeRef<'_>> {
+        let is_type = |item: &Item| matches!(item, Item::Data(_) | Item::Object(_) | Item::Enum(_));
+
+        if let [name] = path {
+            if let Some((_, item)) = self.modules[module].item(name) {
+                return is_type(item).then_some(TypeRef::Item { module, item });
+            }
+        }
+
+        let absolute = self.absolute(module, path);
+        let (name, module_name) = absolute.split_last()?;
+        if module_name.is_empty() || absolute[0] == "Std" {
+            return Some(TypeRef::Builtin(absolute));
+        }
+
+        let index = *self.index.get(module_name)?;
+        let (public, item) = self.modules[index].item(name)?;
+        (is_type(item) && (public || index == module)).then_some(TypeRef::Item {
+            module: index,
+            item,
+        })
+    }
+
+    /// Resolves a path used as a trait inside `module`, such as the `Add` of `T: Add`
+    pub fn lookup_trait(&self, module: usize, path: &[String]) -> Option<TraitRef<'_>> {
+        if let [name] = path {
+            match self.modules[module].item(name) {
+                Some((_, Item::Trait(r#trait))) => {
+                    return Some(TraitRef::Declared { module, r#trait })
+                }
+                Some(_) => return None,
+                None => {}
+            }
+        }
+
+        let absolute = self.absolute(module, path);
+        let (name, module_name) = absolute.split_last()?;
+        if module_name.is_empty() {
+            return BUILTIN_TRAITS
+                .contains(&name.as_str())
+                .then(|| TraitRef::Builtin(name.clone()));
+        }
+
+        let index = *self.index.get(module_name)?;
+        match self.modules[index].item(name)? {
+            (public, Item::Trait(r#trait)) if public || index == module => {
+                Some(TraitRef::Declared {
+                    module: index,
+                    r#trait,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The full path of a type, which identifies it in [Program::implementation]
+    pub fn type_path(&self, ty: &TypeRef) -> Vec<String> {
+        match ty {
+            TypeRef::Item { module, item } => {
+                let name = match item {
+                    Item::Data(data) => &data.name,
+                    Item::Object(object) => &object.name,
+                    Item::Enum(r#enum) => &r#enum.name,
+                    _ => unreachable!("Only data, objects and enums are types"),
+                };
+                self.item_path(*module, &name.name)
+            }
+            TypeRef::Builtin(path) => path.clone(),
+        }
+    }
+
+    /// The full path of a trait, which identifies it in [Program::implementation]
+    pub fn trait_path(&self, r#trait: &TraitRef) -> Vec<String> {
+        match r#trait {
+            TraitRef::Declared { module, r#trait } => self.item_path(*module, &r#trait.name.name),
+            TraitRef::Builtin(name) => vec![name.clone()],
+        }
+    }
+
+    /// The full path of the item called `name` in the module at the given index, which
+    /// identifies its type in [Program::implementation]
+    pub fn item_path(&self, module: usize, name: &str) -> Vec<String> {
+        let mut path = self.modules[module].name.clone();
+        path.push(name.to_string());
+        path
+    }
+
+    /// Every `impl` in the program, with the index of the module that declares it
+    pub fn implementations(&self) -> impl Iterator<Item = (usize, &Impl)> {
+        self.modules.iter().enumerate().flat_map(|(index, module)| {
+            module.ast.items.iter().filter_map(move |item| match item {
+                Item::Impl(implementation) => Some((index, implementation)),
+                _ => None,
+            })
+        })
+    }
+
+    /// The `impl` of the trait at `trait_path` for the type at the full path `ty`
+    pub fn implementation(&self, trait_path: &[String], ty: &[String]) -> Option<(usize, &Impl)> {
+        self.implementations().find(|(module, implementation)| {
+            self.lookup_type(*module, &segments(&implementation.ty.path))
+                .is_some_and(|declared| self.type_path(&declared) == ty)
+                && self
+                    .lookup_trait(*module, &segments(&implementation.r#trait))
+                    .is_some_and(|r#trait| self.trait_path(&r#trait) == trait_path)
+        })
+    }
+
+    /// Why `path` does not name a method, if its prefix is an object or trait `module` can see
+    fn method_error(&self, module: usize, path: &[String]) -> Option<CompileErrorEnum> {
+        let (name, object_path) = path.split_last()?;
+        if let Some(TraitRef::Declared { .. }) = self.lookup_trait(module, object_path) {
+            return Some(CompileErrorEnum::UnknownPath(
+                self.absolute(module, path).join("::"),
+            ));
+        }
+        let Some(TypeRef::Item {
+            item: Item::Object(object),
+            ..
+        }) = self.lookup_type(module, object_path)
+        else {
+            return None;
+        };
+        let joined = self.absolute(module, path).join("::");
+        Some(match object.method(name) {
+            Some(_) => CompileErrorEnum::PrivateItem(joined),
+            None => CompileErrorEnum::UnknownPath(joined),
+        })
+    }
+
+    /// Expands an imported first segment of `path` into the full path it was imported from
+    fn absolute(&self, module: usize, path: &[String]) -> Vec<String> {
+        match self.imports[module].get(&path[0]) {
+            Some(import) => import.iter().chain(&path[1..]).cloned().collect(),
+            None => path.to_vec(),
+        }
+    }
+
+    fn target(&self, module: usize, path: &[String]) -> Result<Target, CompileErrorEnum> {
+        let joined = path.join("::");
+        if path[0] == "Std" {
+            return if STD_ITEMS.contains(&joined.as_str()) {
+                Ok(Target::Std)
+            } else {
+                Err(CompileErrorEnum::UnknownPath(joined))
+            };
+        }
+
+        if let Some(index) = self.index.get(path) {
+            return Ok(Target::Module(*index));
+        }
+
+        let (name, module_name) = path.split_last().expect("Paths always have a segment");
+        let index = *self
+            .index
+            .get(module_name)
+            .ok_or_else(|| CompileErrorEnum::UnknownPath(joined.clone()))?;
+        match self.modules[index].item(name) {
+            Some((public, _)) if public || index == module => Ok(Target::Item(index)),
+            Some(_) => Err(CompileErrorEnum::PrivateItem(joined)),
+            None => Err(CompileErrorEnum::UnknownPath(joined)),
+        }
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.name.clone())
+        .collect()
+}
+
+fn compile_error(module: &SourceModule, location: Span, error: CompileErrorEnum) -> ProjectError {
+    ProjectError::Compile {
+        path: module.file.clone(),
+        error: CompileError::new(location, error),
+    }
+}
+
+/// Resolves the `using` declarations and qualified paths of every module, checking that they
+/// refer to public items and do not form import cycles
+pub fn resolve(modules: Vec<SourceModule>) -> Result<Program, ProjectError> {
+    let index = modules
+        .iter()
+        .enumerate()
+        .map(|(index, module)| (module.name.clone(), index))
+        .collect();
+    let mut program = Program {
+        imports: Vec::new(),
+        modules,
+        index,
+        literal_types: HashMap::new(),
+    };
+
+    let mut errors = Vec::new();
+    let mut imports = vec![HashMap::new(); program.modules.len()];
+    // The modules each module imports from, with the location of the `using`
+    let mut edges = vec![Vec::new(); program.modules.len()];
+
+    for (index, module) in program.modules.iter().enumerate() {
+        for item in &module.ast.items {
+            let Item::Using(using) = item else {
+                continue;
+            };
+
+            let path = segments(&using.path);
+            match program.target(index, &path) {
+                Ok(target) => {
+                    match target {
+                        Target::Module(target) | Target::Item(target) if target != index => {
+                            edges[index].push((target, using.location))
+                        }
+                        _ => {}
+                    }
+                    imports[index].insert(using.path.name().name.clone(), path);
+                }
+                Err(error) => errors.push(compile_error(module, using.location, error)),
+            }
+        }
+    }
+
+    program.imports = imports;
+
+    for (index, module) in program.modules.iter().enumerate() {
+        let bodies = module.ast.items.iter().flat_map(|item| match item {
+            Item::Func(func) => vec![&func.body],
+            Item::Object(object) => object.methods.iter().map(|method| &method.body).collect(),
+            Item::Impl(implementation) => implementation
+                .methods
+                .iter()
+                .map(|method| &method.body)
+                .collect(),
+            _ => Vec::new(),
+        });
+        for body in bodies {
+            visit_block(body, &mut |path| {
+                if path.segments.len() < 2 {
+                    return;
+                }
+                match program.lookup(index, &segments(path)) {
+                    Some(
+                        Callee::Variant { .. } | Callee::Method { .. } | Callee::TraitMethod { .. },
+                    ) => return,
+                    None => {
+                        if let Some(error) = program.method_error(index, &segments(path)) {
+                            errors.push(compile_error(module, path.location, error));
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+                let absolute = program.absolute(index, &segments(path));
+                if absolute[0] == "Std" {
+                    let known = (2..=absolute.len())
+                        .any(|len| STD_ITEMS.contains(&absolute[..len].join("::").as_str()));
+                    if !known {
+                        errors.push(compile_error(
+                            module,
+                            path.location,
+                            CompileErrorEnum::UnknownPath(absolute.join("::")),
+                        ));
+                    }
+                    return;
+                }
+
+                match program.target(index, &absolute) {
+                    Ok(Target::Item(_) | Target::Std) => {}
+                    Ok(Target::Module(_)) => errors.push(compile_error(
+                        module,
+                        path.location,
+                        CompileErrorEnum::UnknownPath(absolute.join("::")),
+                    )),
+                    Err(error) => errors.push(compile_error(module, path.location, error)),
+                }
+            });
+        }
+    }
+
+    errors.extend(find_cycles(&program, &edges));
+
+    match ProjectError::combine(errors) {
+        Some(error) => Err(error),
+        None => Ok(program),
+    }
+}
+
+/// Reports every import cycle once, at the `using` that closes it
+fn find_cycles(program: &Program, edges: &[Vec<(usize, Span)>]) -> Vec<ProjectError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        program: &Program,
+        edges: &[Vec<(usize, Span)>],
+        module: usize,
+        states: &mut [State],
+        stack: &mut Vec<usize>,
+        errors: &mut Vec<ProjectError>,
+    ) {
+        states[module] = State::Visiting;
+        stack.push(module);
+
+        for &(target, location) in &edges[module] {
+            match states[target] {
+                State::Unvisited => visit(program, edges, target, states, stack, errors),
+                State::Visiting => {
+                    let start = stack
+                        .iter()
+                        .position(|index| *index == target)
+                        .expect("Visiting modules are on the stack");
+                    let cycle = stack[start..]
+                        .iter()
+                        .chain([&target])
+                        .map(|index| program.modules[*index].name.join("::"))
+                        .collect();
+                    errors.push(compile_error(
+                        &program.modules[module],
+                        location,
+                        CompileErrorEnum::ImportCycle(cycle),
+                    ));
+                }
+                State::Done => {}
+            }
+        }
+
+        stack.pop();
+        states[module] = State::Done;
+    }
+
+    let mut states = vec![State::Unvisited; program.modules.len()];
+    let mut errors = Vec::new();
+    for module in 0..program.modules.len() {
+        if states[module] == State::Unvisited {
+            visit(
+                program,
+                edges,
+                module,
+                &mut states,
+                &mut Vec::new(),
+                &mut errors,
+            );
+        }
+    }
+    errors
+}
+
+fn visit_block(block: &Block, visitor: &mut impl FnMut(&Path)) {
+    for pipeline in block.statements.iter().chain(block.tail.as_deref()) {
+        visit_pipeline(pipeline, visitor);
+    }
+}
+
+fn visit_pipeline(pipeline: &Pipeline, visitor: &mut impl FnMut(&Path)) {
+    for stage in &pipeline.stages {
+        match &stage.kind {
+            StageKind::Value(expr) | StageKind::Next(expr) | StageKind::Error(expr) => {
+                visit_expr(expr, visitor)
+            }
+            StageKind::Match(arms) => {
+                for arm in arms {
+                    visit_pattern(&arm.pattern, visitor);
+                    visit_pipeline(&arm.body, visitor);
+                }
+            }
+            StageKind::Set(_) | StageKind::Return => {}
+        }
+    }
+}
+
+fn visit_pattern(pattern: &Pattern, visitor: &mut impl FnMut(&Path)) {
+    match &pattern.kind {
+        PatternKind::Compare(_, expr) => visit_expr(expr, visitor),
+        PatternKind::Record(fields) => fields
+            .iter()
+            .for_each(|(_, pattern)| visit_pattern(pattern, visitor)),
+        PatternKind::Variant(path, patterns) => {
+            visitor(path);
+            patterns
+                .iter()
+                .for_each(|pattern| visit_pattern(pattern, visitor));
+        }
+        PatternKind::Tuple(patterns) => patterns
+            .iter()
+            .for_each(|pattern| visit_pattern(pattern, visitor)),
+        PatternKind::Wildcard | PatternKind::Binding(_) | PatternKind::Literal(_) => {}
+    }
+}
+
+fn visit_expr(expr: &Expr, visitor: &mut impl FnMut(&Path)) {
+    match &expr.kind {
+        ExprKind::Path(path) => visitor(path),
+        ExprKind::Call(path, args) => {
+            visitor(path);
+            args.iter().for_each(|arg| visit_expr(arg, visitor));
+        }
+        ExprKind::Binary(_, lhs, rhs)
+        | ExprKind::Range {
+            start: lhs,
+            end: rhs,
+            ..
+        } => {
+            visit_expr(lhs, visitor);
+            visit_expr(rhs, visitor);
+        }
+        ExprKind::Record(fields) => fields
+            .iter()
+            .for_each(|(_, value)| visit_expr(value, visitor)),
+        ExprKind::Update(_, value)
+        | ExprKind::Not(value)
+        | ExprKind::Section(_, value)
+        | ExprKind::Field(value, _) => visit_expr(value, visitor),
+        ExprKind::Group(pipeline) => visit_pipeline(pipeline, visitor),
+        ExprKind::Closure(body) => visit_block(body, visitor),
+        ExprKind::Interpolation(parts) => parts.iter().for_each(|part| {
+            if let InterpolationPart::Expr(expr) = part {
+                visit_expr(expr, visitor);
+            }
+        }),
+        ExprKind::Literal(_) | ExprKind::Current => {}
+    }
+}
@@ -0,0 +1,194 @@
+use super::*;
+use crate::{parser::parse, tokenizer::tokenize};
+
+fn module(name: &str, source: &str) -> SourceModule {
+    let ast = parse(tokenize(source).map(|token| token.unwrap())).unwrap();
+    SourceModule::new(
+        name.split("::").map(String::from).collect(),
+        format!("{name}.st").into(),
+        ast,
+    )
+}
+
+/// The file and start position of each error's span with the error
+fn errors(result: Result<Program, ProjectError>) -> Vec<(PathBuf, usize, usize, CompileErrorEnum)> {
+    let error = result.expect_err("Expected resolving to fail");
+    let errors = match error {
+        ProjectError::Errors(errors) => errors,
+        error => vec![error],
+    };
+    errors
+        .into_iter()
+        .map(|error| match error {
+            ProjectError::Compile { path, error } => (
+                path,
+                error.span().start.row,
+                error.span().start.column,
+                error.error().clone(),
+            ),
+            error => panic!("Expected a compile error, found {error}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_module_name() {
+    assert_eq!(
+        module_name(
+            "ExampleProject",
+            FilePath::new("./src"),
+            FilePath::new("./src/hello_world.st")
+        )
+        .unwrap(),
+        vec!["ExampleProject", "HelloWorld"]
+    );
+    assert_eq!(
+        module_name(
+            "Project",
+            FilePath::new("src"),
+            FilePath::new("src/net/http_client.st")
+        )
+        .unwrap(),
+        vec!["Project", "Net", "HttpClient"]
+    );
+    assert!(matches!(
+        module_name(
+            "Project",
+            FilePath::new("./src"),
+            FilePath::new("src/main.st")
+        ),
+        Err(ProjectError::OutsideSource { .. })
+    ));
+}
+
+#[test]
+fn test_resolve_using() {
+    let program = resolve(vec![
+        module(
+            "Example::Main",
+            "using Example::HelloWorld::hello_world;
+            using Std::CLI;
+            func main () () { hello_world; Example::HelloWorld::hello_world }",
+        ),
+        module("Example::HelloWorld", "pub func hello_world () () { () }"),
+    ])
+    .unwrap();
+
+    let path = |path: &str| path.split("::").map(String::from).collect::<Vec<_>>();
+    assert!(matches!(
+        program.lookup(0, &path("hello_world")),
+        Some(Callee::Func { module: 1, .. })
+    ));
+    assert_eq!(
+        program.lookup(0, &path("CLI::parse_args")),
+        Some(Callee::Builtin(path("Std::CLI::parse_args")))
+    );
+    assert_eq!(
+        program.lookup(0, &path("println")),
+        Some(Callee::Builtin(path("println")))
+    );
+}
+
+#[test]
+fn test_resolve_errors() {
+    let errors = errors(resolve(vec![
+        module(
+            "Example::Main",
+            "using Example::Secret::hidden;
+            using Example::Missing;
+            using Std::Nothing;
+            func main () () { Example::Secret::hidden;
+                Example::Secret::Counter::count; Example::Secret::Counter::missing }",
+        ),
+        module(
+            "Example::Secret",
+            "func hidden () () { () }
+            pub object Counter { func count (ref Counter) () { () } }",
+        ),
+    ]));
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "Example::Main.st".into(),
+                1,
+                1,
+                CompileErrorEnum::PrivateItem("Example::Secret::hidden".into())
+            ),
+            (
+                "Example::Main.st".into(),
+                2,
+                13,
+                CompileErrorEnum::UnknownPath("Example::Missing".into())
+            ),
+            (
+                "Example::Main.st".into(),
+                3,
+                13,
+                CompileErrorEnum::UnknownPath("Std::Nothing".into())
+            ),
+            (
+                "Example::Main.st".into(),
+                4,
+                31,
+                CompileErrorEnum::PrivateItem("Example::Secret::hidden".into())
+            ),
+            (
+                "Example::Main.st".into(),
+                5,
+                17,
+                CompileErrorEnum::PrivateItem("Example::Secret::Counter::count".into())
+            ),
+            (
+                "Example::Main.st".into(),
+                5,
+                50,
+                CompileErrorEnum::UnknownPath("Example::Secret::Counter::missing".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_import_cycle() {
+    let errors = errors(resolve(vec![
+        module("Cycle::A", "using Cycle::B::b; pub func a () () { () }"),
+        module("Cycle::B", "using Cycle::C; pub func b () () { () }"),
+        module("Cycle::C", "using Cycle::A::a;"),
+    ]));
+
+    assert_eq!(
+        errors,
+        vec![(
+            "Cycle::C.st".into(),
+            1,
+            1,
+            CompileErrorEnum::ImportCycle(vec![
+                "Cycle::A".into(),
+                "Cycle::B".into(),
+                "Cycle::C".into(),
+                "Cycle::A".into(),
+            ])
+        )]
+    );
+}
+
+#[test]
+fn test_resolve_example_project() {
+    let project =
+        crate::project::Project::load(FilePath::new("../../examples/example_project/project.st"))
+            .unwrap();
+    let (program, warnings) = project.program().unwrap();
+    assert_eq!(warnings, Vec::new());
+
+    let names = program
+        .modules()
+        .iter()
+        .map(|module| module.name.join("::"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["ExampleProject::HelloWorld", "ExampleProject::Main"]
+    );
+}
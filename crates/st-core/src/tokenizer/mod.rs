@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod tests;
 
-use anyhow::anyhow;
-use std::iter::Peekable;
+use crate::error::{CompileError, CompileErrorEnum};
+use std::{collections::VecDeque, num::IntErrorKind, ops::Range};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenEnum {
@@ -22,6 +22,12 @@ pub enum TokenEnum {
     KWMut,
     /// pub
     KWPub,
+    /// trait
+    KWTrait,
+    /// impl
+    KWImpl,
+    /// for
+    KWFor,
 
     /// |?
     PipeMatch,
@@ -40,6 +46,14 @@ pub enum TokenEnum {
     LessThan,
     /// >
     GreaterThan,
+    /// <=
+    LessEqual,
+    /// >=
+    GreaterEqual,
+    /// ==
+    Equal,
+    /// !=
+    NotEqual,
     /// +
     Plus,
     /// -
@@ -56,6 +70,14 @@ pub enum TokenEnum {
     Arrow,
     /// ||
     Or,
+    /// &&
+    And,
+    /// !
+    Not,
+    /// ..
+    Range,
+    /// ..=
+    RangeInclusive,
 
     /// (
     OpenBrace,
@@ -73,38 +95,143 @@ pub enum TokenEnum {
     SemiColon,
     /// .
     Period,
+    /// `.name`, a `.` directly followed by a name
+    Field(String),
     /// ,
     Comma,
 
     Identifier(String),
     Bool(bool),
-    Integer(i128),
-    Float(f64),
+    /// An integer with the type named by its suffix, `10i32`
+    Integer(i128, Option<&'static str>),
+    /// A float with the type named by its suffix, `1.5f32`
+    Float(f64, Option<&'static str>),
     String(String),
+    /// A string containing `#{expr}`
+    InterpolatedString(Vec<StringPart>),
+    /// `/// text`, the text after the slashes with the space before it removed
+    DocComment(String),
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Token {
+/// A piece of an interpolated string
+#[derive(Debug, PartialEq, Clone)]
+pub enum StringPart {
+    Text(String),
+    /// The tokens of an embedded expression, with the span of the whole `#{...}`
+    Code(Vec<Token>, Span),
+}
+
+/// A position in the source, `row` and `column` start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Position {
+    /// The byte offset from the start of the source
+    pub offset: usize,
     pub row: usize,
     pub column: usize,
+}
+
+impl Position {
+    pub fn new(offset: usize, row: usize, column: usize) -> Self {
+        Self {
+            offset,
+            row,
+            column,
+        }
+    }
+}
+
+/// The source text covered by a token or syntax node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// The position of the first character
+    pub start: Position,
+    /// The position just past the last character
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    /// The span from the start of `self` to the end of `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+
+    /// The byte range of the span, for slicing the source it was read from
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token {
+    pub span: Span,
     pub token: TokenEnum,
 }
 
 pub struct TokenIterItem {
     pub ch: char,
+    /// The byte offset of the character
+    pub offset: usize,
     pub row: usize,
     pub column: usize,
 }
 
+impl TokenIterItem {
+    fn start(&self) -> Position {
+        Position::new(self.offset, self.row, self.column)
+    }
+
+    fn end(&self) -> Position {
+        Position::new(self.offset + self.ch.len_utf8(), self.row, self.column + 1)
+    }
+}
+
+/// The characters being tokenized, remembering where the last consumed character ended so the
+/// span of a token is known once all of its characters have been read
+struct Chars<T: Iterator> {
+    iter: T,
+    /// Characters that have been peeked but not consumed
+    lookahead: VecDeque<TokenIterItem>,
+    end: Position,
+}
+
+impl<T: Iterator<Item = TokenIterItem>> Chars<T> {
+    fn next(&mut self) -> Option<TokenIterItem> {
+        let item = self.lookahead.pop_front().or_else(|| self.iter.next())?;
+        self.end = item.end();
+        Some(item)
+    }
+
+    fn peek(&mut self) -> Option<&TokenIterItem> {
+        self.peek_nth(0)
+    }
+
+    /// The character `n` places after the next one
+    fn peek_nth(&mut self, n: usize) -> Option<&TokenIterItem> {
+        while self.lookahead.len() <= n {
+            self.lookahead.push_back(self.iter.next()?);
+        }
+        self.lookahead.get(n)
+    }
+}
+
 pub struct Tokenize<T: Iterator> {
-    iter: Peekable<T>,
+    iter: Chars<T>,
+    /// Errors found along with the last one returned, such as the other bad escapes in a string
+    errors: VecDeque<CompileError>,
 }
 
 impl<T: Iterator<Item = TokenIterItem>> Iterator for Tokenize<T> {
-    type Item = anyhow::Result<Token>;
+    type Item = Result<Token, CompileError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        get_next(&mut self.iter)
+        if let Some(error) = self.errors.pop_front() {
+            return Some(Err(error));
+        }
+        get_next(&mut self.iter, &mut self.errors)
     }
 }
 
@@ -115,247 +242,267 @@ pub trait TokenizeExt<T: Iterator<Item = TokenIterItem>> {
 impl<T: Iterator<Item = TokenIterItem>> TokenizeExt<T> for T {
     fn tokenize(self) -> Tokenize<T> {
         Tokenize {
-            iter: self.peekable(),
+            iter: Chars {
+                iter: self,
+                lookahead: VecDeque::new(),
+                end: Position::default(),
+            },
+            errors: VecDeque::new(),
         }
     }
 }
 
-pub fn tokenize(source_code: &str) -> impl Iterator<Item = anyhow::Result<Token>> + '_ {
+pub fn tokenize(source_code: &str) -> impl Iterator<Item = Result<Token, CompileError>> + '_ {
     let mut row = 1;
     let mut column = 1;
-    Tokenize {
-        iter: source_code
-            .chars()
-            .map(move |char| match char {
-                '\n' => {
-                    let item = TokenIterItem {
-                        ch: char,
-                        row,
-                        column: column + 1,
-                    };
-
-                    row += 1;
-                    column = 1;
-
-                    item
+    source_code
+        .char_indices()
+        .map(move |(offset, char)| match char {
+            '\n' => {
+                let item = TokenIterItem {
+                    ch: char,
+                    offset,
+                    row,
+                    column: column + 1,
+                };
+
+                row += 1;
+                column = 1;
+
+                item
+            }
+            _ => {
+                let item = TokenIterItem {
+                    ch: char,
+                    offset,
+                    row,
+                    column,
+                };
+                column += 1;
+                item
+            }
+        })
+        .tokenize()
+}
+
+/// Reads the next token, a token with several errors returns the first and leaves the rest in
+/// `errors`
+fn get_next<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    errors: &mut VecDeque<CompileError>,
+) -> Option<Result<Token, CompileError>> {
+    loop {
+        let item = loop {
+            let item = iter.next()?;
+            if !item.ch.is_whitespace() {
+                break item;
+            }
+        };
+        let start = item.start();
+        let ch = item.ch;
+
+        let token = match ch {
+            '/' if matches!(iter.peek(), Some(TokenIterItem { ch: '/', .. })) => {
+                iter.next();
+                match get_comment(iter) {
+                    Some(doc) => Ok(TokenEnum::DocComment(doc)),
+                    None => continue,
                 }
-                _ => {
-                    let item = TokenIterItem {
-                        ch: char,
-                        row,
-                        column,
-                    };
-                    column += 1;
-                    item
+            }
+            '/' if matches!(iter.peek(), Some(TokenIterItem { ch: '*', .. })) => {
+                iter.next();
+                match skip_block_comment(iter) {
+                    Some(()) => continue,
+                    None => Err(CompileError::new(
+                        Span::new(start, iter.end),
+                        CompileErrorEnum::UnclosedComment,
+                    )),
                 }
-            })
-            .peekable(),
+            }
+            '|' => get_chain_operator(iter, start),
+            ';' => Ok(TokenEnum::SemiColon),
+            '.' => Ok(get_period(iter)),
+            '(' => Ok(TokenEnum::OpenBrace),
+            ')' => Ok(TokenEnum::CloseBrace),
+            '{' => Ok(TokenEnum::OpenCurlyBrace),
+            '}' => Ok(TokenEnum::CloseCurlyBrace),
+            '+' => Ok(TokenEnum::Plus),
+            '-' => Ok(get_minus(iter)),
+            '*' => Ok(TokenEnum::Times),
+            '/' => Ok(TokenEnum::Divide),
+            '%' => Ok(TokenEnum::Modulo),
+            '^' => Ok(TokenEnum::Power),
+            '<' => Ok(get_pair(
+                iter,
+                '=',
+                TokenEnum::LessThan,
+                TokenEnum::LessEqual,
+            )),
+            '>' => Ok(get_pair(
+                iter,
+                '=',
+                TokenEnum::GreaterThan,
+                TokenEnum::GreaterEqual,
+            )),
+            '!' => Ok(get_pair(iter, '=', TokenEnum::Not, TokenEnum::NotEqual)),
+            '=' => get_double(iter, '=', TokenEnum::Equal, start),
+            '&' => get_double(iter, '&', TokenEnum::And, start),
+            ':' => Ok(get_colon(iter)),
+            '\\' => get_ends(iter, start),
+            'r' if matches!(iter.peek(), Some(TokenIterItem { ch: '"' | '#', .. })) => {
+                get_raw_string(iter, start)
+            }
+            'a'..='z' | 'A'..='Z' | '_' => Ok(get_identifier(iter, ch)),
+            '0'..='9' => get_number(iter, ch, start),
+            '"' => get_string(iter, start).map_err(|string_errors| {
+                errors.extend(string_errors);
+                errors
+                    .pop_front()
+                    .expect("a string fails with at least one error")
+            }),
+            ',' => Ok(TokenEnum::Comma),
+            _ => Err(CompileError::new(
+                Span::new(start, iter.end),
+                CompileErrorEnum::UnexpectedCharacter(ch),
+            )),
+        };
+
+        return Some(token.map(|token| Token {
+            span: Span::new(start, iter.end),
+            token,
+        }));
     }
 }
 
-fn get_next<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-) -> Option<anyhow::Result<Token>> {
-    let TokenIterItem { ch, row, column } =
-        iter.find(|TokenIterItem { ch, .. }| !ch.is_whitespace())?;
-
-    Some(match ch {
-        '|' => get_chain_operator(iter, column, row),
-        ';' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::SemiColon,
-        }),
-        '.' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::Period,
-        }),
-        '(' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::OpenBrace,
-        }),
-        ')' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::CloseBrace,
-        }),
-        '{' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::OpenCurlyBrace,
-        }),
-        '}' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::CloseCurlyBrace,
-        }),
-        '+' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::Plus,
-        }),
-        '-' => Ok(get_minus(iter, column, row)),
-        '*' => Ok(Token {
-            row,
-            column,
-            token: TokenEnum::Times,
-        }),
-        '/' => return get_slash(iter, column, row),
-        '%' => Ok(Token {
-            row,
-            column,
-            token: TokenEnum::Modulo,
-        }),
-        '^' => Ok(Token {
-            row,
-            column,
-            token: TokenEnum::Power,
-        }),
-        '<' => Ok(Token {
-            row,
-            column,
-            token: TokenEnum::LessThan,
-        }),
-        '>' => Ok(Token {
-            row,
-            column,
-            token: TokenEnum::GreaterThan,
-        }),
-        ':' => Ok(get_colon(iter, column, row)),
-        '\\' => get_ends(iter, column, row),
-        'a'..='z' | 'A'..='Z' | '_' => Ok(get_identifier(iter, ch, column, row)),
-        '0'..='9' => Ok(get_number(iter, ch, column, row)),
-        '"' => get_string(iter, column, row),
-        ',' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::Comma,
-        }),
-        _ => Err(anyhow!("{row}:{column} Unexpected character {ch}")),
-    })
+/// Reads the character after a `|`, a character that does not complete an operator is left
+/// to be read as the next token
+fn get_chain_operator<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Result<TokenEnum, CompileError> {
+    let token = match iter.peek().map(|item| item.ch) {
+        Some('>') => TokenEnum::PipeNext,
+        Some('=') => TokenEnum::PipeSet,
+        Some('?') => TokenEnum::PipeMatch,
+        Some('!') => TokenEnum::PipeError,
+        Some('.') => TokenEnum::PipeReturn,
+        Some('|') => TokenEnum::Or,
+        _ => {
+            return Err(CompileError::new(
+                Span::new(start, iter.end),
+                CompileErrorEnum::IncompletePipe('|'),
+            ))
+        }
+    };
+    iter.next();
+    Ok(token)
 }
 
-fn get_chain_operator<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> anyhow::Result<Token> {
-    let TokenIterItem {
-        ch,
-        row: row1,
-        column: column1,
-    } = iter
-        .next()
-        .ok_or_else(|| anyhow!("{row}:{column} Expected '>' '=' or '?' found EOF"))?;
-    match ch {
-        '>' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeNext,
-        }),
-        '=' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeSet,
-        }),
-        '?' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeMatch,
-        }),
-        '!' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeError,
-        }),
-        '.' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeReturn,
-        }),
-        '|' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::Or,
-        }),
-        _ => Err(anyhow!("{row1}:{column1}: Unexpected character {ch}")),
+fn get_colon<T: Iterator<Item = TokenIterItem>>(iter: &mut Chars<T>) -> TokenEnum {
+    match iter.peek() {
+        Some(TokenIterItem { ch: ':', .. }) => {
+            iter.next();
+            TokenEnum::DoubleColon
+        }
+        _ => TokenEnum::Colon,
     }
 }
 
-fn get_colon<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> Token {
+/// Reads `single`, or `pair` if it is followed by `next`, such as `<` and `<=`
+fn get_pair<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    next: char,
+    single: TokenEnum,
+    pair: TokenEnum,
+) -> TokenEnum {
     match iter.peek() {
-        Some(TokenIterItem { ch: ':', .. }) => {
+        Some(item) if item.ch == next => {
             iter.next();
-            Token {
-                row,
-                column,
-                token: TokenEnum::DoubleColon,
+            pair
+        }
+        _ => single,
+    }
+}
+
+/// Reads an operator that is only valid when its character is followed by `next`, such as `==`
+fn get_double<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    next: char,
+    token: TokenEnum,
+    start: Position,
+) -> Result<TokenEnum, CompileError> {
+    match iter.peek() {
+        Some(item) if item.ch == next => {
+            iter.next();
+            Ok(token)
+        }
+        _ => Err(CompileError::new(
+            Span::new(start, iter.end),
+            CompileErrorEnum::UnexpectedCharacter(next),
+        )),
+    }
+}
+
+fn get_period<T: Iterator<Item = TokenIterItem>>(iter: &mut Chars<T>) -> TokenEnum {
+    match iter.peek() {
+        Some(TokenIterItem { ch: '.', .. }) => {}
+        Some(TokenIterItem {
+            ch: 'a'..='z' | 'A'..='Z' | '_',
+            ..
+        }) => {
+            let mut name = String::new();
+            while let Some(TokenIterItem {
+                ch: ch @ ('a'..='z' | 'A'..='Z' | '_' | '0'..='9'),
+                ..
+            }) = iter.peek()
+            {
+                name.push(*ch);
+                iter.next();
             }
+            return TokenEnum::Field(name);
         }
-        _ => Token {
-            column,
-            row,
-            token: TokenEnum::Colon,
-        },
+        _ => return TokenEnum::Period,
+    }
+    iter.next();
+    match iter.peek() {
+        Some(TokenIterItem { ch: '=', .. }) => {
+            iter.next();
+            TokenEnum::RangeInclusive
+        }
+        _ => TokenEnum::Range,
     }
 }
 
-fn get_minus<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> Token {
+fn get_minus<T: Iterator<Item = TokenIterItem>>(iter: &mut Chars<T>) -> TokenEnum {
     match iter.peek() {
         Some(TokenIterItem { ch: '>', .. }) => {
             iter.next();
-            Token {
-                row,
-                column,
-                token: TokenEnum::Arrow,
-            }
+            TokenEnum::Arrow
         }
-        _ => Token {
-            column,
-            row,
-            token: TokenEnum::Minus,
-        },
+        _ => TokenEnum::Minus,
     }
 }
 
 fn get_ends<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> anyhow::Result<Token> {
-    let TokenIterItem {
-        ch,
-        row: row1,
-        column: column1,
-    } = iter
-        .next()
-        .ok_or_else(|| anyhow!("{row}:{column} Expected '?' found EOF"))?;
-    match ch {
-        '?' => Ok(Token {
-            column,
-            row,
-            token: TokenEnum::PipeMatchEnd,
-        }),
-        _ => Err(anyhow!("{row1}:{column1}: Unexpected character {ch}")),
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Result<TokenEnum, CompileError> {
+    match iter.peek() {
+        Some(TokenIterItem { ch: '?', .. }) => {
+            iter.next();
+            Ok(TokenEnum::PipeMatchEnd)
+        }
+        _ => Err(CompileError::new(
+            Span::new(start, iter.end),
+            CompileErrorEnum::IncompletePipe('\\'),
+        )),
     }
 }
 
 fn get_identifier<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
+    iter: &mut Chars<T>,
     current: char,
-    column: usize,
-    row: usize,
-) -> Token {
+) -> TokenEnum {
     let mut str = format!("{current}");
 
     while {
@@ -370,133 +517,372 @@ fn get_identifier<T: Iterator<Item = TokenIterItem>>(
         str.push(iter.next().unwrap().ch);
     }
 
-    Token {
-        token: match str.as_str() {
-            "data" => TokenEnum::KWData,
-            "func" => TokenEnum::KWFunc,
-            "using" => TokenEnum::KWUsing,
-            "object" => TokenEnum::KWObject,
-            "enum" => TokenEnum::KWEnum,
-            "ref" => TokenEnum::KWRef,
-            "mut" => TokenEnum::KWMut,
-            "true" => TokenEnum::Bool(true),
-            "false" => TokenEnum::Bool(false),
-
-            _ => TokenEnum::Identifier(str),
-        },
-        column,
-        row,
+    match str.as_str() {
+        "data" => TokenEnum::KWData,
+        "func" => TokenEnum::KWFunc,
+        "using" => TokenEnum::KWUsing,
+        "object" => TokenEnum::KWObject,
+        "enum" => TokenEnum::KWEnum,
+        "ref" => TokenEnum::KWRef,
+        "mut" => TokenEnum::KWMut,
+        "pub" => TokenEnum::KWPub,
+        "trait" => TokenEnum::KWTrait,
+        "impl" => TokenEnum::KWImpl,
+        "for" => TokenEnum::KWFor,
+        "true" => TokenEnum::Bool(true),
+        "false" => TokenEnum::Bool(false),
+
+        _ => TokenEnum::Identifier(str),
     }
 }
 
+/// Number type suffixes with the type they give the literal
+const NUMBER_SUFFIXES: &[(&str, &str)] = &[
+    ("i8", "Int8"),
+    ("i16", "Int16"),
+    ("i32", "Int32"),
+    ("i64", "Int64"),
+    ("i128", "Int128"),
+    ("u8", "UInt8"),
+    ("u16", "UInt16"),
+    ("u32", "UInt32"),
+    ("u64", "UInt64"),
+    ("f32", "Float32"),
+    ("f64", "Float64"),
+];
+
+/// Reads a number such as `1_000`, `0xff`, `0b1010`, `1.5e-3` or `10i32`. A `.` is only part
+/// of the number when a digit follows it, so `0..n` is an integer followed by `..`
 fn get_number<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
+    iter: &mut Chars<T>,
     current: char,
-    column: usize,
-    row: usize,
-) -> Token {
+    start: Position,
+) -> Result<TokenEnum, CompileError> {
     let mut str = format!("{current}");
-    let mut found_decimal = false;
+    let radix = match (current, iter.peek().map(|item| item.ch)) {
+        ('0', Some('x')) => 16,
+        ('0', Some('o')) => 8,
+        ('0', Some('b')) => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        str.push(iter.next().unwrap().ch);
+    }
 
-    while {
-        match iter.peek() {
-            Some(TokenIterItem { ch: '0'..='9', .. }) => true,
-            Some(TokenIterItem { ch: '.', .. }) => {
-                if !found_decimal {
-                    found_decimal = true;
-                    true
-                } else {
-                    false
-                }
+    // Letters are read too so a number running into an identifier, `12ab`, is reported whole
+    while let Some(item) = iter.peek() {
+        let ch = item.ch;
+        let accepted = match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => true,
+            '.' => {
+                radix == 10
+                    && !str.contains(['.', 'e', 'E'])
+                    && matches!(iter.peek_nth(1), Some(TokenIterItem { ch: '0'..='9', .. }))
             }
+            '+' | '-' => radix == 10 && str.ends_with(['e', 'E']),
             _ => false,
+        };
+        if !accepted {
+            break;
         }
-    } {
-        str.push(iter.next().unwrap().ch);
+        iter.next();
+        str.push(ch);
     }
 
-    Token {
-        token: if str.contains('.') {
-            TokenEnum::Float(str.parse().unwrap())
-        } else {
-            TokenEnum::Integer(str.parse().unwrap())
-        },
-        column,
-        row,
+    parse_number(&str, radix).map_err(|error| CompileError::new(Span::new(start, iter.end), error))
+}
+
+fn parse_number(str: &str, radix: u32) -> Result<TokenEnum, CompileErrorEnum> {
+    let malformed = || CompileErrorEnum::MalformedNumber(str.into());
+    let out_of_range = |ty: &'static str| CompileErrorEnum::NumberOutOfRange {
+        literal: str.into(),
+        ty,
+    };
+
+    let (body, ty) = NUMBER_SUFFIXES
+        .iter()
+        // `f32` is made of hex digits so hex numbers only take integer suffixes
+        .filter(|(suffix, _)| radix != 16 || !suffix.starts_with('f'))
+        .find_map(|(suffix, ty)| Some((str.strip_suffix(suffix)?, Some(*ty))))
+        .unwrap_or((str, None));
+    // Separators are single underscores between digits, so `1__0`, `1_`, `0x_ff`, `1_.5`,
+    // `1e_5` and `1_i32` are typos
+    let mut groups = if radix == 10 { body } else { &body[2..] }
+        .split(|ch| radix == 10 && matches!(ch, '.' | 'e' | 'E' | '+' | '-'));
+    if groups.any(|group| group.starts_with('_') || group.ends_with('_') || group.contains("__")) {
+        return Err(malformed());
     }
+    let digits = body.chars().filter(|ch| *ch != '_').collect::<String>();
+    let is_float_type = ty.is_some_and(|ty| ty.starts_with("Float"));
+
+    if radix == 10 && (digits.contains(['.', 'e', 'E']) || is_float_type) {
+        if ty.is_some() && !is_float_type {
+            return Err(malformed());
+        }
+        let value = digits.parse::<f64>().map_err(|_| malformed())?;
+        let max = match ty {
+            Some("Float32") => f32::MAX as f64,
+            _ => f64::MAX,
+        };
+        if !value.is_finite() || value > max {
+            return Err(out_of_range(ty.unwrap_or("Float64")));
+        }
+        return Ok(TokenEnum::Float(value, ty));
+    }
+
+    let digits = if radix == 10 { &digits } else { &digits[2..] };
+    let value = i128::from_str_radix(digits, radix).map_err(|error| match error.kind() {
+        IntErrorKind::PosOverflow => out_of_range(ty.unwrap_or("Int128")),
+        _ => malformed(),
+    })?;
+    let max = match ty {
+        Some("Int8") => i8::MAX as i128,
+        Some("Int16") => i16::MAX as i128,
+        Some("Int32") => i32::MAX as i128,
+        Some("Int64") => i64::MAX as i128,
+        Some("UInt8") => u8::MAX as i128,
+        Some("UInt16") => u16::MAX as i128,
+        Some("UInt32") => u32::MAX as i128,
+        Some("UInt64") => u64::MAX as i128,
+        _ => i128::MAX,
+    };
+    if value > max {
+        return Err(out_of_range(ty.unwrap_or("Int128")));
+    }
+    Ok(TokenEnum::Integer(value, ty))
 }
 
+/// Reads a string after its opening `"`, failing with every bad escape and every error in its
+/// interpolations
 fn get_string<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> anyhow::Result<Token> {
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Result<TokenEnum, Vec<CompileError>> {
+    let mut parts = Vec::new();
     let mut str = String::new();
-    let mut escape = false;
-
-    while {
-        match iter.peek() {
-            Some(TokenIterItem { ch: '"', .. }) => {
-                if escape {
-                    escape = false;
-                    str.push('"');
-                    true
-                } else {
-                    false
+    // The rest of the string is still read after a bad escape so tokenizing resumes after it
+    let mut errors = Vec::new();
+    let unclosed = |iter: &Chars<T>, mut errors: Vec<CompileError>| {
+        errors.push(CompileError::new(
+            Span::new(start, iter.end),
+            CompileErrorEnum::UnclosedString,
+        ));
+        errors
+    };
+
+    loop {
+        let Some(item) = iter.next() else {
+            return Err(unclosed(iter, errors));
+        };
+
+        match item.ch {
+            '"' => break,
+            '\\' => match get_escape(iter, item.start()) {
+                Some(Ok(ch)) => str.push(ch),
+                Some(Err(escape_error)) => errors.push(escape_error),
+                None => return Err(unclosed(iter, errors)),
+            },
+            '#' if matches!(iter.peek(), Some(TokenIterItem { ch: '{', .. })) => {
+                iter.next();
+                let Some(code) = get_interpolation(iter) else {
+                    return Err(unclosed(iter, errors));
+                };
+                if !str.is_empty() {
+                    parts.push(StringPart::Text(std::mem::take(&mut str)));
+                }
+                let mut tokens = Vec::new();
+                for token in code.into_iter().tokenize() {
+                    match token {
+                        Ok(token) => tokens.push(token),
+                        Err(code_error) => errors.push(code_error),
+                    }
                 }
+                parts.push(StringPart::Code(tokens, Span::new(item.start(), iter.end)));
             }
-            Some(TokenIterItem { ch: '\\', .. }) => {
-                escape = true;
-                true
+            ch => str.push(ch),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if parts.is_empty() {
+        return Ok(TokenEnum::String(str));
+    }
+    if !str.is_empty() {
+        parts.push(StringPart::Text(str));
+    }
+    Ok(TokenEnum::InterpolatedString(parts))
+}
+
+/// Reads the escape sequence after a `\\` at `start`, [None] if the input ends first
+fn get_escape<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Option<Result<char, CompileError>> {
+    let ch = match iter.next()?.ch {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        '#' => '#',
+        'u' => return Some(get_unicode_escape(iter, start)),
+        ch => {
+            return Some(Err(CompileError::new(
+                Span::new(start, iter.end),
+                CompileErrorEnum::InvalidEscape(ch),
+            )))
+        }
+    };
+    Some(Ok(ch))
+}
+
+/// Reads the `{1F600}` of a `\\u{1F600}` escape, up to 6 hex digits naming a unicode scalar value
+fn get_unicode_escape<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Result<char, CompileError> {
+    let invalid = |iter: &Chars<T>, digits: String| {
+        CompileError::new(
+            Span::new(start, iter.end),
+            CompileErrorEnum::InvalidUnicodeEscape(digits),
+        )
+    };
+
+    let mut digits = String::new();
+    if !matches!(iter.peek(), Some(TokenIterItem { ch: '{', .. })) {
+        return Err(invalid(iter, digits));
+    }
+    iter.next();
+    loop {
+        match iter.peek() {
+            Some(TokenIterItem { ch: '}', .. }) => {
+                iter.next();
+                break;
             }
-            Some(TokenIterItem { ch, .. }) => {
-                str.push(*ch);
-                true
+            Some(TokenIterItem { ch, .. }) if ch.is_ascii_hexdigit() => {
+                digits.push(iter.next().unwrap().ch)
             }
+            _ => return Err(invalid(iter, digits)),
+        }
+    }
 
-            None => {
-                return Err(anyhow!(
-                    "{row}:{column} Unclosed String, could not find closing '\"'"
-                ))
-            }
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .filter(|_| digits.len() <= 6)
+        .and_then(char::from_u32)
+        .ok_or_else(|| invalid(iter, digits))
+}
+
+/// `r"..."` or `r#"..."#`, the text is kept as written without escapes or interpolation. Any
+/// number of `#` can be used so the string can contain `"#`
+fn get_raw_string<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+    start: Position,
+) -> Result<TokenEnum, CompileError> {
+    let unclosed = |iter: &Chars<T>| {
+        CompileError::new(Span::new(start, iter.end), CompileErrorEnum::UnclosedString)
+    };
+
+    let mut hashes = 0;
+    while let Some(TokenIterItem { ch: '#', .. }) = iter.peek() {
+        iter.next();
+        hashes += 1;
+    }
+    match iter.next() {
+        Some(TokenIterItem { ch: '"', .. }) => {}
+        Some(item) => {
+            return Err(CompileError::new(
+                Span::new(item.start(), iter.end),
+                CompileErrorEnum::UnexpectedCharacter(item.ch),
+            ))
         }
-    } {
+        None => return Err(unclosed(iter)),
+    }
+
+    let mut str = String::new();
+    loop {
+        let Some(item) = iter.next() else {
+            return Err(unclosed(iter));
+        };
+        if item.ch != '"' {
+            str.push(item.ch);
+            continue;
+        }
+
+        let mut closing = 0;
+        while closing < hashes && matches!(iter.peek(), Some(TokenIterItem { ch: '#', .. })) {
+            iter.next();
+            closing += 1;
+        }
+        if closing == hashes {
+            return Ok(TokenEnum::String(str));
+        }
+        str.push('"');
+        str.extend(std::iter::repeat_n('#', closing));
+    }
+}
+
+/// Reads the characters of a `#{expr}` up to its closing `}`, skipping over braces in nested
+/// blocks and strings. [None] if the input ends first
+fn get_interpolation<T: Iterator<Item = TokenIterItem>>(
+    iter: &mut Chars<T>,
+) -> Option<Vec<TokenIterItem>> {
+    let mut code = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escape = false;
+
+    loop {
+        let item = iter.next()?;
+        match item.ch {
+            _ if escape => escape = false,
+            '\\' if in_string => escape = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string && depth == 0 => return Some(code),
+            '}' if !in_string => depth -= 1,
+            _ => {}
+        }
+        code.push(item);
+    }
+}
+
+/// Reads the rest of a line after `//`, returning the text of a `///` doc comment. Comments
+/// starting with four or more slashes are not doc comments
+fn get_comment<T: Iterator<Item = TokenIterItem>>(iter: &mut Chars<T>) -> Option<String> {
+    let mut text = String::new();
+    while let Some(item) = iter.peek() {
+        if item.ch == '\n' {
+            break;
+        }
+        text.push(item.ch);
         iter.next();
     }
 
-    iter.next();
+    let doc = text.strip_prefix('/').filter(|doc| !doc.starts_with('/'))?;
+    Some(doc.strip_prefix(' ').unwrap_or(doc).trim_end().to_string())
+}
 
-    Ok(Token {
-        token: TokenEnum::String(str),
-        column,
-        row,
-    })
-}
-
-fn get_slash<T: Iterator<Item = TokenIterItem>>(
-    iter: &mut Peekable<T>,
-    column: usize,
-    row: usize,
-) -> Option<anyhow::Result<Token>> {
-    let next = iter.peek();
-
-    match next {
-        Some(TokenIterItem { ch: '/', .. }) => {
-            while {
-                match iter.peek() {
-                    Some(TokenIterItem { ch: '\n', .. }) => false,
-                    Some(_) => true,
-                    None => false,
-                }
-            } {
+/// Skips the rest of a `/* ... */` comment after the `/*`, comments can be nested. Returns
+/// [None] if the comment is not closed
+fn skip_block_comment<T: Iterator<Item = TokenIterItem>>(iter: &mut Chars<T>) -> Option<()> {
+    let mut depth = 1;
+    while depth > 0 {
+        let item = iter.next()?;
+        match (item.ch, iter.peek().map(|next| next.ch)) {
+            ('*', Some('/')) => {
+                iter.next();
+                depth -= 1;
+            }
+            ('/', Some('*')) => {
                 iter.next();
+                depth += 1;
             }
-            get_next(iter)
+            _ => {}
         }
-        _ => Some(Ok(Token {
-            token: TokenEnum::Divide,
-            column,
-            row,
-        })),
     }
+    Some(())
 }
@@ -1,44 +1,50 @@
 use super::*;
 
+/// The start of each error's span with its kind
+fn errors(source: &str) -> Vec<(usize, usize, CompileErrorEnum)> {
+    tokenize(source)
+        .filter_map(Result::err)
+        .map(|error| {
+            (
+                error.span().start.row,
+                error.span().start.column,
+                error.error().clone(),
+            )
+        })
+        .collect()
+}
+
 #[test]
 fn test_pipe_operators() {
     let test_str = "|> |! |. |= |? \\?";
     let expected = vec![
         Ok(Token {
             token: TokenEnum::PipeNext,
-            row: 1,
-            column: 1,
+            span: Span::new(Position::new(0, 1, 1), Position::new(2, 1, 3)),
         }),
         Ok(Token {
             token: TokenEnum::PipeError,
-            row: 1,
-            column: 4,
+            span: Span::new(Position::new(3, 1, 4), Position::new(5, 1, 6)),
         }),
         Ok(Token {
             token: TokenEnum::PipeReturn,
-            row: 1,
-            column: 7,
+            span: Span::new(Position::new(6, 1, 7), Position::new(8, 1, 9)),
         }),
         Ok(Token {
             token: TokenEnum::PipeSet,
-            row: 1,
-            column: 10,
+            span: Span::new(Position::new(9, 1, 10), Position::new(11, 1, 12)),
         }),
         Ok(Token {
             token: TokenEnum::PipeMatch,
-            row: 1,
-            column: 13,
+            span: Span::new(Position::new(12, 1, 13), Position::new(14, 1, 15)),
         }),
         Ok(Token {
             token: TokenEnum::PipeMatchEnd,
-            row: 1,
-            column: 16,
+            span: Span::new(Position::new(15, 1, 16), Position::new(17, 1, 18)),
         }),
     ];
 
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -58,93 +64,74 @@ fn parse_singles() {
     let expected = vec![
         Ok(Token {
             token: TokenEnum::Plus,
-            row: 1,
-            column: 1,
+            span: Span::new(Position::new(0, 1, 1), Position::new(1, 1, 2)),
         }),
         Ok(Token {
             token: TokenEnum::Minus,
-            row: 1,
-            column: 2,
+            span: Span::new(Position::new(1, 1, 2), Position::new(2, 1, 3)),
         }),
         Ok(Token {
             token: TokenEnum::Times,
-            row: 1,
-            column: 3,
+            span: Span::new(Position::new(2, 1, 3), Position::new(3, 1, 4)),
         }),
         Ok(Token {
             token: TokenEnum::Divide,
-            row: 1,
-            column: 4,
+            span: Span::new(Position::new(3, 1, 4), Position::new(4, 1, 5)),
         }),
         Ok(Token {
             token: TokenEnum::Modulo,
-            row: 1,
-            column: 5,
+            span: Span::new(Position::new(4, 1, 5), Position::new(5, 1, 6)),
         }),
         Ok(Token {
             token: TokenEnum::Power,
-            row: 1,
-            column: 6,
+            span: Span::new(Position::new(5, 1, 6), Position::new(6, 1, 7)),
         }),
         Ok(Token {
             token: TokenEnum::Period,
-            row: 1,
-            column: 7,
+            span: Span::new(Position::new(6, 1, 7), Position::new(7, 1, 8)),
         }),
         Ok(Token {
             token: TokenEnum::OpenBrace,
-            row: 1,
-            column: 8,
+            span: Span::new(Position::new(7, 1, 8), Position::new(8, 1, 9)),
         }),
         Ok(Token {
             token: TokenEnum::CloseBrace,
-            row: 1,
-            column: 9,
+            span: Span::new(Position::new(8, 1, 9), Position::new(9, 1, 10)),
         }),
         Ok(Token {
             token: TokenEnum::OpenCurlyBrace,
-            row: 1,
-            column: 10,
+            span: Span::new(Position::new(9, 1, 10), Position::new(10, 1, 11)),
         }),
         Ok(Token {
             token: TokenEnum::CloseCurlyBrace,
-            row: 1,
-            column: 11,
+            span: Span::new(Position::new(10, 1, 11), Position::new(11, 1, 12)),
         }),
         Ok(Token {
             token: TokenEnum::LessThan,
-            row: 1,
-            column: 12,
+            span: Span::new(Position::new(11, 1, 12), Position::new(12, 1, 13)),
         }),
         Ok(Token {
             token: TokenEnum::GreaterThan,
-            row: 1,
-            column: 13,
+            span: Span::new(Position::new(12, 1, 13), Position::new(13, 1, 14)),
         }),
         Ok(Token {
             token: TokenEnum::SemiColon,
-            row: 1,
-            column: 14,
+            span: Span::new(Position::new(13, 1, 14), Position::new(14, 1, 15)),
         }),
         Ok(Token {
             token: TokenEnum::Colon,
-            row: 1,
-            column: 15,
+            span: Span::new(Position::new(14, 1, 15), Position::new(15, 1, 16)),
         }),
         Ok(Token {
             token: TokenEnum::Period,
-            row: 1,
-            column: 16,
+            span: Span::new(Position::new(15, 1, 16), Position::new(16, 1, 17)),
         }),
         Ok(Token {
             token: TokenEnum::Comma,
-            row: 1,
-            column: 17,
+            span: Span::new(Position::new(16, 1, 17), Position::new(17, 1, 18)),
         }),
     ];
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -159,68 +146,63 @@ fn parse_singles() {
 
 #[test]
 fn test_words() {
-    let test_str = "func data using.object enum lalala true false mut ref";
+    let test_str = "func data using.object enum lalala true false mut ref trait impl for";
 
     let expected = vec![
         Ok(Token {
             token: TokenEnum::KWFunc,
-            row: 1,
-            column: 1,
+            span: Span::new(Position::new(0, 1, 1), Position::new(4, 1, 5)),
         }),
         Ok(Token {
             token: TokenEnum::KWData,
-            row: 1,
-            column: 6,
+            span: Span::new(Position::new(5, 1, 6), Position::new(9, 1, 10)),
         }),
         Ok(Token {
             token: TokenEnum::KWUsing,
-            row: 1,
-            column: 11,
-        }),
-        Ok(Token {
-            token: TokenEnum::Period,
-            row: 1,
-            column: 16,
+            span: Span::new(Position::new(10, 1, 11), Position::new(15, 1, 16)),
         }),
         Ok(Token {
-            token: TokenEnum::KWObject,
-            row: 1,
-            column: 17,
+            token: TokenEnum::Field("object".into()),
+            span: Span::new(Position::new(15, 1, 16), Position::new(22, 1, 23)),
         }),
         Ok(Token {
             token: TokenEnum::KWEnum,
-            row: 1,
-            column: 24,
+            span: Span::new(Position::new(23, 1, 24), Position::new(27, 1, 28)),
         }),
         Ok(Token {
             token: TokenEnum::Identifier("lalala".into()),
-            row: 1,
-            column: 29,
+            span: Span::new(Position::new(28, 1, 29), Position::new(34, 1, 35)),
         }),
         Ok(Token {
             token: TokenEnum::Bool(true),
-            row: 1,
-            column: 36,
+            span: Span::new(Position::new(35, 1, 36), Position::new(39, 1, 40)),
         }),
         Ok(Token {
             token: TokenEnum::Bool(false),
-            row: 1,
-            column: 41,
+            span: Span::new(Position::new(40, 1, 41), Position::new(45, 1, 46)),
         }),
         Ok(Token {
             token: TokenEnum::KWMut,
-            column: 47,
-            row: 1,
+            span: Span::new(Position::new(46, 1, 47), Position::new(49, 1, 50)),
         }),
         Ok(Token {
             token: TokenEnum::KWRef,
-            column: 51,
-            row: 1,
+            span: Span::new(Position::new(50, 1, 51), Position::new(53, 1, 54)),
+        }),
+        Ok(Token {
+            token: TokenEnum::KWTrait,
+            span: Span::new(Position::new(54, 1, 55), Position::new(59, 1, 60)),
+        }),
+        Ok(Token {
+            token: TokenEnum::KWImpl,
+            span: Span::new(Position::new(60, 1, 61), Position::new(64, 1, 65)),
+        }),
+        Ok(Token {
+            token: TokenEnum::KWFor,
+            span: Span::new(Position::new(65, 1, 66), Position::new(68, 1, 69)),
         }),
     ];
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -239,24 +221,19 @@ fn test_misc() {
     let expected = vec![
         Ok(Token {
             token: TokenEnum::DoubleColon,
-            row: 1,
-            column: 1,
+            span: Span::new(Position::new(0, 1, 1), Position::new(2, 1, 3)),
         }),
         Ok(Token {
             token: TokenEnum::Arrow,
-            row: 1,
-            column: 3,
+            span: Span::new(Position::new(2, 1, 3), Position::new(4, 1, 5)),
         }),
         Ok(Token {
             token: TokenEnum::Or,
-            row: 1,
-            column: 5,
+            span: Span::new(Position::new(4, 1, 5), Position::new(6, 1, 7)),
         }),
     ];
 
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -269,40 +246,57 @@ fn test_misc() {
         });
 }
 
+#[test]
+fn test_comparison_operators() {
+    let tokens = tokenize("< <= > >= == != && ! !x |!")
+        .map(|token| token.unwrap().token)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenEnum::LessThan,
+            TokenEnum::LessEqual,
+            TokenEnum::GreaterThan,
+            TokenEnum::GreaterEqual,
+            TokenEnum::Equal,
+            TokenEnum::NotEqual,
+            TokenEnum::And,
+            TokenEnum::Not,
+            TokenEnum::Not,
+            TokenEnum::Identifier("x".into()),
+            TokenEnum::PipeError,
+        ]
+    );
+}
+
 #[test]
 fn test_parse_numbers() {
     let test_str = "123 123.123 123.123.123";
     let expected = vec![
         Ok(Token {
-            token: TokenEnum::Integer(123),
-            row: 1,
-            column: 1,
+            token: TokenEnum::Integer(123, None),
+            span: Span::new(Position::new(0, 1, 1), Position::new(3, 1, 4)),
         }),
         Ok(Token {
-            token: TokenEnum::Float(123.123),
-            row: 1,
-            column: 5,
+            token: TokenEnum::Float(123.123, None),
+            span: Span::new(Position::new(4, 1, 5), Position::new(11, 1, 12)),
         }),
         Ok(Token {
-            token: TokenEnum::Float(123.123),
-            row: 1,
-            column: 13,
+            token: TokenEnum::Float(123.123, None),
+            span: Span::new(Position::new(12, 1, 13), Position::new(19, 1, 20)),
         }),
         Ok(Token {
             token: TokenEnum::Period,
-            row: 1,
-            column: 20,
+            span: Span::new(Position::new(19, 1, 20), Position::new(20, 1, 21)),
         }),
         Ok(Token {
-            token: TokenEnum::Integer(123),
-            row: 1,
-            column: 21,
+            token: TokenEnum::Integer(123, None),
+            span: Span::new(Position::new(20, 1, 21), Position::new(23, 1, 24)),
         }),
     ];
 
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -315,19 +309,100 @@ fn test_parse_numbers() {
         });
 }
 
+#[test]
+fn test_number_grammar() {
+    let tokens =
+        tokenize("1_000 0xff_ff 0o17 0b1010 1.5e3 2E-2 10i32 0x1fu8 1.5f32 7f64 0..n 1..=10 1.max")
+            .map(|token| token.unwrap().token)
+            .collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenEnum::Integer(1000, None),
+            TokenEnum::Integer(0xffff, None),
+            TokenEnum::Integer(0o17, None),
+            TokenEnum::Integer(0b1010, None),
+            TokenEnum::Float(1500.0, None),
+            TokenEnum::Float(0.02, None),
+            TokenEnum::Integer(10, Some("Int32")),
+            TokenEnum::Integer(0x1f, Some("UInt8")),
+            TokenEnum::Float(1.5, Some("Float32")),
+            TokenEnum::Float(7.0, Some("Float64")),
+            TokenEnum::Integer(0, None),
+            TokenEnum::Range,
+            TokenEnum::Identifier("n".into()),
+            TokenEnum::Integer(1, None),
+            TokenEnum::RangeInclusive,
+            TokenEnum::Integer(10, None),
+            TokenEnum::Integer(1, None),
+            TokenEnum::Field("max".into()),
+        ]
+    );
+
+    assert_eq!(
+        errors("256u8 1.5i32 0x 0b12 1e400 128i8 1__0 0xff__ffu8"),
+        vec![
+            (
+                1,
+                1,
+                CompileErrorEnum::NumberOutOfRange {
+                    literal: "256u8".into(),
+                    ty: "UInt8"
+                }
+            ),
+            (1, 7, CompileErrorEnum::MalformedNumber("1.5i32".into())),
+            (1, 14, CompileErrorEnum::MalformedNumber("0x".into())),
+            (1, 17, CompileErrorEnum::MalformedNumber("0b12".into())),
+            (
+                1,
+                22,
+                CompileErrorEnum::NumberOutOfRange {
+                    literal: "1e400".into(),
+                    ty: "Float64"
+                }
+            ),
+            (
+                1,
+                28,
+                CompileErrorEnum::NumberOutOfRange {
+                    literal: "128i8".into(),
+                    ty: "Int8"
+                }
+            ),
+            (1, 34, CompileErrorEnum::MalformedNumber("1__0".into())),
+            (
+                1,
+                39,
+                CompileErrorEnum::MalformedNumber("0xff__ffu8".into())
+            ),
+        ]
+    );
+    assert_eq!(
+        errors("1_ 0x_ff 1_.5 1._5 1_e5 1e_5 1e-_5 1_i32 0b1_"),
+        vec![
+            (1, 1, CompileErrorEnum::MalformedNumber("1_".into())),
+            (1, 4, CompileErrorEnum::MalformedNumber("0x_ff".into())),
+            (1, 10, CompileErrorEnum::MalformedNumber("1_.5".into())),
+            (1, 20, CompileErrorEnum::MalformedNumber("1_e5".into())),
+            (1, 25, CompileErrorEnum::MalformedNumber("1e_5".into())),
+            (1, 30, CompileErrorEnum::MalformedNumber("1e-_5".into())),
+            (1, 36, CompileErrorEnum::MalformedNumber("1_i32".into())),
+            (1, 42, CompileErrorEnum::MalformedNumber("0b1_".into())),
+        ]
+    );
+}
+
 #[test]
 fn test_parse_str() {
     let test_str = r#""This is a \"beautiful\" 
 string""#;
     let expected = vec![Ok(Token {
         token: TokenEnum::String("This is a \"beautiful\" \nstring".into()),
-        row: 1,
-        column: 1,
+        span: Span::new(Position::new(0, 1, 1), Position::new(33, 2, 8)),
     })];
 
-    let actual = tokenize(test_str)
-        .map(|item| item.map_err(|e| e.to_string()))
-        .collect::<Vec<_>>();
+    let actual = tokenize(test_str).collect::<Vec<_>>();
 
     assert_eq!(expected.len(), actual.len(), "{actual:#?}");
 
@@ -340,6 +415,180 @@ string""#;
         });
 }
 
+#[test]
+fn test_escapes() {
+    let strings = tokenize(r###""a\nb\t\"c\"\\ \#{d} \u{1F600}\u{e9}" r"\n #{x}" r##"a "# b"##"###)
+        .map(|token| token.unwrap().token)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        strings,
+        vec![
+            TokenEnum::String("a\nb\t\"c\"\\ #{d} \u{1F600}\u{e9}".into()),
+            TokenEnum::String(r"\n #{x}".into()),
+            TokenEnum::String(r##"a "# b"##.into()),
+        ]
+    );
+    assert_eq!(
+        errors(r#""ab \q \u{110000} \u{zz}" r#"a"#),
+        vec![
+            (1, 5, CompileErrorEnum::InvalidEscape('q')),
+            (
+                1,
+                8,
+                CompileErrorEnum::InvalidUnicodeEscape("110000".into())
+            ),
+            (1, 19, CompileErrorEnum::InvalidUnicodeEscape("".into())),
+            (1, 27, CompileErrorEnum::UnclosedString),
+        ]
+    );
+    assert_eq!(
+        errors(r#""\u{110000}" "\u{zz}" "\u1""#),
+        vec![
+            (
+                1,
+                2,
+                CompileErrorEnum::InvalidUnicodeEscape("110000".into())
+            ),
+            (1, 15, CompileErrorEnum::InvalidUnicodeEscape(String::new())),
+            (1, 24, CompileErrorEnum::InvalidUnicodeEscape(String::new())),
+        ]
+    );
+}
+
+#[test]
+fn test_interpolated_str() {
+    let test_str = r#""a #{b + "}"} c #{.}""#;
+    let token = |token, start: usize, end: usize| Token {
+        token,
+        span: Span::new(
+            Position::new(start, 1, start + 1),
+            Position::new(end, 1, end + 1),
+        ),
+    };
+    let expected = vec![Ok(token(
+        TokenEnum::InterpolatedString(vec![
+            StringPart::Text("a ".into()),
+            StringPart::Code(
+                vec![
+                    token(TokenEnum::Identifier("b".into()), 5, 6),
+                    token(TokenEnum::Plus, 7, 8),
+                    token(TokenEnum::String("}".into()), 9, 12),
+                ],
+                Span::new(Position::new(3, 1, 4), Position::new(13, 1, 14)),
+            ),
+            StringPart::Text(" c ".into()),
+            StringPart::Code(
+                vec![token(TokenEnum::Period, 18, 19)],
+                Span::new(Position::new(16, 1, 17), Position::new(20, 1, 21)),
+            ),
+        ]),
+        0,
+        21,
+    ))];
+
+    assert_eq!(tokenize(test_str).collect::<Vec<_>>(), expected);
+    assert_eq!(
+        errors(r##""#{ $ }" "#{a"##),
+        vec![
+            (1, 5, CompileErrorEnum::UnexpectedCharacter('$')),
+            (1, 10, CompileErrorEnum::UnclosedString),
+        ]
+    );
+    assert_eq!(
+        errors(r##""#{$} and #{@}" "\q \z" "#{~}""##),
+        vec![
+            (1, 4, CompileErrorEnum::UnexpectedCharacter('$')),
+            (1, 13, CompileErrorEnum::UnexpectedCharacter('@')),
+            (1, 18, CompileErrorEnum::InvalidEscape('q')),
+            (1, 21, CompileErrorEnum::InvalidEscape('z')),
+            (1, 28, CompileErrorEnum::UnexpectedCharacter('~')),
+        ]
+    );
+}
+
+#[test]
+fn test_comments() {
+    let source = "a // line
+/* block /* nested */ still a comment */ b
+/// First line
+///Second line
+//// not a doc comment
+c /**/ / d";
+    let tokens = tokenize(source)
+        .map(|token| token.map(|token| token.token))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenEnum::Identifier("a".into()),
+            TokenEnum::Identifier("b".into()),
+            TokenEnum::DocComment("First line".into()),
+            TokenEnum::DocComment("Second line".into()),
+            TokenEnum::Identifier("c".into()),
+            TokenEnum::Divide,
+            TokenEnum::Identifier("d".into()),
+        ]
+    );
+    assert_eq!(
+        errors("a /* open /* nested */"),
+        vec![(1, 3, CompileErrorEnum::UnclosedComment)]
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        errors("a $ b"),
+        vec![(1, 3, CompileErrorEnum::UnexpectedCharacter('$'))]
+    );
+    assert_eq!(
+        errors("a = b & c"),
+        vec![
+            (1, 3, CompileErrorEnum::UnexpectedCharacter('=')),
+            (1, 7, CompileErrorEnum::UnexpectedCharacter('&')),
+        ]
+    );
+    assert_eq!(
+        errors("| x \\"),
+        vec![
+            (1, 1, CompileErrorEnum::IncompletePipe('|')),
+            (1, 5, CompileErrorEnum::IncompletePipe('\\')),
+        ]
+    );
+    assert_eq!(
+        errors("12ab 340282366920938463463374607431768211456 1u128"),
+        vec![
+            (1, 1, CompileErrorEnum::MalformedNumber("12ab".into())),
+            (
+                1,
+                6,
+                CompileErrorEnum::NumberOutOfRange {
+                    literal: "340282366920938463463374607431768211456".into(),
+                    ty: "Int128"
+                }
+            ),
+            (1, 46, CompileErrorEnum::MalformedNumber("1u128".into())),
+        ]
+    );
+    assert_eq!(
+        errors(r#""a \q b" "open"#),
+        vec![
+            (1, 4, CompileErrorEnum::InvalidEscape('q')),
+            (1, 10, CompileErrorEnum::UnclosedString),
+        ]
+    );
+}
+
+#[test]
+fn test_error_spans() {
+    let source = r#"x "a \q b""#;
+    let error = tokenize(source).find_map(Result::err).unwrap();
+    assert_eq!(&source[error.span().range()], "\\q");
+}
+
 #[test]
 fn test_examples() {
     let files = glob::glob("../../examples/**/*.st").unwrap();
@@ -350,12 +599,42 @@ fn test_examples() {
         let path = a.unwrap();
         let source = std::fs::read_to_string(&path).unwrap();
         let errors = tokenize(&source)
-            .filter_map(|item| match item {
-                Ok(_) => None,
-                Err(e) => Some(e),
-            })
+            .filter_map(Result::err)
             .collect::<Vec<_>>();
         assert_eq!(errors.len(), 0, "{}:\n{:#?}", path.display(), errors);
     }
     assert!(parsed > 0, "Parsed 0 files");
 }
+
+#[test]
+fn test_spans() {
+    let source = std::fs::read_to_string("../../examples/hello_world.st").unwrap();
+    let tokens = tokenize(&source).map(Result::unwrap).collect::<Vec<_>>();
+
+    let text = tokens
+        .iter()
+        .map(|token| &source[token.span.range()])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        text,
+        vec![
+            "func",
+            "main",
+            "(",
+            ")",
+            "(",
+            ")",
+            "{",
+            "println",
+            "\"Hello world\nthis is a multiline str\"",
+            ";",
+            "(",
+            ")",
+            "}"
+        ]
+    );
+
+    let string = &tokens[8].span;
+    assert_eq!((string.start.row, string.start.column), (2, 13));
+    assert_eq!((string.end.row, string.end.column), (3, 25));
+}
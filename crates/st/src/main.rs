@@ -1,6 +1,17 @@
 use argster::command;
-use st_core::tokenizer::tokenize;
-use std::path::PathBuf;
+use st_core::{
+    checker::check,
+    diagnostic::Diagnostic,
+    error::ProjectError,
+    interpreter::Interpreter,
+    project::{parse_file, Project},
+    resolver::{resolve, SourceModule},
+};
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::exit,
+};
 
 struct App;
 
@@ -11,24 +22,90 @@ impl App {
     /// input The path to the source file
     /// --project -p Use the provided source file a project manifest
     fn run(input: PathBuf, project: bool) {
-        _ = project;
+        if project {
+            return run_project(&input);
+        }
+
         let path = input;
-        let file = std::fs::read_to_string(&path).expect("Could not read file");
-        let mut was_tokenize_error = false;
-
-        let _tokenizer = tokenize(&file)
-            .filter(|x| x.is_ok())
-            .filter_map(|i| match i {
-                Ok(val) => Some(val),
-                Err(ex) => {
-                    eprintln!("{}:{ex}", path.display());
-                    was_tokenize_error = true;
-                    None
-                }
-            });
+        let module = match parse_file(&path) {
+            Ok(module) => SourceModule::from_file(path.clone(), module),
+            Err(ex) => fail(&ex),
+        };
+
+        let mut program = match resolve(vec![module]) {
+            Ok(program) => program,
+            Err(ex) => fail(&ex),
+        };
+
+        match check(&mut program) {
+            Ok(warnings) => warnings
+                .iter()
+                .for_each(|warning| emit(&Diagnostic::from_warning(warning))),
+            Err(ex) => fail(&ex),
+        }
+
+        let result = Interpreter::new(&program).with_args(program_args()).run();
+        if let Err(ex) = result {
+            emit(&Diagnostic::from_runtime_error(&path, &ex));
+            exit(1);
+        }
+    }
+}
+
+fn run_project(path: &Path) {
+    let project = match Project::load(path) {
+        Ok(project) => project,
+        Err(ex) => fail(&ex),
+    };
+
+    let program = match project.program() {
+        Ok((program, warnings)) => {
+            for warning in project.warnings.iter().chain(&warnings) {
+                emit(&Diagnostic::from_warning(warning));
+            }
+            program
+        }
+        Err(ex) => fail(&ex),
+    };
+
+    if let Err(ex) = project.run(&program, program_args()) {
+        fail(&ex);
     }
 }
 
+/// The arguments after `--`, which are passed on to the program being run
+fn program_args() -> Vec<String> {
+    std::env::args()
+        .skip_while(|arg| arg != "--")
+        .skip(1)
+        .collect()
+}
+
+/// Prints every error in `error` and exits
+fn fail(error: &ProjectError) -> ! {
+    for diagnostic in Diagnostic::from_project_error(error) {
+        emit(&diagnostic);
+    }
+    exit(1);
+}
+
+/// Prints a diagnostic to stderr, with colour if it is a terminal and `NO_COLOR` is not set
+fn emit(diagnostic: &Diagnostic) {
+    let source = std::fs::read_to_string(&diagnostic.path).ok();
+    let colour = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    eprintln!("{}", diagnostic.render(source.as_deref(), colour));
+}
+
+/// The stack size of the thread programs run on, large enough for
+/// [st_core::interpreter::MAX_CALL_DEPTH] nested calls
+const STACK_SIZE: usize = 1 << 30;
+
 fn main() {
-    App::main();
+    let app = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(App::main)
+        .expect("Could not start the interpreter thread");
+    if app.join().is_err() {
+        exit(101);
+    }
 }